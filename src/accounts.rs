@@ -0,0 +1,142 @@
+use std::{fs, path::PathBuf};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::{main::LoginInformation, oauth::StoredOAuthSession},
+    defs::bsky::actor::defs::ProfileViewDetailed,
+};
+
+const KEYRING_SERVICE: &str = "com.headassbtw.metro.bluesky";
+
+/// Enough about an account to show it in the switcher without logging in to it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedAccount {
+    pub did: String,
+    pub handle: String,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+}
+
+impl SavedAccount {
+    pub fn name(&self) -> &str {
+        self.display_name.as_deref().filter(|name| !name.is_empty()).unwrap_or(&self.handle)
+    }
+}
+
+/// Every account we've got a session for, and which one to log in as on startup.
+/// Only the bridge writes this, the frontend reads it when it needs the list.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Accounts {
+    pub active: Option<String>,
+    pub accounts: Vec<SavedAccount>,
+}
+
+/// What goes in the keyring for each account, under its DID
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StoredSession {
+    pub refresh_token: String,
+    pub oauth: Option<StoredOAuthSession>,
+}
+
+fn accounts_path() -> Option<PathBuf> {
+    let proj_dirs = ProjectDirs::from("dev", "headassbtw", "com.headassbtw.metro.bluesky")?;
+    let dir = proj_dirs.data_local_dir();
+    if !dir.exists()
+        && let Err(err) = fs::create_dir_all(dir)
+    {
+        println!("Could not create data folder: {}", err);
+        return None;
+    }
+    Some(dir.join("accounts.json"))
+}
+
+impl Accounts {
+    pub fn load() -> Self {
+        let Some(path) = accounts_path() else { return Self::default() };
+        fs::read_to_string(path).ok().and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let Some(path) = accounts_path() else { return };
+        let res = serde_json::to_string(self).map_err(|err| err.to_string()).and_then(|json| fs::write(path, json).map_err(|err| err.to_string()));
+        if let Err(err) = res {
+            println!("Failed to save accounts: {}", err);
+        }
+    }
+
+    pub fn get(&self, did: &str) -> Option<&SavedAccount> {
+        self.accounts.iter().find(|account| account.did == did)
+    }
+
+    /// Adds or updates an account we just logged in as, and makes it the one to start up with
+    pub fn remember(&mut self, did: &str, profile: Option<&ProfileViewDetailed>) {
+        let idx = match self.accounts.iter().position(|account| account.did == did) {
+            Some(idx) => idx,
+            None => {
+                self.accounts.push(SavedAccount { did: did.to_owned(), handle: did.to_owned(), display_name: None, avatar: None });
+                self.accounts.len() - 1
+            }
+        };
+        if let Some(profile) = profile {
+            let account = &mut self.accounts[idx];
+            account.handle = profile.handle.clone();
+            account.display_name = profile.display_name.clone();
+            account.avatar = profile.avatar.clone();
+        }
+        self.active = Some(did.to_owned());
+    }
+
+    pub fn forget(&mut self, did: &str) {
+        self.accounts.retain(|account| account.did != did);
+        if self.active.as_deref() == Some(did) {
+            self.active = None;
+        }
+    }
+}
+
+fn session_entry(did: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, did)
+}
+
+pub fn load_session(did: &str) -> keyring::Result<Option<StoredSession>> {
+    match session_entry(did)?.get_password() {
+        Ok(json) => Ok(serde_json::from_str(&json).ok()),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn save_session(login: &LoginInformation) -> keyring::Result<()> {
+    let session = StoredSession { refresh_token: login.refresh_token.clone(), oauth: login.oauth.clone() };
+    let json = serde_json::to_string(&session).map_err(|err| keyring::Error::PlatformFailure(Box::new(err)))?;
+    session_entry(&login.did)?.set_password(&json)
+}
+
+pub fn delete_session(did: &str) -> keyring::Result<()> {
+    match session_entry(did)?.delete_password() {
+        Err(keyring::Error::NoEntry) => Ok(()),
+        result => result,
+    }
+}
+
+/// The one session we kept before there could be more than one, if it's still around
+pub fn legacy_session() -> keyring::Result<Option<StoredSession>> {
+    let refresh_token = match keyring::Entry::new(KEYRING_SERVICE, "refreshJwt")?.get_password() {
+        Ok(token) => token,
+        Err(keyring::Error::NoEntry) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let oauth = keyring::Entry::new(KEYRING_SERVICE, "oauthSession")?.get_password().ok().and_then(|json| serde_json::from_str(&json).ok());
+    Ok(Some(StoredSession { refresh_token, oauth }))
+}
+
+/// Once the legacy session has moved to its DID's entry
+pub fn delete_legacy_session() {
+    for user in ["refreshJwt", "oauthSession"] {
+        if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, user) {
+            let _ = entry.delete_password();
+        }
+    }
+}
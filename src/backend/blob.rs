@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
 
 use crate::defs;
 
@@ -10,9 +12,27 @@ pub struct Response {
     pub blob: defs::Blob,
 }
 
+/// An image picked in the composer, waiting to be uploaded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageAttachment {
+    pub path: PathBuf,
+    /// Empty if the user didn't write any
+    pub alt: String,
+}
+
+/// A video picked in the composer, waiting to be uploaded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoAttachment {
+    pub path: PathBuf,
+    /// Empty if the user didn't write any
+    pub alt: String,
+    /// Language code and path of each WebVTT caption file
+    pub captions: Vec<(String, PathBuf)>,
+}
+
 impl ClientBackend {
-    pub async fn upload_blob(&mut self, buffer: Vec<u8>) -> Result<defs::Blob, BlueskyApiError> {
-        let req = self.client.post(format!("{}/xrpc/com.atproto.repo.uploadBlob", self.user_pds)).body(buffer).header("content-type", "image/*");
+    pub async fn upload_blob(&mut self, buffer: Vec<u8>, mime_type: &str) -> Result<defs::Blob, BlueskyApiError> {
+        let req = self.client.post(format!("{}/xrpc/com.atproto.repo.uploadBlob", self.user_pds)).body(buffer).header("content-type", mime_type);
         let req = self.make_request(req).await?;
 
         let res: Result<Response, serde_json::Error> = serde_json::from_str(&req);
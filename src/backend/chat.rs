@@ -0,0 +1,337 @@
+use std::sync::{Arc, Mutex};
+
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    defs::chat::convo::defs::{ConvoView, LogVariant, MessageInput, MessageVariant, MessageView},
+    frontend::CursorListPair,
+};
+
+use super::{record::link_detector, BlueskyApiError, ClientBackend};
+
+/// Chat isn't served by the PDS itself, every chat.bsky call has to be proxied through to this service
+const CHAT_PROXY: &str = "did:web:api.bsky.chat#bsky_chat";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListConvosResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    pub convos: Vec<ConvoView>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvoResponse {
+    pub convo: ConvoView,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMessagesResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Newest first
+    pub messages: Vec<MessageVariant>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLogResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    pub logs: Vec<LogVariant>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SendMessageRequest {
+    pub convo_id: String,
+    pub message: MessageInput,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConvoIdRequest {
+    pub convo_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+}
+
+/// A conversation and whatever we've loaded of it, shared between the backend and any view showing it.
+pub struct Conversation {
+    pub convo: ConvoView,
+    /// For older messages, same deal as CursorListPair
+    pub cursor: Option<String>,
+    /// Oldest first, so it can be drawn top to bottom
+    pub messages: Vec<MessageVariant>,
+    /// Kept here so it survives backing out of the chat, and so a failed send doesn't eat it
+    pub draft: String,
+    pub sending: bool,
+    /// Set once we've left the conversation, views should stop showing it
+    pub left: bool,
+}
+
+impl Conversation {
+    /// Adds a message to the end, unless we already have it (our own messages come back through the log too)
+    pub fn push_message(&mut self, message: MessageVariant) {
+        if let Some(id) = message.id()
+            && self.messages.iter().rev().any(|existing| existing.id() == Some(id))
+        {
+            return;
+        }
+        self.convo.last_message = Some(message.clone());
+        self.messages.push(message);
+    }
+}
+
+impl ClientBackend {
+    fn chat_request(&self, request: RequestBuilder) -> RequestBuilder {
+        request.header("atproto-proxy", CHAT_PROXY)
+    }
+
+    async fn chat_procedure<T: Serialize>(&mut self, nsid: &str, body: &T) -> Result<String, BlueskyApiError> {
+        let body = serde_json::to_string(body);
+        if let Err(err) = body {
+            return Err(BlueskyApiError::ParseError(err, String::new()));
+        }
+
+        let req = self.client.post(format!("{}/xrpc/{}", self.user_pds, nsid)).body(body.unwrap()).header("content-type", "application/json");
+        let req = self.chat_request(req);
+        self.make_request(req).await
+    }
+
+    /// takes a convo view, and returns the shared conversation for it, updating the cached one if it exists.
+    pub fn deduplicate_convo(&mut self, convo: ConvoView) -> Arc<Mutex<Conversation>> {
+        if let Some(cached) = self.convo_cache.get(&convo.id) {
+            let mut conversation = cached.lock().unwrap();
+            // the cached one might have newer messages from the log than this view knows about
+            if conversation.convo.rev > convo.rev {
+                return cached.clone();
+            }
+            conversation.convo = convo;
+            drop(conversation);
+            cached.clone()
+        } else {
+            let id = convo.id.clone();
+            let conversation = Arc::new(Mutex::new(Conversation { convo, cursor: Some(String::new()), messages: Vec::new(), draft: String::new(), sending: false, left: false }));
+            self.convo_cache.insert(id, conversation.clone());
+            conversation
+        }
+    }
+
+    pub async fn list_convos(&mut self, cursor: String) -> Result<(Option<String>, Vec<Arc<Mutex<Conversation>>>), BlueskyApiError> {
+        let cursor = if cursor.is_empty() { String::new() } else { format!("&cursor={}", cursor) };
+        let req = self.client.get(format!("{}/xrpc/chat.bsky.convo.listConvos?limit=50{}", self.user_pds, cursor));
+        let req = self.chat_request(req);
+        let req = self.make_request(req).await?;
+
+        let res: Result<ListConvosResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+        let res = res.unwrap();
+
+        let mut rtn = Vec::new();
+        for convo in res.convos {
+            rtn.push(self.deduplicate_convo(convo));
+        }
+
+        Ok((res.cursor, rtn))
+    }
+
+    pub async fn get_convo(&mut self, convo_id: &str) -> Result<Arc<Mutex<Conversation>>, BlueskyApiError> {
+        let req = self.client.get(format!("{}/xrpc/chat.bsky.convo.getConvo?convoId={}", self.user_pds, convo_id));
+        let req = self.chat_request(req);
+        let req = self.make_request(req).await?;
+
+        let res: Result<ConvoResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        Ok(self.deduplicate_convo(res.unwrap().convo))
+    }
+
+    /// Gets the conversation with another user, creating it if there isn't one yet.
+    pub async fn get_convo_for_member(&mut self, did: &str) -> Result<Arc<Mutex<Conversation>>, BlueskyApiError> {
+        let req = self.client.get(format!("{}/xrpc/chat.bsky.convo.getConvoForMembers?members={}", self.user_pds, did));
+        let req = self.chat_request(req);
+        let req = self.make_request(req).await?;
+
+        let res: Result<ConvoResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        Ok(self.deduplicate_convo(res.unwrap().convo))
+    }
+
+    /// Loads the page of messages before the conversation's cursor, and puts them in front of what's there.
+    pub async fn get_messages(&mut self, conversation: &Arc<Mutex<Conversation>>, cursor: String) -> Result<(), BlueskyApiError> {
+        let id = conversation.lock().unwrap().convo.id.clone();
+        let cursor = if cursor.is_empty() { String::new() } else { format!("&cursor={}", cursor) };
+        let req = self.client.get(format!("{}/xrpc/chat.bsky.convo.getMessages?convoId={}&limit=50{}", self.user_pds, id, cursor));
+        let req = self.chat_request(req);
+        let req = self.make_request(req).await?;
+
+        let res: Result<GetMessagesResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+        let res = res.unwrap();
+
+        let mut conversation = conversation.lock().unwrap();
+        conversation.cursor = res.cursor;
+        let mut older: Vec<MessageVariant> = res.messages.into_iter().rev().filter(|message| !conversation.messages.iter().any(|existing| existing.id() == message.id())).collect();
+        older.append(&mut conversation.messages);
+        conversation.messages = older;
+
+        Ok(())
+    }
+
+    pub async fn send_message(&mut self, conversation: &Arc<Mutex<Conversation>>, text: String) -> Result<(), BlueskyApiError> {
+        let convo_id = conversation.lock().unwrap().convo.id.clone();
+        let facets = link_detector(text.clone());
+        let message = MessageInput { text, facets: if facets.is_empty() { None } else { Some(facets) }, embed: None };
+
+        let req = self.chat_procedure("chat.bsky.convo.sendMessage", &SendMessageRequest { convo_id, message }).await?;
+
+        let res: Result<MessageView, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        conversation.lock().unwrap().push_message(MessageVariant::Message(res.unwrap()));
+        Ok(())
+    }
+
+    /// Marks everything up to the newest message (or a specific one) as read.
+    pub async fn update_read(&mut self, conversation: &Arc<Mutex<Conversation>>, message_id: Option<String>) -> Result<(), BlueskyApiError> {
+        let convo_id = conversation.lock().unwrap().convo.id.clone();
+        let req = self.chat_procedure("chat.bsky.convo.updateRead", &ConvoIdRequest { convo_id, message_id }).await?;
+
+        let res: Result<ConvoResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        conversation.lock().unwrap().convo = res.unwrap().convo;
+        Ok(())
+    }
+
+    pub async fn mute_convo(&mut self, conversation: &Arc<Mutex<Conversation>>, mute: bool) -> Result<(), BlueskyApiError> {
+        let convo_id = conversation.lock().unwrap().convo.id.clone();
+        let nsid = if mute { "chat.bsky.convo.muteConvo" } else { "chat.bsky.convo.unmuteConvo" };
+        let req = self.chat_procedure(nsid, &ConvoIdRequest { convo_id, message_id: None }).await?;
+
+        let res: Result<ConvoResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        conversation.lock().unwrap().convo = res.unwrap().convo;
+        Ok(())
+    }
+
+    pub async fn leave_convo(&mut self, conversation: &Arc<Mutex<Conversation>>) -> Result<(), BlueskyApiError> {
+        let convo_id = conversation.lock().unwrap().convo.id.clone();
+        self.chat_procedure("chat.bsky.convo.leaveConvo", &ConvoIdRequest { convo_id: convo_id.clone(), message_id: None }).await?;
+
+        conversation.lock().unwrap().left = true;
+        self.convo_cache.remove(&convo_id);
+        Ok(())
+    }
+
+    /// Polls the chat event log and applies whatever happened to the cached conversations.
+    /// New conversations get put at the top of `convos` if there is one.
+    pub(crate) async fn poll_chat_log(&mut self, convos: Option<Arc<Mutex<CursorListPair<Mutex<Conversation>>>>>) -> Result<(), BlueskyApiError> {
+        // the first poll just gets us a cursor, anything before it came in with the initial loads
+        let cursor = match &self.chat_log_cursor {
+            Some(cursor) => format!("?cursor={}", cursor),
+            None => String::new(),
+        };
+        let req = self.client.get(format!("{}/xrpc/chat.bsky.convo.getLog{}", self.user_pds, cursor));
+        let req = self.chat_request(req);
+        let req = self.make_request(req).await?;
+
+        let res: Result<GetLogResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+        let res = res.unwrap();
+
+        let first_poll = self.chat_log_cursor.is_none();
+        if let Some(cursor) = res.cursor {
+            self.chat_log_cursor = Some(cursor);
+        }
+        if first_poll {
+            return Ok(());
+        }
+
+        for log in res.logs {
+            match log {
+                LogVariant::BeginConvo(log) => {
+                    if self.convo_cache.contains_key(&log.convo_id) {
+                        continue;
+                    }
+                    let conversation = self.get_convo(&log.convo_id).await?;
+                    if let Some(convos) = &convos {
+                        convos.lock().unwrap().items.insert(0, conversation);
+                    }
+                }
+                LogVariant::LeaveConvo(log) => {
+                    if let Some(conversation) = self.convo_cache.remove(&log.convo_id) {
+                        conversation.lock().unwrap().left = true;
+                    }
+                }
+                LogVariant::MuteConvo(log) | LogVariant::UnmuteConvo(log) | LogVariant::AcceptConvo(log) => {
+                    // cheaper to just refetch than to figure out what changed
+                    if self.convo_cache.contains_key(&log.convo_id) {
+                        self.get_convo(&log.convo_id).await?;
+                    }
+                }
+                LogVariant::CreateMessage(log) => {
+                    let conversation = match self.convo_cache.get(&log.convo_id) {
+                        Some(conversation) => conversation.clone(),
+                        None => {
+                            let conversation = self.get_convo(&log.convo_id).await?;
+                            if let Some(convos) = &convos {
+                                convos.lock().unwrap().items.insert(0, conversation.clone());
+                            }
+                            conversation
+                        }
+                    };
+                    let mut conversation = conversation.lock().unwrap();
+                    let theirs = log.message.sender().is_some_and(|sender| sender != self.did);
+                    let had = conversation.messages.len();
+                    conversation.push_message(log.message);
+                    if theirs && conversation.messages.len() > had {
+                        conversation.convo.unread_count += 1;
+                    }
+                    conversation.convo.rev = log.rev;
+                }
+                LogVariant::DeleteMessage(log) => {
+                    if let Some(conversation) = self.convo_cache.get(&log.convo_id) {
+                        let mut conversation = conversation.lock().unwrap();
+                        if let Some(existing) = conversation.messages.iter_mut().find(|existing| existing.id() == log.message.id()) {
+                            *existing = log.message;
+                        }
+                    }
+                }
+                LogVariant::ReadMessage(_) | LogVariant::Unknown => {}
+            }
+        }
+
+        if let Some(convos) = &convos {
+            // most recent activity at the top, like everywhere else
+            convos.lock().unwrap().items.sort_by_key(|conversation| {
+                let conversation = conversation.lock().unwrap();
+                std::cmp::Reverse(conversation.convo.rev.clone())
+            });
+        }
+
+        Ok(())
+    }
+}
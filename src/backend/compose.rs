@@ -0,0 +1,232 @@
+use std::sync::{Arc, Mutex};
+
+use image::GenericImageView;
+
+use crate::defs::bsky::{
+    embed::{
+        self,
+        images::Image,
+        record_with_media::{MediaRawVariant, RecordWithMediaRaw},
+        video, AspectRatio,
+    },
+    feed::{self, ReplyRef, StrongRef},
+};
+
+use super::{
+    blob::{ImageAttachment, VideoAttachment},
+    gates::InteractionSettings,
+    link_card::LinkCard,
+    record::BlueskyApiRecord,
+    video::{mp4_aspect_ratio, VideoProgress},
+    BlueskyApiError, BlueskyApiErrorMessage, ClientBackend,
+};
+
+/// What goes in a post's embed slot. A quote is already in the record, these get put alongside it.
+#[derive(Debug, Clone)]
+pub enum PostMedia {
+    None,
+    Images(Vec<ImageAttachment>),
+    /// Progress gets written into the arc while the video service chews on it
+    Video(VideoAttachment, Arc<Mutex<VideoProgress>>),
+    /// The card's thumbnail gets uploaded first
+    LinkCard(LinkCard),
+}
+
+/// How far a thread got. Everything after the first `sent` posts never went out.
+#[derive(Debug)]
+pub struct ThreadCreationResult {
+    pub sent: usize,
+    /// Where the rest of the thread would hang off, so sending again carries on from the last post that made it
+    pub reply: Option<ReplyRef>,
+    pub error: Option<BlueskyApiError>,
+    /// A post that went out, but its threadgate or postgate didn't. Sending again tries those first.
+    pub ungated: Option<String>,
+    /// The rest of the thread, if it stopped at a video the service is still processing
+    pub pending: Option<PendingThread>,
+}
+
+/// What's left of a thread while its video gets processed, it all gets sent as is once that's done
+#[derive(Debug)]
+pub struct PendingThread {
+    pub posts: Vec<(feed::Post, PostMedia)>,
+    pub interaction: InteractionSettings,
+    pub progress: Arc<Mutex<VideoProgress>>,
+}
+
+fn file_error(err: std::io::Error) -> BlueskyApiError {
+    BlueskyApiError::BadRequest(BlueskyApiErrorMessage { error: "FileError".to_owned(), message: err.to_string() })
+}
+
+/// Puts media in a post's embed, next to the quote if there is one
+fn embed_media(post: &mut feed::Post, media: MediaRawVariant) {
+    post.embed = Some(
+        match post.embed.as_deref() {
+            Some(embed::Variant::RecordRaw(quote)) => embed::Variant::RecordWithMediaRaw(RecordWithMediaRaw { record: quote.clone(), media }),
+            _ => match media {
+                MediaRawVariant::Images { images } => embed::Variant::ImagesRaw { images },
+                MediaRawVariant::Video(video) => embed::Variant::VideoRaw(video),
+                MediaRawVariant::External { external } => embed::Variant::ExternalRaw { external },
+            },
+        }
+        .into(),
+    );
+}
+
+/// Only the root gets a threadgate, the postgate goes on everything
+fn post_settings(interaction: &InteractionSettings, is_root: bool) -> InteractionSettings {
+    if is_root { interaction.clone() } else { InteractionSettings { reply_allow: None, quotes_disabled: interaction.quotes_disabled } }
+}
+
+impl ClientBackend {
+    async fn upload_images(&mut self, images: Vec<ImageAttachment>) -> Result<Vec<Image>, BlueskyApiError> {
+        let mut rtn = Vec::new();
+        for ImageAttachment { path, alt } in images {
+            let buffer = std::fs::read(&path).map_err(file_error)?;
+
+            let aspect_ratio = match image::load_from_memory(&buffer) {
+                Ok(img) => Some(AspectRatio { width: img.dimensions().0, height: img.dimensions().1 }),
+                Err(err) => {
+                    println!("failed to open image: {}", err);
+                    None
+                }
+            };
+
+            let blob = self.upload_blob(buffer, "image/*").await?;
+            rtn.push(Image { image: blob, alt, aspect_ratio });
+        }
+        Ok(rtn)
+    }
+
+    /// Hands the video to the service, the job ends up in `progress`. It might already be done if the service has seen it before.
+    async fn start_video_upload(&mut self, video: &VideoAttachment, progress: &Arc<Mutex<VideoProgress>>) -> Result<(), BlueskyApiError> {
+        let buffer = std::fs::read(&video.path).map_err(file_error)?;
+        let extension = video.path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let mime_type = match extension.as_str() {
+            "mov" => "video/quicktime",
+            "webm" => "video/webm",
+            "mpeg" | "mpg" => "video/mpeg",
+            _ => "video/mp4",
+        };
+        let aspect_ratio = if mime_type == "video/mp4" || mime_type == "video/quicktime" { mp4_aspect_ratio(&buffer) } else { None };
+        let name = video.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_else(|| "video.mp4".to_owned());
+
+        let job = self.upload_video(buffer, &name, mime_type).await?;
+        let mut progress = progress.lock().unwrap();
+        progress.aspect_ratio = aspect_ratio;
+        progress.update(job);
+        Ok(())
+    }
+
+    /// Once the service is done with the video
+    async fn upload_video_embed(&mut self, video: VideoAttachment, progress: &Arc<Mutex<VideoProgress>>) -> Result<video::Video, BlueskyApiError> {
+        let (blob, aspect_ratio) = {
+            let progress = progress.lock().unwrap();
+            (progress.blob().cloned(), progress.aspect_ratio.clone())
+        };
+        let Some(blob) = blob else {
+            return Err(BlueskyApiError::BadRequest(BlueskyApiErrorMessage { error: "VideoProcessingFailed".to_owned(), message: "The video finished processing, but never showed up".to_owned() }));
+        };
+
+        progress.lock().unwrap().state = "Posting".to_owned();
+        let mut captions = Vec::new();
+        for (lang, path) in video.captions {
+            let buffer = std::fs::read(&path).map_err(file_error)?;
+            let file = self.upload_blob(buffer, "text/vtt").await?;
+            captions.push(video::Caption { lang, file });
+        }
+
+        Ok(video::Video {
+            video: blob,
+            captions: if captions.is_empty() { None } else { Some(captions) },
+            alt: if video.alt.is_empty() { None } else { Some(video.alt) },
+            aspect_ratio,
+        })
+    }
+
+    /// Uploads whatever's attached to a post and puts it in the embed
+    pub async fn attach_media(&mut self, post: &mut feed::Post, media: PostMedia) -> Result<(), BlueskyApiError> {
+        match media {
+            PostMedia::None => {}
+            PostMedia::Images(images) => {
+                let images = self.upload_images(images).await?;
+                embed_media(post, MediaRawVariant::Images { images });
+            }
+            PostMedia::Video(video, progress) => {
+                let video = self.upload_video_embed(video, &progress).await?;
+                embed_media(post, MediaRawVariant::Video(video));
+            }
+            PostMedia::LinkCard(card) => {
+                let external = self.link_card_embed(card).await;
+                embed_media(post, MediaRawVariant::External { external });
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends posts in order, each one replying to the one before it. The first one keeps whatever reply it already has.
+    /// Stops at the first thing that goes wrong. The threadgate goes on the thread's root, if we're the ones starting it, and the postgate goes on everything.
+    /// Also stops at a video that isn't processed yet, the rest is left in `pending` for whoever waits on it.
+    /// `ungated` is a post from last time whose settings didn't get written, they go on before anything else.
+    pub async fn create_thread(&mut self, posts: Vec<(feed::Post, PostMedia)>, interaction: &InteractionSettings, ungated: Option<String>) -> ThreadCreationResult {
+        let mut rtn = ThreadCreationResult { sent: 0, reply: None, error: None, ungated: None, pending: None };
+
+        if let Some(uri) = ungated {
+            let is_root = posts.first().and_then(|(post, _)| post.reply.as_ref()).is_none_or(|reply| reply.root.uri == uri);
+            if let Err(err) = self.set_interaction_settings(&uri, &post_settings(interaction, is_root)).await {
+                rtn.error = Some(err);
+                rtn.ungated = Some(uri);
+                return rtn;
+            }
+        }
+
+        let mut posts = posts.into_iter();
+        while let Some((mut post, media)) = posts.next() {
+            if let Some(reply) = &rtn.reply {
+                post.reply = Some(reply.clone());
+            }
+            let is_root = post.reply.is_none();
+            let root = post.reply.as_ref().map(|reply| reply.root.clone());
+
+            if let PostMedia::Video(video, progress) = &media {
+                let uploaded = progress.lock().unwrap().job.is_some();
+                if !uploaded && let Err(err) = self.start_video_upload(video, progress).await {
+                    rtn.error = Some(err);
+                    return rtn;
+                }
+                let processed = progress.lock().unwrap().processed();
+                if !processed {
+                    let progress = progress.clone();
+                    rtn.pending = Some(PendingThread { posts: std::iter::once((post, media)).chain(posts).collect(), interaction: interaction.clone(), progress });
+                    return rtn;
+                }
+            }
+
+            if let Err(err) = self.attach_media(&mut post, media).await {
+                rtn.error = Some(err);
+                return rtn;
+            }
+            let res = match self.create_record(BlueskyApiRecord::Post(post)).await {
+                Ok(res) => res,
+                Err(err) => {
+                    rtn.error = Some(err);
+                    return rtn;
+                }
+            };
+            rtn.sent += 1;
+
+            let parent = StrongRef { uri: res.uri.clone(), cid: res.cid };
+            rtn.reply = Some(ReplyRef { root: root.unwrap_or(parent.clone()), parent });
+
+            let settings = post_settings(interaction, is_root);
+            if !settings.is_default()
+                && let Err(err) = self.set_interaction_settings(&res.uri, &settings).await
+            {
+                rtn.error = Some(err);
+                rtn.ungated = Some(res.uri);
+                return rtn;
+            }
+        }
+
+        rtn
+    }
+}
@@ -1,8 +1,10 @@
+use std::sync::{Arc, Mutex};
+
 use serde::Deserialize;
 
 use super::{BlueskyApiError, ClientBackend};
 
-use crate::defs::bsky::feed::defs::{GeneratorView, FeedCursorPair};
+use crate::defs::bsky::{actor::defs::ProfileView, feed::defs::{GeneratorView, FeedCursorPair, FeedViewPost, PostView}};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +20,42 @@ pub struct ActorFeedsResponse {
 	pub feeds: Vec<GeneratorView>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPostsResponse {
+	pub posts: Vec<Arc<Mutex<PostView>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Like {
+	pub actor: Arc<ProfileView>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLikesResponse {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub cursor: Option<String>,
+	pub likes: Vec<Like>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRepostedByResponse {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub cursor: Option<String>,
+	pub reposted_by: Vec<Arc<ProfileView>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetQuotesResponse {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub cursor: Option<String>,
+	pub posts: Vec<Arc<Mutex<PostView>>>,
+}
+
 impl ActorFeedsResponse {
 	pub fn none() -> Self {
 		ActorFeedsResponse {
@@ -82,4 +120,84 @@ impl ClientBackend {
 
         Ok(res.unwrap().feeds)
     }
-}
\ No newline at end of file
+
+	/// Gets post views for a specified list of posts (by AT-URI). Goes 25 at a time, so any amount is fine.
+	pub async fn get_posts(&mut self, uris: Vec<String>) -> Result<Vec<Arc<Mutex<PostView>>>, BlueskyApiError> {
+		let mut rtn = Vec::new();
+		for chunk in uris.chunks(25) {
+			let query: Vec<(&str, &String)> = chunk.iter().map(|uri| ("uris", uri)).collect();
+			let req = self.client.get(format!("{}/xrpc/app.bsky.feed.getPosts", self.user_pds)).query(&query);
+			let req = self.make_request(req).await?;
+
+			let res: Result<GetPostsResponse, serde_json::Error> = serde_json::from_str(&req);
+			if let Err(err) = res {
+				return Err(BlueskyApiError::ParseError(err, req));
+			}
+
+			for mut post in res.unwrap().posts {
+				rtn.push(self.deduplicate_post(&mut post));
+			}
+		}
+
+		Ok(rtn)
+	}
+
+	/// Get like records which reference a subject (by AT-URI), as the accounts that liked it. A None cursor means there's no more.
+	pub async fn get_likes(&mut self, uri: String, cursor: String) -> Result<(Option<String>, Vec<Arc<ProfileView>>), BlueskyApiError> {
+		let mut query = vec![("uri", uri)];
+		if !cursor.is_empty() {
+			query.push(("cursor", cursor));
+		}
+		let req = self.client.get(format!("{}/xrpc/app.bsky.feed.getLikes", self.user_pds)).query(&query);
+		let req = self.make_request(req).await?;
+
+		let res: Result<GetLikesResponse, serde_json::Error> = serde_json::from_str(&req);
+		if let Err(err) = res {
+			return Err(BlueskyApiError::ParseError(err, req));
+		}
+		let res = res.unwrap();
+
+		Ok((res.cursor, res.likes.into_iter().map(|like| like.actor).collect()))
+	}
+
+	/// Get a list of reposts for a given post. A None cursor means there's no more.
+	pub async fn get_reposted_by(&mut self, uri: String, cursor: String) -> Result<(Option<String>, Vec<Arc<ProfileView>>), BlueskyApiError> {
+		let mut query = vec![("uri", uri)];
+		if !cursor.is_empty() {
+			query.push(("cursor", cursor));
+		}
+		let req = self.client.get(format!("{}/xrpc/app.bsky.feed.getRepostedBy", self.user_pds)).query(&query);
+		let req = self.make_request(req).await?;
+
+		let res: Result<GetRepostedByResponse, serde_json::Error> = serde_json::from_str(&req);
+		if let Err(err) = res {
+			return Err(BlueskyApiError::ParseError(err, req));
+		}
+		let res = res.unwrap();
+
+		Ok((res.cursor, res.reposted_by))
+	}
+
+	/// Get a list of quotes for a given post. Comes back as a feed so it can be shown like any other.
+	pub async fn get_quotes(&mut self, uri: String, cursor: String) -> Result<FeedCursorPair, BlueskyApiError> {
+		let mut query = vec![("uri", uri)];
+		if !cursor.is_empty() {
+			query.push(("cursor", cursor));
+		}
+		let req = self.client.get(format!("{}/xrpc/app.bsky.feed.getQuotes", self.user_pds)).query(&query);
+		let req = self.make_request(req).await?;
+
+		let res: Result<GetQuotesResponse, serde_json::Error> = serde_json::from_str(&req);
+		if let Err(err) = res {
+			return Err(BlueskyApiError::ParseError(err, req));
+		}
+		let res = res.unwrap();
+
+		let mut feed = Vec::new();
+		for mut post in res.posts {
+			feed.push(FeedViewPost { post: self.deduplicate_post(&mut post), reply: None, reason: None, feed_context: None });
+		}
+
+		Ok(FeedCursorPair { cursor: res.cursor, feed })
+	}
+}
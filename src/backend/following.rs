@@ -13,23 +13,50 @@ pub struct GetFollowersResponse {
 	pub cursor: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct GetFollowsResponse {
+	pub subject: Arc<ProfileView>,
+	pub follows: Vec<Arc<ProfileView>>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub cursor: Option<String>,
+}
+
 impl ClientBackend {
-	pub async fn get_followers(&mut self, did: String, cursor: String) -> Result<(String, Vec<Arc<ProfileView>>), BlueskyApiError> {
-        let request = self.client.get(format!("{}/xrpc/app.bsky.graph.getFollowers?actor={}&cursor={}", self.user_pds, did, cursor));
-        let req = self.make_request(request).await?;
-
-        let parse: Result<GetFollowersResponse, serde_json::Error> = serde_json::from_str(&req);
-        if let Err(err) = parse {
-            return Err(BlueskyApiError::ParseError(err, req));
-        }
-        //TODO: de-duplication
-        let res = parse.unwrap();
-        /*
-        for post in res.followers.iter_mut() {
-            
-        };
-        */
-
-        return Ok((res.cursor.unwrap_or(String::new()), res.followers));
-    }
-}
\ No newline at end of file
+	/// Enumerates accounts which follow a specified account (actor). A None cursor means there's no more.
+	pub async fn get_followers(&mut self, did: String, cursor: String) -> Result<(Option<String>, Vec<Arc<ProfileView>>), BlueskyApiError> {
+		let mut query = vec![("actor", did)];
+		if !cursor.is_empty() {
+			query.push(("cursor", cursor));
+		}
+		let request = self.client.get(format!("{}/xrpc/app.bsky.graph.getFollowers", self.user_pds)).query(&query);
+		let req = self.make_request(request).await?;
+
+		let parse: Result<GetFollowersResponse, serde_json::Error> = serde_json::from_str(&req);
+		if let Err(err) = parse {
+			return Err(BlueskyApiError::ParseError(err, req));
+		}
+		//TODO: de-duplication
+		let res = parse.unwrap();
+
+		Ok((res.cursor, res.followers))
+	}
+
+	/// Enumerates accounts which a specified account (actor) follows. A None cursor means there's no more.
+	pub async fn get_follows(&mut self, did: String, cursor: String) -> Result<(Option<String>, Vec<Arc<ProfileView>>), BlueskyApiError> {
+		let mut query = vec![("actor", did)];
+		if !cursor.is_empty() {
+			query.push(("cursor", cursor));
+		}
+		let request = self.client.get(format!("{}/xrpc/app.bsky.graph.getFollows", self.user_pds)).query(&query);
+		let req = self.make_request(request).await?;
+
+		let parse: Result<GetFollowsResponse, serde_json::Error> = serde_json::from_str(&req);
+		if let Err(err) = parse {
+			return Err(BlueskyApiError::ParseError(err, req));
+		}
+		let res = parse.unwrap();
+
+		Ok((res.cursor, res.follows))
+	}
+}
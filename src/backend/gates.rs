@@ -0,0 +1,110 @@
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::defs::bsky::{
+    feed::{defs::PostView, PostGate, PostGateEmbeddingRule, ThreadGate, ThreatGateAllow},
+    graph::defs::{ListView, CURATELIST},
+};
+
+use super::{record::BlueskyApiRecord, BlueskyApiError, ClientBackend};
+
+/// Who can reply to and quote a post, what ends up in its threadgate and postgate
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InteractionSettings {
+    /// `None` lets anyone reply, an empty list lets nobody
+    pub reply_allow: Option<Vec<ThreatGateAllow>>,
+    pub quotes_disabled: bool,
+}
+
+impl InteractionSettings {
+    /// Who can reply to a post, as far as its view says. The view doesn't say whether quotes are off, that's in the postgate (see `get_quotes_disabled`).
+    pub fn from_post(post: &PostView) -> Self {
+        let reply_allow = post.threadgate.as_ref().and_then(|gate| gate.record.as_ref()).and_then(|gate| gate.allow.clone());
+        Self { reply_allow, quotes_disabled: false }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetListsResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    pub lists: Vec<ListView>,
+}
+
+impl ClientBackend {
+    /// Writes (or clears) the threadgate and postgate for one of our posts, both at the post's rkey.
+    /// Anything else in them, like hidden replies and detached quotes, is left alone. Gives back the threadgate as it now stands.
+    pub async fn set_interaction_settings(&mut self, post_uri: &str, settings: &InteractionSettings) -> Result<Option<ThreadGate>, BlueskyApiError> {
+        let rkey = post_uri.split("/").last().unwrap_or_default().to_owned();
+
+        let hidden_replies = match self.get_record("app.bsky.feed.threadgate", &rkey).await? {
+            Some(BlueskyApiRecord::ThreadGate(gate)) => gate.hidden_replies,
+            _ => None,
+        };
+        let threadgate = if settings.reply_allow.is_some() || hidden_replies.is_some() {
+            let gate = ThreadGate { post: post_uri.to_owned(), allow: settings.reply_allow.clone(), created_at: Utc::now(), hidden_replies };
+            self.put_record(BlueskyApiRecord::ThreadGate(gate.clone()), &rkey).await?;
+            Some(gate)
+        } else {
+            self.delete_record(rkey.clone(), "app.bsky.feed.threadgate".to_owned()).await?;
+            None
+        };
+
+        let detached_embedding_uris = match self.get_record("app.bsky.feed.postgate", &rkey).await? {
+            Some(BlueskyApiRecord::PostGate(gate)) => gate.detached_embedding_uris,
+            _ => None,
+        };
+        if settings.quotes_disabled || detached_embedding_uris.is_some() {
+            let embedding_rules = if settings.quotes_disabled { Some(vec![PostGateEmbeddingRule::Disable]) } else { None };
+            let gate = PostGate { created_at: Utc::now(), post: post_uri.to_owned(), detached_embedding_uris, embedding_rules };
+            self.put_record(BlueskyApiRecord::PostGate(gate), &rkey).await?;
+        } else {
+            self.delete_record(rkey, "app.bsky.feed.postgate".to_owned()).await?;
+        }
+
+        Ok(threadgate)
+    }
+
+    /// Whether one of our posts' postgate turns quotes off
+    pub async fn get_quotes_disabled(&mut self, post_uri: &str) -> Result<bool, BlueskyApiError> {
+        let rkey = post_uri.split("/").last().unwrap_or_default().to_owned();
+        Ok(match self.get_record("app.bsky.feed.postgate", &rkey).await? {
+            Some(BlueskyApiRecord::PostGate(gate)) => gate.embedding_rules.is_some_and(|rules| rules.contains(&PostGateEmbeddingRule::Disable)),
+            _ => false,
+        })
+    }
+
+    /// Our curation lists, the only kind a threadgate can point at
+    pub async fn get_own_lists(&mut self) -> Result<Vec<ListView>, BlueskyApiError> {
+        let mut rtn = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut query = vec![("actor", self.did.clone()), ("limit", "100".to_owned())];
+            if let Some(cursor) = cursor {
+                query.push(("cursor", cursor));
+            }
+            let req = self.client.get(format!("{}/xrpc/app.bsky.graph.getLists", self.user_pds)).query(&query);
+            let req = self.make_request(req).await?;
+
+            let res: Result<GetListsResponse, serde_json::Error> = serde_json::from_str(&req);
+            if let Err(err) = res {
+                return Err(BlueskyApiError::ParseError(err, req));
+            }
+            let res = res.unwrap();
+
+            rtn.extend(res.lists.into_iter().filter(|list| list.purpose == CURATELIST));
+            cursor = res.cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(rtn)
+    }
+}
@@ -0,0 +1,99 @@
+use hickory_resolver::{error::ResolveErrorKind, TokioAsyncResolver};
+
+use super::{login::DidDoc, BlueskyApiError, BlueskyApiErrorMessage, ClientBackend};
+
+const PLC_DIRECTORY: &str = "https://plc.directory";
+
+fn identity_error(error: &str, message: String) -> BlueskyApiError {
+    BlueskyApiError::BadRequest(BlueskyApiErrorMessage { error: error.to_owned(), message })
+}
+
+/// Whatever someone typed into the login box, `@` and all, into a bare handle
+fn normalize_handle(handle: &str) -> String {
+    handle.trim().trim_start_matches('@').to_lowercase()
+}
+
+/// Someone's hosting provider as typed, into a URL we can make requests against
+pub fn normalize_service_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    if url.starts_with("http://") || url.starts_with("https://") { url.to_owned() } else { format!("https://{}", url) }
+}
+
+impl DidDoc {
+    /// Where the account's repo lives. Goes by the service's id, not where it is in the list.
+    pub fn pds_endpoint(&self) -> Option<String> {
+        self.service.iter().find(|service| service.id == "#atproto_pds" || service.id == format!("{}#atproto_pds", self.id)).map(|service| service.service_endpoint.trim_end_matches('/').to_owned())
+    }
+}
+
+impl ClientBackend {
+    /// The `did=` out of `_atproto.<handle>`'s TXT record, asked of the system's own resolver
+    async fn resolve_handle_dns(&mut self, handle: &str) -> Result<Option<String>, BlueskyApiError> {
+        let resolver = TokioAsyncResolver::tokio_from_system_conf().map_err(|err| identity_error("DnsFailed", err.to_string()))?;
+        let lookup = match resolver.txt_lookup(format!("_atproto.{}.", handle)).await {
+            Ok(lookup) => lookup,
+            Err(err) if matches!(err.kind(), ResolveErrorKind::NoRecordsFound { .. }) => return Ok(None),
+            Err(err) => return Err(identity_error("DnsFailed", err.to_string())),
+        };
+
+        // long records come back as a few strings in a row
+        Ok(lookup.iter().map(|txt| txt.txt_data().iter().map(|part| String::from_utf8_lossy(part)).collect::<String>()).find_map(|record| record.strip_prefix("did=").map(str::to_owned)))
+    }
+
+    /// The DID served at `https://<handle>/.well-known/atproto-did`
+    async fn resolve_handle_well_known(&mut self, handle: &str) -> Result<Option<String>, BlueskyApiError> {
+        let res = self.client.get(format!("https://{}/.well-known/atproto-did", handle)).send().await.map_err(BlueskyApiError::NetworkError)?;
+        if !res.status().is_success() {
+            return Ok(None);
+        }
+        let body = res.text().await.map_err(BlueskyApiError::NetworkError)?;
+        let did = body.trim();
+        Ok(if did.starts_with("did:") { Some(did.to_owned()) } else { None })
+    }
+
+    /// Handle to DID without going through a PDS, since we don't have one yet at login.
+    /// Tries DNS first and then the well-known file, like the spec says.
+    pub async fn resolve_handle_directly(&mut self, handle: &str) -> Result<String, BlueskyApiError> {
+        let handle = normalize_handle(handle);
+        match self.resolve_handle_dns(&handle).await {
+            Ok(Some(did)) => return Ok(did),
+            Ok(None) => {}
+            Err(err) => println!("DNS lookup for {} failed: {:?}", handle, err),
+        }
+        match self.resolve_handle_well_known(&handle).await {
+            Ok(Some(did)) => Ok(did),
+            Ok(None) => Err(identity_error("HandleNotFound", format!("{} isn't pointing at an account", handle))),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// DID to DID document, from the PLC directory or the domain for `did:web`
+    pub async fn resolve_did(&mut self, did: &str) -> Result<DidDoc, BlueskyApiError> {
+        let url = if did.starts_with("did:plc:") {
+            format!("{}/{}", PLC_DIRECTORY, did)
+        } else if let Some(host) = did.strip_prefix("did:web:") {
+            // ports get percent-encoded in the DID
+            format!("https://{}/.well-known/did.json", host.replace("%3A", ":"))
+        } else {
+            return Err(identity_error("UnsupportedDidMethod", format!("Can't look up {}", did)));
+        };
+
+        let req = Self::send_request(self.client.get(url)).await?;
+        let res: Result<DidDoc, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+        let doc = res.unwrap();
+        if doc.id != did {
+            return Err(identity_error("DidMismatch", format!("Asked for {}, got the document for {}", did, doc.id)));
+        }
+        Ok(doc)
+    }
+
+    /// Finds the PDS for a handle or DID. Emails can't be looked up, those have to go through a hosting provider.
+    pub async fn resolve_pds(&mut self, identifier: &str) -> Result<String, BlueskyApiError> {
+        let did = if identifier.starts_with("did:") { identifier.to_owned() } else { self.resolve_handle_directly(identifier).await? };
+        let doc = self.resolve_did(&did).await?;
+        doc.pds_endpoint().ok_or_else(|| identity_error("PdsNotFound", format!("{} doesn't list a PDS", did)))
+    }
+}
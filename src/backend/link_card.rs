@@ -0,0 +1,252 @@
+use std::io::Cursor;
+
+use image::{imageops::FilterType, ImageFormat};
+use reqwest::Url;
+
+use crate::defs::{self, bsky::embed::external};
+
+use super::{BlueskyApiError, BlueskyApiErrorMessage, ClientBackend};
+
+/// Nobody puts their meta tags this far down, don't download the whole page for them
+const MAX_PAGE_SIZE: usize = 512 * 1024;
+/// The lexicon's limit on external thumbnails
+const MAX_THUMB_SIZE: usize = 1_000_000;
+const MAX_THUMB_DIMENSION: u32 = 1000;
+/// Bigger than this and it's not worth downloading just to shrink it, the card goes without
+const MAX_THUMB_DOWNLOAD: usize = 10 * 1024 * 1024;
+const MAX_TITLE_LENGTH: usize = 300;
+const MAX_DESCRIPTION_LENGTH: usize = 1000;
+
+/// What a page says about itself, enough to show and post a card for it
+#[derive(Debug, Clone)]
+pub struct LinkCard {
+    pub uri: String,
+    pub title: String,
+    pub description: String,
+    /// Absolute URL of the preview image
+    pub image: Option<String>,
+}
+
+/// Shared with the composer while the page is being looked at
+#[derive(Debug, Clone)]
+pub enum LinkCardState {
+    Loading,
+    Loaded(LinkCard),
+    /// Couldn't get anything useful, the post goes out without a card
+    Failed,
+}
+
+fn link_card_error(error: &str, message: String) -> BlueskyApiError {
+    BlueskyApiError::BadRequest(BlueskyApiErrorMessage { error: error.to_owned(), message })
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut rtn = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        rtn.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';').filter(|semi| *semi <= 10) else {
+            rtn.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                if let Some(hex) = entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok().and_then(char::from_u32)
+                } else {
+                    None
+                }
+            }
+        };
+        match decoded {
+            Some(chr) => {
+                rtn.push(chr);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                rtn.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    rtn.push_str(rest);
+    rtn
+}
+
+/// Pulls `key="value"` pairs out of the inside of a tag, keys lowercased
+fn tag_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut rtn = Vec::new();
+    let mut rest = tag;
+
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].split_whitespace().last().unwrap_or("").to_lowercase();
+        rest = rest[eq + 1..].trim_start();
+
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let inner = &rest[1..];
+                let end = inner.find(quote).unwrap_or(inner.len());
+                rest = inner.get(end + 1..).unwrap_or("");
+                &inner[..end]
+            }
+            _ => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                let value = &rest[..end];
+                rest = &rest[end..];
+                value
+            }
+        };
+        rtn.push((key, decode_entities(value)));
+    }
+
+    rtn
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if text.chars().count() <= max {
+        return text;
+    }
+    let mut rtn: String = text.chars().take(max - 1).collect();
+    rtn.push('…');
+    rtn
+}
+
+/// Reads OpenGraph and Twitter card tags out of a page, falling back to `<title>` and `<meta name="description">`.
+pub fn parse_link_card(html: &str, uri: &Url) -> LinkCard {
+    // ascii only, so byte offsets still line up with the original
+    let lower = html.to_ascii_lowercase();
+    let mut og_title = None;
+    let mut og_description = None;
+    let mut og_image = None;
+    let mut twitter_title = None;
+    let mut twitter_description = None;
+    let mut twitter_image = None;
+    let mut meta_description = None;
+
+    let mut offset = 0;
+    while let Some(start) = lower[offset..].find("<meta") {
+        let start = offset + start;
+        let Some(end) = lower[start..].find('>') else { break };
+        let end = start + end;
+        offset = end;
+
+        let attrs = tag_attributes(&html[start + 5..end]);
+        let get = |name: &str| attrs.iter().find(|(key, _)| key == name).map(|(_, value)| value.trim().to_owned());
+        let (Some(key), Some(content)) = (get("property").or_else(|| get("name")), get("content")) else { continue };
+        if content.is_empty() {
+            continue;
+        }
+
+        let slot = match key.to_lowercase().as_str() {
+            "og:title" => &mut og_title,
+            "og:description" => &mut og_description,
+            "og:image" | "og:image:url" | "og:image:secure_url" => &mut og_image,
+            "twitter:title" => &mut twitter_title,
+            "twitter:description" => &mut twitter_description,
+            "twitter:image" | "twitter:image:src" => &mut twitter_image,
+            "description" => &mut meta_description,
+            _ => continue,
+        };
+        // the first one is usually the one they meant
+        if slot.is_none() {
+            *slot = Some(content);
+        }
+    }
+
+    let page_title = lower.find("<title").and_then(|start| {
+        let open_end = start + lower[start..].find('>')? + 1;
+        let close = open_end + lower[open_end..].find("</title")?;
+        Some(decode_entities(html[open_end..close].trim()))
+    });
+
+    let title = og_title.or(twitter_title).or(page_title).unwrap_or_default();
+    let description = og_description.or(twitter_description).or(meta_description).unwrap_or_default();
+    let image = og_image.or(twitter_image).and_then(|image| uri.join(&image).ok()).map(|image| image.to_string());
+
+    LinkCard { uri: uri.to_string(), title: truncate(&title, MAX_TITLE_LENGTH), description: truncate(&description, MAX_DESCRIPTION_LENGTH), image }
+}
+
+impl ClientBackend {
+    /// Downloads the top of a page and reads its card metadata. Not a Bluesky endpoint, so none of the auth.
+    pub async fn get_link_card(&mut self, uri: &str) -> Result<LinkCard, BlueskyApiError> {
+        let url = Url::parse(uri).map_err(|err| link_card_error("InvalidUrl", err.to_string()))?;
+        let mut res = self.client.get(url.clone()).header("accept", "text/html").send().await.map_err(BlueskyApiError::NetworkError)?;
+        if !res.status().is_success() {
+            return Err(link_card_error("LinkCardFailed", format!("{} responded with {}", uri, res.status())));
+        }
+        // relative image paths are relative to wherever the redirects ended up
+        let url = res.url().clone();
+
+        let mut body: Vec<u8> = Vec::new();
+        while let Some(chunk) = res.chunk().await.map_err(BlueskyApiError::NetworkError)? {
+            body.extend_from_slice(&chunk);
+            if body.len() > MAX_PAGE_SIZE || body.windows(7).any(|window| window.eq_ignore_ascii_case(b"</head>")) {
+                break;
+            }
+        }
+
+        let mut card = parse_link_card(&String::from_utf8_lossy(&body), &url);
+        // keep what they typed, not where it went
+        card.uri = uri.to_owned();
+        if card.title.is_empty() {
+            card.title = url.host_str().unwrap_or(uri).to_owned();
+        }
+        Ok(card)
+    }
+
+    /// Grabs a card's image and uploads it as a blob, shrinking it first if it's over the size limit
+    pub async fn upload_link_card_thumb(&mut self, image: &str) -> Result<defs::Blob, BlueskyApiError> {
+        let mut res = self.client.get(image).send().await.map_err(BlueskyApiError::NetworkError)?;
+        let mime_type = res.headers().get("content-type").and_then(|mime| mime.to_str().ok()).unwrap_or("image/jpeg").to_owned();
+        let too_big = || link_card_error("ThumbnailTooLarge", format!("{} is over {} bytes", image, MAX_THUMB_DOWNLOAD));
+        if res.content_length().is_some_and(|len| len as usize > MAX_THUMB_DOWNLOAD) {
+            return Err(too_big());
+        }
+        // the length can be missing or wrong, so keep counting
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = res.chunk().await.map_err(BlueskyApiError::NetworkError)? {
+            buffer.extend_from_slice(&chunk);
+            if buffer.len() > MAX_THUMB_DOWNLOAD {
+                return Err(too_big());
+            }
+        }
+
+        if buffer.len() <= MAX_THUMB_SIZE && mime_type.starts_with("image/") {
+            return self.upload_blob(buffer, &mime_type).await;
+        }
+
+        let img = image::load_from_memory(&buffer).map_err(|err| link_card_error("ThumbnailError", err.to_string()))?;
+        let img = if img.width() > MAX_THUMB_DIMENSION || img.height() > MAX_THUMB_DIMENSION { img.resize(MAX_THUMB_DIMENSION, MAX_THUMB_DIMENSION, FilterType::Triangle) } else { img };
+        let mut shrunk = Cursor::new(Vec::new());
+        img.to_rgb8().write_to(&mut shrunk, ImageFormat::Jpeg).map_err(|err| link_card_error("ThumbnailError", err.to_string()))?;
+
+        self.upload_blob(shrunk.into_inner(), "image/jpeg").await
+    }
+
+    /// Turns a card into what goes in the post, a thumbnail that won't upload just gets left off
+    pub async fn link_card_embed(&mut self, card: LinkCard) -> external::External {
+        let thumb = match &card.image {
+            Some(image) => match self.upload_link_card_thumb(image).await {
+                Ok(blob) => Some(blob),
+                Err(err) => {
+                    println!("Failed to upload link card thumbnail: {:?}", err);
+                    None
+                }
+            },
+            None => None,
+        };
+        external::External { uri: card.uri, title: card.title, description: card.description, thumb }
+    }
+}
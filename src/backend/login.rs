@@ -4,7 +4,7 @@ use chrono::{DateTime, TimeDelta, Utc};
 use reqwest::{RequestBuilder, StatusCode};
 use serde::Deserialize;
 
-use super::{main::{BlueskyLoginResponse, BlueskyLoginResponseError, LoginInformation}, BlueskyApiErrorMessage, ClientBackend};
+use super::{identity::normalize_service_url, main::{BlueskyLoginResponse, BlueskyLoginResponseError, BlueskyLoginResponseInfo, LoginInformation}, BlueskyApiErrorMessage, ClientBackend, SessionState};
 use base64::prelude::*;
 
 #[allow(dead_code)]
@@ -29,15 +29,41 @@ pub struct AtProtoService {
 #[serde(rename_all = "camelCase")]
 /// THERE'S NO SPEC FOR THIS. IT DOESN'T FUCKING EXIST. I'm guessing this on API responses and github issues.
 pub struct DidDoc {
-    #[serde(rename = "@context")]
+    #[serde(rename = "@context", default)]
     pub context: Vec<serde_json::Value>,
     pub id: String,
+    #[serde(default)]
     pub also_known_as: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verification_method: Option<Vec<serde_json::Value>>,
+    #[serde(default)]
     pub service: Vec<AtProtoService>,
 }
 
+/// Where the default login goes when we can't work out someone's PDS, it'll forward them
+pub(super) const DEFAULT_ENTRYWAY: &str = "https://bsky.social";
+
+/// Reads a JWT's claims without checking the signature, the PDS does that
+fn decode_jwt(token: &str) -> Option<JwtMidsection> {
+    let payload = token.split(".").nth(1)?;
+    let decode = BASE64_URL_SAFE_NO_PAD.decode(payload.trim_end_matches('='));
+    let Ok(dec) = decode else {
+        println!("couldn't base64 decode {:?}", decode);
+        return None;
+    };
+    let Ok(payload) = String::from_utf8(dec) else {
+        println!("couldn't parse jwt string");
+        return None;
+    };
+    match serde_json::from_str(&payload) {
+        Ok(jwt) => Some(jwt),
+        Err(_) => {
+            println!("couldn't parse jwt json");
+            None
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BlueskyApiLoginResponse {
@@ -64,15 +90,31 @@ impl ClientBackend {
         Self {
             did: String::new(),
             standard_pds: "https://public.api.bsky.app".into(),
-            user_pds: "https://bsky.social".into(),
+            user_pds: DEFAULT_ENTRYWAY.into(),
             access_token: String::new(),
             refresh_token: String::new(),
             access_token_expiry: Utc::now(),
             client: reqwest::Client::new(),
             post_cache: HashMap::new(),
+            convo_cache: HashMap::new(),
+            chat_log_cursor: None,
+            oauth: None,
+            session: SessionState::LoggedOut,
+            refreshed: false,
+            expired_unreported: false,
         }
     }
 
+    /// Who this session is for, empty if it isn't logged in
+    pub fn did(&self) -> &str {
+        &self.did
+    }
+
+    /// What a fresh login would've given back, for a session that's already logged in
+    pub fn login_information(&self) -> LoginInformation {
+        LoginInformation { did: self.did.clone(), refresh_token: self.refresh_token.clone(), oauth: self.oauth_session() }
+    }
+
     async fn handle_login_response(&mut self, req: RequestBuilder) -> BlueskyLoginResponse {
         let res = req.send().await;
 
@@ -82,7 +124,14 @@ impl ClientBackend {
         let res = res.unwrap();
 
         match res.status() {
-            StatusCode::UNAUTHORIZED => return BlueskyLoginResponse::Error(BlueskyLoginResponseError::Unauthorized),
+            StatusCode::UNAUTHORIZED => {
+                // accounts with email 2FA get this instead of a session, and the PDS emails them a code
+                let jason = res.text().await.unwrap_or_default();
+                match serde_json::from_str::<BlueskyApiErrorMessage>(&jason) {
+                    Ok(response) if response.error == "AuthFactorTokenRequired" => BlueskyLoginResponse::Info(BlueskyLoginResponseInfo::TwoFactorTokenRequired),
+                    _ => BlueskyLoginResponse::Error(BlueskyLoginResponseError::Unauthorized),
+                }
+            }
             StatusCode::BAD_REQUEST => {
                 let jason = res.text().await.unwrap();
                 let response: Result<BlueskyApiErrorMessage, serde_json::Error> = serde_json::from_str(&jason);
@@ -95,6 +144,7 @@ impl ClientBackend {
 
                 match response.error.as_str() {
                     "ExpiredToken" => BlueskyLoginResponse::Error(BlueskyLoginResponseError::ExpiredToken),
+                    "InvalidToken" => BlueskyLoginResponse::Error(BlueskyLoginResponseError::InvalidToken),
                     catchall => BlueskyLoginResponse::Error(BlueskyLoginResponseError::Generic(catchall.to_string())),
                 }
             }
@@ -110,10 +160,8 @@ impl ClientBackend {
 
                 self.did = response.did;
                 self.access_token = response.access_jwt;
-                if let Some(did_doc) = response.did_doc {
-                    if did_doc.service.len() > 0 {
-                        self.user_pds = did_doc.service[0].service_endpoint.clone();
-                    }
+                if let Some(pds) = response.did_doc.and_then(|did_doc| did_doc.pds_endpoint()) {
+                    self.user_pds = pds;
                 }
 
                 if response.active.is_none() || !response.active.unwrap() {
@@ -129,31 +177,7 @@ impl ClientBackend {
                     }
                 }
 
-                let token_list: Vec<&str> = self.access_token.split(".").collect();
-                let mut proc = String::new();
-                proc.push_str(token_list[1]);
-                for _ in 0..(token_list[1].len() % 4) {
-                    proc.push('=');
-                }
-                let decode = BASE64_STANDARD.decode(proc);
-
-                let expiry: Option<DateTime<Utc>> = if let Ok(dec) = decode {
-                    if let Ok(payload) = String::from_utf8(dec) {
-                        let jason: Result<JwtMidsection, serde_json::Error> = serde_json::from_str(&payload);
-                        if let Ok(jwt) = jason {
-                            DateTime::from_timestamp(jwt.exp as i64, 0 as u32)
-                        } else {
-                            println!("couldn't parse jwt json");
-                            None
-                        }
-                    } else {
-                        println!("couldn't parse jwt string");
-                        None
-                    }
-                } else {
-                    println!("couldn't base64 decode {:?}", decode);
-                    None
-                };
+                let expiry: Option<DateTime<Utc>> = decode_jwt(&self.access_token).and_then(|jwt| DateTime::from_timestamp(jwt.exp as i64, 0 as u32));
 
                 if let Some(expiry) = expiry {
                     self.access_token_expiry = expiry;
@@ -163,10 +187,12 @@ impl ClientBackend {
                 }
 
                 self.refresh_token = response.refresh_jwt.clone();
+                self.session = SessionState::Active;
                 
                 return BlueskyLoginResponse::Success(LoginInformation {
                     did: self.did.clone(),
-                    refresh_token: response.refresh_jwt
+                    refresh_token: response.refresh_jwt,
+                    oauth: None,
                 });
             }
             _ => {
@@ -176,8 +202,31 @@ impl ClientBackend {
         }
     }
 
+    /// Points us at the PDS to log in against, the hosting provider they gave us or wherever their handle says their account is
+    async fn pick_login_pds(&mut self, identifier: &str, provider: Option<String>) {
+        // a password session replaces whatever OAuth one we had
+        self.oauth = None;
+        if let Some(provider) = provider.filter(|provider| !provider.trim().is_empty()) {
+            self.user_pds = normalize_service_url(&provider);
+            return;
+        }
+        self.user_pds = if identifier.contains('@') && !identifier.starts_with('@') {
+            // an email, nothing to look up
+            DEFAULT_ENTRYWAY.to_owned()
+        } else {
+            match self.resolve_pds(identifier).await {
+                Ok(pds) => pds,
+                Err(err) => {
+                    println!("Couldn't find the PDS for {}, trying the default: {:?}", identifier, err);
+                    DEFAULT_ENTRYWAY.to_owned()
+                }
+            }
+        };
+    }
+
     /// Fresh login
-    pub async fn login(&mut self, handle: String, password: String) -> BlueskyLoginResponse {
+    pub async fn login(&mut self, handle: String, password: String, provider: Option<String>) -> BlueskyLoginResponse {
+        self.pick_login_pds(&handle, provider).await;
         let mut map = HashMap::new();
         map.insert("identifier", handle);
         map.insert("password", password);
@@ -189,16 +238,31 @@ impl ClientBackend {
         self.handle_login_response(req).await
     }
 
-    /*
-    /// Fresh login, the user has 2FA
-    pub async fn login_2fa(&mut self, handle: String, password: String, two_factor_code: String) -> BlueskyLoginResponse {
-        todo!()
+    /// Fresh login, the user has 2FA and has the code that got emailed to them
+    pub async fn login_2fa(&mut self, handle: String, password: String, two_factor_code: String, provider: Option<String>) -> BlueskyLoginResponse {
+        self.pick_login_pds(&handle, provider).await;
+        let mut map = HashMap::new();
+        map.insert("identifier", handle);
+        map.insert("password", password);
+        map.insert("authFactorToken", two_factor_code);
+
+        let req = self.client.post(format!("{}/xrpc/com.atproto.server.createSession", self.user_pds)).json(&map);
+
+        self.handle_login_response(req).await
     }
-    */
 
     /// Login from a cached token
     pub async fn login_refresh(&mut self, refresh_token: String) -> BlueskyLoginResponse {
         println!("Refreshing login");
+        // on startup we don't know where the account lives yet, but the token knows who it's for
+        if self.did.is_empty()
+            && let Some(jwt) = decode_jwt(&refresh_token)
+        {
+            match self.resolve_pds(&jwt.sub).await {
+                Ok(pds) => self.user_pds = pds,
+                Err(err) => println!("Couldn't find the PDS for {}: {:?}", jwt.sub, err),
+            }
+        }
         let req = self.client.post(format!("{}/xrpc/com.atproto.server.refreshSession", self.user_pds)).bearer_auth(refresh_token);
 
         self.handle_login_response(req).await
@@ -1,3 +1,5 @@
+use super::oauth::StoredOAuthSession;
+
 pub enum BlueskyLoginResponseInfo {
     WasntLoggedIn,
     TwoFactorTokenRequired,
@@ -19,6 +21,8 @@ pub enum BlueskyLoginResponseError {
 pub struct LoginInformation {
     pub did: String,
     pub refresh_token: String,
+    /// Only for browser logins, what's needed to refresh alongside the token
+    pub oauth: Option<StoredOAuthSession>,
 }
 
 pub enum BlueskyLoginResponse {
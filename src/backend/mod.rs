@@ -1,7 +1,7 @@
 use std::{collections::HashMap, sync::{Arc, Mutex}};
 
 use chrono::{DateTime, Utc};
-use reqwest::{RequestBuilder, StatusCode};
+use reqwest::{RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 
 use crate::defs::bsky::feed::defs::PostView;
@@ -16,6 +16,16 @@ pub mod timeline;
 pub mod blob;
 pub mod feeds;
 pub mod following;
+pub mod notifications;
+pub mod chat;
+pub mod search;
+pub mod mute;
+pub mod video;
+pub mod link_card;
+pub mod gates;
+pub mod compose;
+pub mod identity;
+pub mod oauth;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct BlueskyApiErrorMessage {
@@ -32,6 +42,25 @@ pub enum BlueskyApiError {
     NotImplemented,
 }
 
+/// Where the session's at. Everything goes through `&mut self` one request at a time,
+/// so a refresh is never in flight twice and whatever comes after it goes out with the new token.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionState {
+    LoggedOut,
+    Active,
+    /// The access token's expired or got turned down, the next request refreshes first
+    Stale,
+    /// The refresh token got turned down too, only logging in again fixes it
+    Dead,
+}
+
+fn session_error(error: &str, message: String) -> BlueskyApiError {
+    BlueskyApiError::Unauthorized(BlueskyApiErrorMessage { error: error.to_owned(), message })
+}
+
+/// What a session that's been given up on fails requests with, so they can be told apart from everything else
+pub const SESSION_EXPIRED: &str = "SessionExpired";
+
 pub struct ClientBackend {
     pub(self) did: String,
     /// Used for general unauthenticated things, lookups, profiles, most things
@@ -43,24 +72,99 @@ pub struct ClientBackend {
     pub access_token_expiry: DateTime<Utc>,
     client: reqwest::Client,
     post_cache: HashMap<String, Arc<Mutex<crate::defs::bsky::feed::defs::PostView>>>,
+    /// Same idea as the post cache, keyed by convo id, so the chat log can update conversations in place
+    convo_cache: HashMap<String, Arc<Mutex<chat::Conversation>>>,
+    chat_log_cursor: Option<String>,
+    /// Set when we logged in through the browser, requests get signed with its DPoP key instead of a plain bearer token
+    oauth: Option<oauth::OAuthState>,
+    session: SessionState,
+    /// The refresh token got rotated and hasn't been written back yet
+    refreshed: bool,
+    /// The session died and nobody's been told yet
+    expired_unreported: bool,
 }
 
 impl ClientBackend {
     pub async fn make_request(&mut self, request: RequestBuilder) -> Result<String, BlueskyApiError> {
-        if self.access_token_expiry < Utc::now() {
-            println!("Token was outdated, refreshing...");
-            self.login_refresh(self.refresh_token.clone()).await;
-            println!("Refreshed.");
+        if self.session == SessionState::Dead {
+            return Err(session_error(SESSION_EXPIRED, "The session expired, log in again".into()));
+        }
+        if self.session == SessionState::Active && self.access_token_expiry < Utc::now() {
+            self.session = SessionState::Stale;
+        }
+        if self.session == SessionState::Stale {
+            self.refresh_session().await?;
+        }
+
+        // kept in case the token turns out to be expired anyway, and it has to go again
+        let retry = request.try_clone();
+        let res = self.send_authenticated(request).await;
+        let expired = matches!(&res, Err(BlueskyApiError::BadRequest(err) | BlueskyApiError::Unauthorized(err)) if err.error == "ExpiredToken" || (self.oauth.is_some() && err.error.eq_ignore_ascii_case("invalid_token")));
+        match retry {
+            Some(retry) if expired && self.session == SessionState::Active => {
+                self.session = SessionState::Stale;
+                self.refresh_session().await?;
+                self.send_authenticated(retry).await
+            }
+            _ => res,
         }
+    }
 
+    async fn send_authenticated(&mut self, request: RequestBuilder) -> Result<String, BlueskyApiError> {
+        if self.oauth.is_some() {
+            return self.send_dpop_request(request).await;
+        }
         let request = request.bearer_auth(&self.access_token);
+        Self::send_request(request).await
+    }
+
+    /// Trades the refresh token in for a new session. Network trouble leaves it to be tried again on the next request,
+    /// but a refresh token that gets turned down kills the session for good.
+    async fn refresh_session(&mut self) -> Result<(), BlueskyApiError> {
+        println!("Token was outdated, refreshing...");
+        let res = if self.oauth.is_some() { self.oauth_refresh().await } else { self.login_refresh(self.refresh_token.clone()).await };
+        match res {
+            main::BlueskyLoginResponse::Success(_) => {
+                println!("Refreshed.");
+                self.refreshed = true;
+                Ok(())
+            }
+            main::BlueskyLoginResponse::Error(main::BlueskyLoginResponseError::Network(reason) | main::BlueskyLoginResponseError::Generic(reason)) => {
+                self.session = SessionState::Stale;
+                Err(session_error("RefreshFailed", reason))
+            }
+            _ => {
+                println!("Refresh token was turned down, the session's dead.");
+                self.session = SessionState::Dead;
+                self.expired_unreported = true;
+                Err(session_error(SESSION_EXPIRED, "The session expired, log in again".into()))
+            }
+        }
+    }
+
+    /// The session as it is now, if a refresh has rotated the refresh token since the last time this was asked
+    pub fn take_refreshed_login(&mut self) -> Option<main::LoginInformation> {
+        if std::mem::take(&mut self.refreshed) && self.session == SessionState::Active { Some(self.login_information()) } else { None }
+    }
+
+    /// True once, after the session dies
+    pub fn take_session_expired(&mut self) -> bool {
+        std::mem::take(&mut self.expired_unreported)
+    }
+
+    /// Sends a request that's already been authenticated (or doesn't need to be) and sorts out the response.
+    /// Used for services that take a service auth token instead of our access token.
+    pub async fn send_request(request: RequestBuilder) -> Result<String, BlueskyApiError> {
         let response = request.send().await;
 
         if let Err(err) = response {
             return Err(BlueskyApiError::NetworkError(err));
         }
-        let response = response.unwrap();
+        Self::read_response(response.unwrap()).await
+    }
 
+    /// Sorts out a response, pulling XRPC errors out of the body
+    pub async fn read_response(response: Response) -> Result<String, BlueskyApiError> {
         let status_code = response.status();
 
         let string = response.text().await;
@@ -86,9 +190,12 @@ impl ClientBackend {
         }
 
         if cfg!(debug_assertions) {
+            // some procedures (updateSeen, etc.) respond with an empty body, don't try to prettify those
             let val: Result<serde_json::Value, serde_json::Error> = serde_json::from_str(&string);
-            let val2 = serde_json::to_string_pretty(&val.unwrap()).unwrap();
-            return Ok(val2);
+            if let Ok(val) = val {
+                return Ok(serde_json::to_string_pretty(&val).unwrap());
+            }
+            return Ok(string);
         } else {
             return Ok(string);
         }
@@ -112,4 +219,15 @@ impl ClientBackend {
             post.clone()
         }
     }
+
+    /// Marks one of our posts as deleted in the cache, which hides it everywhere it's loaded
+    pub fn mark_post_deleted(&mut self, rkey: &str) {
+        let uri = format!("at://{}/app.bsky.feed.post/{}", self.did, rkey);
+        for post in self.post_cache.values() {
+            let mut post = post.lock().unwrap();
+            if post.uri == uri {
+                post.deleted = true;
+            }
+        }
+    }
 }
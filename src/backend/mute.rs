@@ -0,0 +1,59 @@
+use serde::Serialize;
+
+use super::{BlueskyApiError, ClientBackend};
+
+#[derive(Serialize)]
+struct MuteActorRequest<'a> {
+    actor: &'a str,
+}
+
+#[derive(Serialize)]
+struct MuteThreadRequest<'a> {
+    root: &'a str,
+}
+
+impl ClientBackend {
+    async fn graph_procedure<T: Serialize>(&mut self, nsid: &str, body: &T) -> Result<(), BlueskyApiError> {
+        let body = serde_json::to_string(body);
+        if let Err(err) = body {
+            return Err(BlueskyApiError::ParseError(err, String::new()));
+        }
+
+        let req = self.client.post(format!("{}/xrpc/{}", self.user_pds, nsid)).body(body.unwrap()).header("content-type", "application/json");
+        self.make_request(req).await?;
+
+        Ok(())
+    }
+
+    /// Mutes (or unmutes) an account. Mutes are private, and only hide things on the requesting account's side.
+    pub async fn mute_actor(&mut self, did: &str, mute: bool) -> Result<(), BlueskyApiError> {
+        let nsid = if mute { "app.bsky.graph.muteActor" } else { "app.bsky.graph.unmuteActor" };
+        self.graph_procedure(nsid, &MuteActorRequest { actor: did }).await?;
+
+        // post authors aren't de-duplicated, but the posts are, so fix up every post by them
+        for post in self.post_cache.values() {
+            let mut post = post.lock().unwrap();
+            if post.author.did == did
+                && let Some(viewer) = &mut post.author.viewer
+            {
+                viewer.muted = Some(mute);
+            }
+        }
+        Ok(())
+    }
+
+    /// Mutes (or unmutes) notifications for a thread, by the uri of its root post.
+    pub async fn mute_thread(&mut self, root: &str, mute: bool) -> Result<(), BlueskyApiError> {
+        let nsid = if mute { "app.bsky.graph.muteThread" } else { "app.bsky.graph.unmuteThread" };
+        self.graph_procedure(nsid, &MuteThreadRequest { root }).await?;
+
+        for post in self.post_cache.values() {
+            let mut post = post.lock().unwrap();
+            let in_thread = post.uri == root || post.record.reply.as_ref().is_some_and(|reply| reply.root.uri == root);
+            if in_thread && let Some(viewer) = &mut post.viewer {
+                viewer.thread_muted = Some(mute);
+            }
+        }
+        Ok(())
+    }
+}
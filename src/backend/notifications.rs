@@ -0,0 +1,131 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::defs::bsky::{actor::defs::ProfileView, feed::defs::PostView, notification::defs::{Notification, Reason}};
+
+use super::{BlueskyApiError, ClientBackend};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListNotificationsResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    pub notifications: Vec<Notification>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seen_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadCountResponse {
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateSeenRequest {
+    pub seen_at: DateTime<Utc>,
+}
+
+/// One or more notifications, shown as a single entry. Only likes and reposts on the same post get merged.
+pub struct NotificationGroup {
+    pub reason: Reason,
+    /// Newest first
+    pub authors: Vec<ProfileView>,
+    /// Only used to decide whether newer notifications still belong to this group
+    pub indexed_at: DateTime<Utc>,
+    pub subject_uri: Option<String>,
+    /// Hydrated from the subject URI, None if it doesn't have one or it failed to load (deleted, etc.)
+    pub subject: Option<Arc<Mutex<PostView>>>,
+    pub is_read: bool,
+}
+
+pub struct NotificationList {
+    /// Same deal as CursorListPair, Some("") means it hasn't loaded yet, None means it's loading or out of pages.
+    pub cursor: Option<String>,
+    pub groups: Vec<NotificationGroup>,
+}
+
+impl NotificationList {
+    pub fn new() -> Self {
+        Self { cursor: Some(String::new()), groups: Vec::new() }
+    }
+
+    /// Adds a page of notifications, merging repeated likes/reposts into an existing group if one's close enough.
+    pub fn append(&mut self, notifications: Vec<Notification>, posts: &HashMap<String, Arc<Mutex<PostView>>>) {
+        // bsky.app uses a similar window, any longer and a popular post turns into one entry forever
+        let window = TimeDelta::hours(48);
+        for notification in notifications {
+            let groupable = matches!(notification.reason, Reason::Like | Reason::Repost);
+            let subject_uri = notification.subject_uri().cloned();
+
+            if groupable {
+                let existing = self.groups.iter_mut().rev().find(|group| {
+                    group.reason == notification.reason && group.subject_uri == subject_uri && group.indexed_at - notification.indexed_at < window
+                });
+                if let Some(group) = existing {
+                    group.is_read &= notification.is_read;
+                    group.authors.push(notification.author);
+                    continue;
+                }
+            }
+
+            let subject = if let Some(uri) = &subject_uri { posts.get(uri).cloned() } else { None };
+            self.groups.push(NotificationGroup {
+                reason: notification.reason,
+                authors: vec![notification.author],
+                indexed_at: notification.indexed_at,
+                subject_uri,
+                subject,
+                is_read: notification.is_read,
+            });
+        }
+    }
+}
+
+impl ClientBackend {
+    /// Enumerate notifications for the requesting account. Requires auth.
+    pub async fn list_notifications(&mut self, cursor: Option<String>, limit: Option<u32>) -> Result<ListNotificationsResponse, BlueskyApiError> {
+        let limit = limit.unwrap_or(50);
+        let cursor = if let Some(cursor) = cursor && !cursor.is_empty() { format!("&cursor={}", cursor) } else { String::new() };
+        let req = self.client.get(format!("{}/xrpc/app.bsky.notification.listNotifications?limit={}{}", self.user_pds, limit, cursor));
+        let req = self.make_request(req).await?;
+
+        let res: Result<ListNotificationsResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        Ok(res.unwrap())
+    }
+
+    /// Count the number of unread notifications for the requesting account. Requires auth.
+    pub async fn get_unread_count(&mut self) -> Result<usize, BlueskyApiError> {
+        let req = self.client.get(format!("{}/xrpc/app.bsky.notification.getUnreadCount", self.user_pds));
+        let req = self.make_request(req).await?;
+
+        let res: Result<UnreadCountResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        Ok(res.unwrap().count)
+    }
+
+    /// Notify server that the requesting account has seen notifications. Requires auth.
+    pub async fn update_seen(&mut self, seen_at: DateTime<Utc>) -> Result<(), BlueskyApiError> {
+        let body = serde_json::to_string(&UpdateSeenRequest { seen_at });
+        if let Err(err) = body {
+            return Err(BlueskyApiError::ParseError(err, String::new()));
+        }
+
+        let req = self.client.post(format!("{}/xrpc/app.bsky.notification.updateSeen", self.user_pds)).body(body.unwrap()).header("content-type", "application/json");
+        self.make_request(req).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,393 @@
+use std::{collections::HashMap, time::Duration};
+
+use base64::prelude::*;
+use chrono::{TimeDelta, Utc};
+use reqwest::{header::{HeaderValue, AUTHORIZATION, WWW_AUTHENTICATE}, RequestBuilder, Response, StatusCode, Url};
+use ring::{digest, rand::{SecureRandom, SystemRandom}, signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING}};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+
+use super::{identity::normalize_service_url, login::DEFAULT_ENTRYWAY, main::{BlueskyLoginResponse, BlueskyLoginResponseError, LoginInformation}, BlueskyApiError, BlueskyApiErrorMessage, ClientBackend, SessionState};
+
+/// Everything the app does, chat included
+const SCOPE: &str = "atproto transition:generic transition:chat.bsky";
+/// How long someone gets to finish up in their browser before we stop listening
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+/// What the browser tab says once it's handed the code back
+const CALLBACK_PAGE: &str = "<!DOCTYPE html><html><head><title>Skyway</title></head><body style=\"font-family: sans-serif\"><h1>You're logged in</h1><p>You can close this tab and go back to the app.</p></body></html>";
+
+#[derive(Debug, Deserialize)]
+struct ProtectedResourceMetadata {
+    authorization_servers: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorizationServerMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    pushed_authorization_request_endpoint: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParResponse {
+    request_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: String,
+    expires_in: Option<i64>,
+    sub: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OAuthErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// What it takes to pick an OAuth session back up, next to the refresh token
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StoredOAuthSession {
+    pub issuer: String,
+    pub token_endpoint: String,
+    pub client_id: String,
+    /// The DPoP key as base64 PKCS#8, the tokens are bound to it
+    pub dpop_key: String,
+}
+
+/// An OAuth session's DPoP key, and the nonces the servers want to see in our proofs
+pub struct OAuthState {
+    stored: StoredOAuthSession,
+    key: EcdsaKeyPair,
+    auth_nonce: Option<String>,
+    pds_nonce: Option<String>,
+}
+
+fn oauth_error(error: &str, message: String) -> BlueskyApiError {
+    BlueskyApiError::BadRequest(BlueskyApiErrorMessage { error: error.to_owned(), message })
+}
+
+fn b64(bytes: &[u8]) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn sha256_b64(text: &str) -> String {
+    b64(digest::digest(&digest::SHA256, text.as_bytes()).as_ref())
+}
+
+fn random_string(len: usize) -> Result<String, BlueskyApiError> {
+    let mut bytes = vec![0; len];
+    SystemRandom::new().fill(&mut bytes).map_err(|_| oauth_error("RandomFailed", "Couldn't get random bytes".into()))?;
+    Ok(b64(&bytes))
+}
+
+/// `form_urlencoded` would give us `+` for spaces, and the client id has to match exactly
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn parse<T: DeserializeOwned>(body: String) -> Result<T, BlueskyApiError> {
+    serde_json::from_str(&body).map_err(|err| BlueskyApiError::ParseError(err, body))
+}
+
+fn dpop_nonce(res: &Response) -> Option<String> {
+    res.headers().get("DPoP-Nonce").and_then(|nonce| nonce.to_str().ok()).map(str::to_owned)
+}
+
+/// Turns a failed OAuth step into something the login modal can show
+fn login_error(err: BlueskyApiError) -> BlueskyLoginResponse {
+    BlueskyLoginResponse::Error(match err {
+        BlueskyApiError::BadRequest(err) if err.error == "invalid_grant" => BlueskyLoginResponseError::InvalidToken,
+        BlueskyApiError::BadRequest(err) | BlueskyApiError::Unauthorized(err) => BlueskyLoginResponseError::Generic(format!("{}: {}", err.error, err.message)),
+        BlueskyApiError::NetworkError(err) => BlueskyLoginResponseError::Network(format!("{:?}", err)),
+        err => BlueskyLoginResponseError::Generic(format!("{:?}", err)),
+    })
+}
+
+/// Waits for the browser to come back to our loopback redirect, and gives back its query
+async fn wait_for_callback(listener: &TcpListener) -> Result<HashMap<String, String>, BlueskyApiError> {
+    let io_error = |err: std::io::Error| oauth_error("CallbackFailed", err.to_string());
+    loop {
+        let (mut stream, _) = listener.accept().await.map_err(io_error)?;
+
+        // all we want is the request line, the rest of the headers can stay unread
+        let mut buf = vec![0; 8192];
+        let mut len = 0;
+        while len < buf.len() && !buf[..len].windows(4).any(|window| window == b"\r\n\r\n") {
+            let read = stream.read(&mut buf[len..]).await.map_err(io_error)?;
+            if read == 0 {
+                break;
+            }
+            len += read;
+        }
+        let request = String::from_utf8_lossy(&buf[..len]);
+        let path = request.lines().next().and_then(|line| line.split(' ').nth(1)).unwrap_or("/");
+
+        // browsers like to ask for a favicon too
+        let Ok(url) = Url::parse(&format!("http://127.0.0.1{}", path)) else { continue };
+        if url.path() != "/callback" {
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+            continue;
+        }
+
+        let response = format!("HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", CALLBACK_PAGE.len(), CALLBACK_PAGE);
+        let _ = stream.write_all(response.as_bytes()).await;
+        return Ok(url.query_pairs().into_owned().collect());
+    }
+}
+
+impl OAuthState {
+    fn new(issuer: String, token_endpoint: String, client_id: String) -> Result<Self, BlueskyApiError> {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).map_err(|_| oauth_error("KeyFailed", "Couldn't make a DPoP key".into()))?;
+        let stored = StoredOAuthSession { issuer, token_endpoint, client_id, dpop_key: BASE64_STANDARD.encode(pkcs8.as_ref()) };
+        Self::from_stored(stored).ok_or_else(|| oauth_error("KeyFailed", "Couldn't read back the DPoP key".into()))
+    }
+
+    fn from_stored(stored: StoredOAuthSession) -> Option<Self> {
+        let pkcs8 = BASE64_STANDARD.decode(&stored.dpop_key).ok()?;
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &pkcs8, &SystemRandom::new()).ok()?;
+        Some(Self { stored, key, auth_nonce: None, pds_nonce: None })
+    }
+
+    /// A DPoP proof JWT for one request. Resource requests also carry a hash of the access token.
+    fn proof(&self, method: &str, url: &Url, nonce: Option<&str>, access_token: Option<&str>) -> Result<String, BlueskyApiError> {
+        // uncompressed point, 0x04 then x then y
+        let public = self.key.public_key().as_ref();
+        let header = serde_json::json!({
+            "typ": "dpop+jwt",
+            "alg": "ES256",
+            "jwk": { "kty": "EC", "crv": "P-256", "x": b64(&public[1..33]), "y": b64(&public[33..65]) },
+        });
+
+        let mut htu = url.clone();
+        htu.set_query(None);
+        htu.set_fragment(None);
+        let mut claims = serde_json::json!({
+            "jti": random_string(16)?,
+            "htm": method,
+            "htu": htu.as_str(),
+            "iat": Utc::now().timestamp(),
+        });
+        if let Some(nonce) = nonce {
+            claims["nonce"] = nonce.into();
+        }
+        if let Some(access_token) = access_token {
+            claims["ath"] = sha256_b64(access_token).into();
+        }
+
+        let signing_input = format!("{}.{}", b64(header.to_string().as_bytes()), b64(claims.to_string().as_bytes()));
+        let signature = self.key.sign(&SystemRandom::new(), signing_input.as_bytes()).map_err(|_| oauth_error("SigningFailed", "Couldn't sign a DPoP proof".into()))?;
+        Ok(format!("{}.{}", signing_input, b64(signature.as_ref())))
+    }
+
+    /// Posts a form to the authorization server. It hands out nonces as it likes, so one it rejects gets one retry.
+    async fn post_form(&mut self, client: &reqwest::Client, url: &str, form: &[(&str, String)]) -> Result<String, BlueskyApiError> {
+        let url = Url::parse(url).map_err(|err| oauth_error("InvalidUrl", format!("{}: {}", url, err)))?;
+        for _ in 0..2 {
+            let proof = self.proof("POST", &url, self.auth_nonce.as_deref(), None)?;
+            let res = client.post(url.clone()).header("DPoP", proof).form(form).send().await.map_err(BlueskyApiError::NetworkError)?;
+            if let Some(nonce) = dpop_nonce(&res) {
+                self.auth_nonce = Some(nonce);
+            }
+
+            let status = res.status();
+            let body = res.text().await.map_err(BlueskyApiError::NetworkError)?;
+            if status.is_success() {
+                return Ok(body);
+            }
+            match serde_json::from_str::<OAuthErrorResponse>(&body) {
+                Ok(err) if err.error == "use_dpop_nonce" => continue,
+                Ok(err) => return Err(oauth_error(&err.error, err.error_description.unwrap_or_default())),
+                Err(_) => return Err(oauth_error("OAuthError", format!("{} responded with {}\n{}", url, status, body))),
+            }
+        }
+        Err(oauth_error("use_dpop_nonce", format!("{} kept asking for a new nonce", url)))
+    }
+}
+
+impl ClientBackend {
+    async fn get_json<T: DeserializeOwned>(&self, url: String) -> Result<T, BlueskyApiError> {
+        parse(Self::send_request(self.client.get(url)).await?)
+    }
+
+    /// The authorization server a PDS trusts. Entryways like bsky.social are their own.
+    async fn authorization_server(&self, pds: &str) -> Result<String, BlueskyApiError> {
+        let res = self.client.get(format!("{}/.well-known/oauth-protected-resource", pds)).send().await.map_err(BlueskyApiError::NetworkError)?;
+        // only a PDS that's its own authorization server goes without the metadata
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(pds.to_owned());
+        }
+        let meta: ProtectedResourceMetadata = parse(ClientBackend::read_response(res).await?)?;
+        meta.authorization_servers.into_iter().next().ok_or_else(|| oauth_error("NoAuthorizationServer", format!("{} doesn't list an authorization server", pds)))
+    }
+
+    /// Logs in through the browser. Doesn't return until they finish there or give up, so it's best run on its own.
+    pub async fn login_oauth(&mut self, handle: String, provider: Option<String>) -> BlueskyLoginResponse {
+        match self.authorize_oauth(handle, provider).await {
+            Ok(info) => BlueskyLoginResponse::Success(info),
+            Err(err) => login_error(err),
+        }
+    }
+
+    async fn authorize_oauth(&mut self, handle: String, provider: Option<String>) -> Result<LoginInformation, BlueskyApiError> {
+        let handle = handle.trim().trim_start_matches('@').to_owned();
+        let pds = match provider.filter(|provider| !provider.trim().is_empty()) {
+            Some(provider) => normalize_service_url(&provider),
+            None if !handle.is_empty() => self.resolve_pds(&handle).await?,
+            None => DEFAULT_ENTRYWAY.to_owned(),
+        };
+        let issuer = self.authorization_server(&pds).await?;
+        let meta: AuthorizationServerMetadata = self.get_json(format!("{}/.well-known/oauth-authorization-server", issuer)).await?;
+        if meta.issuer != issuer {
+            return Err(oauth_error("IssuerMismatch", format!("{} says it's {}", issuer, meta.issuer)));
+        }
+
+        // we're a loopback client, so there's no client metadata to host, the id says it all
+        let listener = TcpListener::bind("127.0.0.1:0").await.map_err(|err| oauth_error("CallbackFailed", err.to_string()))?;
+        let port = listener.local_addr().map_err(|err| oauth_error("CallbackFailed", err.to_string()))?.port();
+        let redirect_uri = format!("http://127.0.0.1:{}/callback", port);
+        let client_id = format!("http://localhost?redirect_uri={}&scope={}", percent_encode(&redirect_uri), percent_encode(SCOPE));
+
+        let mut state = OAuthState::new(issuer.clone(), meta.token_endpoint.clone(), client_id.clone())?;
+        let verifier = random_string(32)?;
+        let csrf = random_string(16)?;
+
+        let mut form = vec![
+            ("client_id", client_id.clone()),
+            ("response_type", "code".to_owned()),
+            ("code_challenge", sha256_b64(&verifier)),
+            ("code_challenge_method", "S256".to_owned()),
+            ("state", csrf.clone()),
+            ("redirect_uri", redirect_uri.clone()),
+            ("scope", SCOPE.to_owned()),
+        ];
+        if !handle.is_empty() {
+            form.push(("login_hint", handle));
+        }
+        let par: ParResponse = parse(state.post_form(&self.client, &meta.pushed_authorization_request_endpoint, &form).await?)?;
+
+        let mut auth_url = Url::parse(&meta.authorization_endpoint).map_err(|err| oauth_error("InvalidUrl", format!("{}: {}", meta.authorization_endpoint, err)))?;
+        auth_url.query_pairs_mut().append_pair("client_id", &client_id).append_pair("request_uri", &par.request_uri);
+        crate::open_in_browser(auth_url.as_str());
+
+        let params = tokio::time::timeout(CALLBACK_TIMEOUT, wait_for_callback(&listener)).await.map_err(|_| oauth_error("TimedOut", "Didn't hear back from the browser".into()))??;
+        if params.get("state") != Some(&csrf) {
+            return Err(oauth_error("StateMismatch", "The browser came back with someone else's login".into()));
+        }
+        if let Some(error) = params.get("error") {
+            return Err(oauth_error(error, params.get("error_description").cloned().unwrap_or_default()));
+        }
+        if params.get("iss").is_some_and(|iss| *iss != issuer) {
+            return Err(oauth_error("IssuerMismatch", format!("Expected {} to answer", issuer)));
+        }
+        let Some(code) = params.get("code").cloned() else {
+            return Err(oauth_error("NoCode", "The browser didn't come back with a code".into()));
+        };
+
+        let form = [
+            ("grant_type", "authorization_code".to_owned()),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", verifier),
+            ("client_id", client_id),
+        ];
+        let tokens: TokenResponse = parse(state.post_form(&self.client, &meta.token_endpoint, &form).await?)?;
+
+        // make sure whoever we got tokens for really does trust this server with their account
+        let pds = self.resolve_pds(&tokens.sub).await?;
+        if self.authorization_server(&pds).await? != issuer {
+            return Err(oauth_error("IssuerMismatch", format!("{} isn't the authorization server for {}", issuer, tokens.sub)));
+        }
+        self.user_pds = pds;
+        self.oauth = Some(state);
+        Ok(self.set_oauth_tokens(tokens))
+    }
+
+    fn set_oauth_tokens(&mut self, tokens: TokenResponse) -> LoginInformation {
+        self.did = tokens.sub;
+        self.access_token = tokens.access_token;
+        self.refresh_token = tokens.refresh_token;
+        self.access_token_expiry = Utc::now().checked_add_signed(TimeDelta::seconds(tokens.expires_in.unwrap_or(300))).unwrap_or(Utc::now());
+        self.session = SessionState::Active;
+        self.login_information()
+    }
+
+    pub(super) fn oauth_session(&self) -> Option<StoredOAuthSession> {
+        self.oauth.as_ref().map(|state| state.stored.clone())
+    }
+
+    /// Login from a cached OAuth session
+    pub async fn login_oauth_refresh(&mut self, refresh_token: String, stored: StoredOAuthSession) -> BlueskyLoginResponse {
+        let Some(state) = OAuthState::from_stored(stored) else {
+            return BlueskyLoginResponse::Error(BlueskyLoginResponseError::InvalidToken);
+        };
+        self.oauth = Some(state);
+        self.refresh_token = refresh_token;
+        self.oauth_refresh().await
+    }
+
+    /// Trades the refresh token in for new tokens. Each refresh token only works once.
+    pub async fn oauth_refresh(&mut self) -> BlueskyLoginResponse {
+        println!("Refreshing OAuth session");
+        let Some(state) = self.oauth.as_mut() else {
+            return BlueskyLoginResponse::Error(BlueskyLoginResponseError::InvalidRequest);
+        };
+        let form = [
+            ("grant_type", "refresh_token".to_owned()),
+            ("refresh_token", self.refresh_token.clone()),
+            ("client_id", state.stored.client_id.clone()),
+        ];
+        let token_endpoint = state.stored.token_endpoint.clone();
+        let tokens: TokenResponse = match state.post_form(&self.client, &token_endpoint, &form).await.and_then(parse) {
+            Ok(tokens) => tokens,
+            Err(err) => return login_error(err),
+        };
+
+        // on startup we don't know where the account lives yet
+        if self.did != tokens.sub {
+            match self.resolve_pds(&tokens.sub).await {
+                Ok(pds) => self.user_pds = pds,
+                Err(err) => return login_error(err),
+            }
+        }
+        BlueskyLoginResponse::Success(self.set_oauth_tokens(tokens))
+    }
+
+    /// Sends a request with our access token bound to the DPoP key. The PDS rotates its nonce too, a stale one gets one retry.
+    pub(super) async fn send_dpop_request(&mut self, request: RequestBuilder) -> Result<String, BlueskyApiError> {
+        let (client, request) = request.build_split();
+        let request = request.map_err(BlueskyApiError::NetworkError)?;
+        let Some(state) = self.oauth.as_mut() else {
+            return Err(oauth_error("NotLoggedIn", "No OAuth session to sign with".into()));
+        };
+        let header = |value: String| HeaderValue::from_str(&value).map_err(|err| oauth_error("InvalidHeader", err.to_string()));
+
+        for _ in 0..2 {
+            let Some(mut req) = request.try_clone() else {
+                return Err(oauth_error("StreamingBody", "Can't sign a request with a streamed body".into()));
+            };
+            let proof = state.proof(req.method().as_str(), req.url(), state.pds_nonce.as_deref(), Some(&self.access_token))?;
+            req.headers_mut().insert(AUTHORIZATION, header(format!("DPoP {}", self.access_token))?);
+            req.headers_mut().insert("DPoP", header(proof)?);
+
+            let res = client.execute(req).await.map_err(BlueskyApiError::NetworkError)?;
+            if let Some(nonce) = dpop_nonce(&res) {
+                state.pds_nonce = Some(nonce);
+            }
+            let wants_nonce = res.status() == StatusCode::UNAUTHORIZED && res.headers().get(WWW_AUTHENTICATE).and_then(|value| value.to_str().ok()).is_some_and(|value| value.contains("use_dpop_nonce"));
+            if !wants_nonce {
+                return Self::read_response(res).await;
+            }
+        }
+        Err(oauth_error("use_dpop_nonce", "The PDS kept asking for a new nonce".into()))
+    }
+}
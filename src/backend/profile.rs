@@ -7,7 +7,25 @@ pub struct PreferencesResponse {
     pub preferences: Vec<Preference>
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct ResolveHandleResponse {
+    pub did: String,
+}
+
 impl ClientBackend {
+    pub async fn resolve_handle(&mut self, handle: &str) -> Result<String, BlueskyApiError> {
+        let query = vec![("handle", handle.to_owned())];
+        let request = self.client.get(format!("{}/xrpc/com.atproto.identity.resolveHandle", self.user_pds)).query(&query);
+        let req = self.make_request(request).await?;
+
+        let parse: Result<ResolveHandleResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = parse {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        Ok(parse.unwrap().did)
+    }
+
     pub async fn get_profile_self(&mut self) -> Result<defs::bsky::actor::defs::ProfileViewDetailed, BlueskyApiError> {
         self.get_profile(self.did.clone()).await
     }
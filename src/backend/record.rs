@@ -1,4 +1,6 @@
-use crate::defs::bsky::richtext::{facet::Link, Facet, Feature, Index};
+use std::collections::HashMap;
+
+use crate::defs::bsky::richtext::{facet::{Link, Mention, Tag}, Facet, Feature, Index};
 
 use super::{BlueskyApiError, ClientBackend};
 use serde::{self, Deserialize, Serialize};
@@ -16,6 +18,26 @@ pub enum BlueskyApiRecord {
     Repost(crate::defs::bsky::feed::Like),
     #[serde(rename = "app.bsky.feed.threadgate")]
     ThreadGate(crate::defs::bsky::feed::ThreadGate),
+    #[serde(rename = "app.bsky.feed.postgate")]
+    PostGate(crate::defs::bsky::feed::PostGate),
+    #[serde(rename = "app.bsky.graph.follow")]
+    Follow(crate::defs::bsky::graph::Follow),
+    #[serde(rename = "app.bsky.graph.block")]
+    Block(crate::defs::bsky::graph::Block),
+}
+
+impl BlueskyApiRecord {
+    pub fn nsid(&self) -> &'static str {
+        match self {
+            BlueskyApiRecord::Post(_) => "app.bsky.feed.post",
+            BlueskyApiRecord::Like(_) => "app.bsky.feed.like",
+            BlueskyApiRecord::Repost(_) => "app.bsky.feed.repost",
+            BlueskyApiRecord::ThreadGate(_) => "app.bsky.feed.threadgate",
+            BlueskyApiRecord::PostGate(_) => "app.bsky.feed.postgate",
+            BlueskyApiRecord::Follow(_) => "app.bsky.graph.follow",
+            BlueskyApiRecord::Block(_) => "app.bsky.graph.block",
+        }
+    }
 }
 
 #[derive(std::fmt::Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -51,6 +73,21 @@ struct CreateRecordRequest {
     pub record: BlueskyApiRecord,
 }
 
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PutRecordRequest {
+    pub repo: String,
+    pub collection: String,
+    pub rkey: String,
+    pub record: BlueskyApiRecord,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GetRecordResponse {
+    pub value: BlueskyApiRecord,
+}
+
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct DeleteRecordRequest {
@@ -59,7 +96,7 @@ struct DeleteRecordRequest {
     pub rkey: String,
 }
 
-fn link_detector(text: String) -> Vec<Facet> {
+pub(crate) fn link_detector(text: String) -> Vec<Facet> {
     let mut rtn = Vec::new();
     let mut start_idx: usize = 0;
     'find: loop {
@@ -95,26 +132,138 @@ fn link_detector(text: String) -> Vec<Facet> {
     rtn
 }
 
+/// Same as the official grammar, hashtags can be up to 64 characters
+const MAX_TAG_LENGTH: usize = 64;
+
+/// Characters that end a hashtag without being whitespace
+fn is_invisible(chr: char) -> bool {
+    matches!(chr, '\u{00AD}' | '\u{2060}' | '\u{200A}' | '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{20E2}')
+}
+
+/// Close enough to unicode's `P` category for trimming tags, ascii symbols like `$` and `+` don't count
+fn is_punctuation(chr: char) -> bool {
+    matches!(chr, '!' | '"' | '#' | '%' | '&' | '\'' | '(' | ')' | '*' | ',' | '-' | '.' | '/' | ':' | ';' | '?' | '@' | '[' | '\\' | ']' | '_' | '{' | '}')
+        || matches!(chr, '\u{00A1}' | '\u{00A7}' | '\u{00AB}' | '\u{00B6}' | '\u{00B7}' | '\u{00BB}' | '\u{00BF}')
+        || matches!(chr, '\u{2010}'..='\u{2027}' | '\u{2030}'..='\u{205E}' | '\u{3001}'..='\u{3003}' | '\u{3008}'..='\u{3011}' | '\u{FF01}'..='\u{FF0F}' | '\u{FF1A}'..='\u{FF1F}')
+}
+
+/// A handle is a domain name, at least two labels and a TLD that doesn't start with a number
+fn is_valid_handle(handle: &str) -> bool {
+    let labels: Vec<&str> = handle.split('.').collect();
+    handle.len() <= 253
+        && labels.len() >= 2
+        && labels.iter().all(|label| !label.is_empty() && label.len() <= 63 && !label.starts_with('-') && !label.ends_with('-'))
+        && labels.last().is_some_and(|tld| tld.starts_with(|chr: char| chr.is_ascii_alphabetic()))
+}
+
+/// Finds `@handle` mentions that start a word (or follow an opening paren), returning the lowercased handle and where it sits.
+/// They still need resolving to a DID before they can be a facet.
+pub(crate) fn mention_detector(text: &str) -> Vec<(String, Index)> {
+    let mut rtn = Vec::new();
+
+    for (start, _) in text.match_indices('@') {
+        if text[..start].chars().next_back().is_some_and(|prev| !prev.is_whitespace() && prev != '(') {
+            continue;
+        }
+        let handle_start = start + 1;
+        let length = text[handle_start..].find(|chr: char| !chr.is_ascii_alphanumeric() && chr != '.' && chr != '-').unwrap_or(text.len() - handle_start);
+        // a sentence ending right after a handle isn't part of it
+        let handle = text[handle_start..handle_start + length].trim_end_matches(['.', '-']);
+
+        if is_valid_handle(handle) {
+            rtn.push((handle.to_lowercase(), Index { byte_start: start, byte_end: handle_start + handle.len() }));
+        }
+    }
+
+    rtn
+}
+
+/// Finds `#hashtags` that start a word. Trailing punctuation gets left off, and all-number tags like `#1` don't count.
+pub(crate) fn tag_detector(text: &str) -> Vec<Facet> {
+    let mut rtn = Vec::new();
+
+    for (start, chr) in text.char_indices() {
+        if chr != '#' && chr != '\u{FF03}' {
+            continue;
+        }
+        if text[..start].chars().next_back().is_some_and(|prev| !prev.is_whitespace()) {
+            continue;
+        }
+        let tag_start = start + chr.len_utf8();
+        let length = text[tag_start..].find(|chr: char| chr.is_whitespace() || is_invisible(chr)).unwrap_or(text.len() - tag_start);
+        let tag = text[tag_start..tag_start + length].trim_end_matches(is_punctuation);
+
+        // keycap emoji, #️⃣
+        if tag.starts_with('\u{FE0F}') {
+            continue;
+        }
+        if tag.is_empty() || tag.chars().count() > MAX_TAG_LENGTH || tag.chars().all(|chr| chr.is_ascii_digit() || is_punctuation(chr)) {
+            continue;
+        }
+
+        rtn.push(Facet {
+            features: vec![Feature::Tag(Tag { tag: tag.to_owned() })],
+            index: Index { byte_start: start, byte_end: tag_start + tag.len() },
+        });
+    }
+
+    rtn
+}
+
 impl ClientBackend {
+    /// Links, hashtags and mentions, in order. Mentions of handles that don't resolve get left as plain text.
+    pub async fn build_facets(&mut self, text: &str) -> Vec<Facet> {
+        let mut rtn = link_detector(text.to_owned());
+        rtn.extend(tag_detector(text));
+
+        let mut resolved: HashMap<String, Option<String>> = HashMap::new();
+        for (handle, index) in mention_detector(text) {
+            if !resolved.contains_key(&handle) {
+                let did = match self.resolve_handle(&handle).await {
+                    Ok(did) => Some(did),
+                    Err(err) => {
+                        println!("Couldn't resolve @{}: {:?}", handle, err);
+                        None
+                    }
+                };
+                resolved.insert(handle.clone(), did);
+            }
+            if let Some(Some(did)) = resolved.get(&handle) {
+                rtn.push(Facet { features: vec![Feature::Mention(Mention { did: did.clone() })], index });
+            }
+        }
+
+        // facets can't overlap, whichever starts first wins
+        rtn.sort_by_key(|facet| facet.index.byte_start);
+        let mut end = 0;
+        rtn.retain(|facet| {
+            if facet.index.byte_start < end {
+                return false;
+            }
+            end = facet.index.byte_end;
+            true
+        });
+
+        rtn
+    }
+
     pub async fn create_record(&mut self, record: BlueskyApiRecord) -> Result<BlueskyApiCreateRecordResponse, BlueskyApiError> {
-        let (nsid, record) = match record {
+        let record = match record {
             BlueskyApiRecord::Post(post) => {
                 let post = if post.facets.is_some() {
                     post
                 } else {
                     let mut post = post;
-                    post.facets = Some(link_detector(post.text.clone()));
+                    post.facets = Some(self.build_facets(&post.text).await);
                     post
                 };
                 
-                ("app.bsky.feed.post", BlueskyApiRecord::Post(post))
+                BlueskyApiRecord::Post(post)
             },
-            BlueskyApiRecord::Like(_) => ("app.bsky.feed.like", record),
-            BlueskyApiRecord::Repost(_) => ("app.bsky.feed.repost", record),
-            BlueskyApiRecord::ThreadGate(_) => ("app.bsky.feed.threadgate", record),
+            record => record,
         };
 
-        let contents = CreateRecordRequest { repo: self.did.clone(), collection: nsid.to_owned(), record };
+        let contents = CreateRecordRequest { repo: self.did.clone(), collection: record.nsid().to_owned(), record };
 
         let body = serde_json::to_string::<CreateRecordRequest>(&contents);
         if let Err(err) = body {
@@ -133,6 +282,45 @@ impl ClientBackend {
         return Ok(parse.unwrap());
     }
 
+    /// Writes a record at a specific rkey, replacing whatever was there
+    pub async fn put_record(&mut self, record: BlueskyApiRecord, rkey: &str) -> Result<BlueskyApiCreateRecordResponse, BlueskyApiError> {
+        let contents = PutRecordRequest { repo: self.did.clone(), collection: record.nsid().to_owned(), rkey: rkey.to_owned(), record };
+
+        let body = serde_json::to_string::<PutRecordRequest>(&contents);
+        if let Err(err) = body {
+            return Err(BlueskyApiError::ParseError(err, String::new()));
+        }
+        let body = body.unwrap();
+
+        let req = self.client.post(format!("{}/xrpc/com.atproto.repo.putRecord", self.user_pds)).body(body).header("content-type", "application/json");
+        let req = self.make_request(req).await?;
+
+        let parse: Result<BlueskyApiCreateRecordResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = parse {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        Ok(parse.unwrap())
+    }
+
+    /// One of our own records, `None` if there's nothing at that rkey
+    pub async fn get_record(&mut self, nsid: &str, rkey: &str) -> Result<Option<BlueskyApiRecord>, BlueskyApiError> {
+        let query = vec![("repo", self.did.clone()), ("collection", nsid.to_owned()), ("rkey", rkey.to_owned())];
+        let req = self.client.get(format!("{}/xrpc/com.atproto.repo.getRecord", self.user_pds)).query(&query);
+        let req = match self.make_request(req).await {
+            Ok(req) => req,
+            Err(BlueskyApiError::BadRequest(err)) if err.error == "RecordNotFound" => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let parse: Result<GetRecordResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = parse {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        Ok(Some(parse.unwrap().value))
+    }
+
     pub async fn delete_record(&mut self, rkey: String, nsid: String) -> Result<BlueskyApiDeleteRecordResponse, BlueskyApiError> {
         let contents = DeleteRecordRequest { repo: self.did.clone(), collection: nsid.to_owned(), rkey };
 
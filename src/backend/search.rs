@@ -0,0 +1,144 @@
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::defs::bsky::{actor::defs::ProfileView, feed::defs::{FeedCursorPair, FeedViewPost, PostView}};
+
+use super::{BlueskyApiError, ClientBackend};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchSort {
+    Top,
+    Latest,
+}
+
+impl SearchSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchSort::Top => "top",
+            SearchSort::Latest => "latest",
+        }
+    }
+}
+
+/// Everything app.bsky.feed.searchPosts can filter on, empty strings/vecs are left out of the request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchPostsParams {
+    /// Lucene query syntax is recommended by the lexicon, whatever the appview does with it
+    pub q: String,
+    pub sort: SearchSort,
+    /// Only posts after this, either a datetime or YYYY-MM-DD
+    pub since: String,
+    /// Only posts before this, either a datetime or YYYY-MM-DD
+    pub until: String,
+    /// Handle or DID
+    pub author: String,
+    /// Language code, like "en"
+    pub lang: String,
+    /// Handle or DID of an account mentioned in the post
+    pub mentions: String,
+    /// Hashtags, without the #. Posts have to have all of them.
+    pub tag: Vec<String>,
+}
+
+impl SearchPostsParams {
+    pub fn new(q: String) -> Self {
+        Self { q, sort: SearchSort::Top, since: String::new(), until: String::new(), author: String::new(), lang: String::new(), mentions: String::new(), tag: Vec::new() }
+    }
+
+    fn to_query(&self) -> Vec<(&'static str, String)> {
+        let mut rtn = vec![("q", self.q.clone()), ("sort", self.sort.as_str().to_owned())];
+        for (key, val) in [("since", &self.since), ("until", &self.until), ("author", &self.author), ("lang", &self.lang), ("mentions", &self.mentions)] {
+            if !val.is_empty() {
+                rtn.push((key, val.clone()));
+            }
+        }
+        for tag in self.tag.iter() {
+            rtn.push(("tag", tag.trim_start_matches('#').to_owned()));
+        }
+        rtn
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchPostsResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hits_total: Option<usize>,
+    pub posts: Vec<Arc<Mutex<PostView>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchActorsResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    pub actors: Vec<Arc<ProfileView>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchActorsTypeaheadResponse {
+    pub actors: Vec<Arc<ProfileView>>,
+}
+
+impl ClientBackend {
+    /// Find posts matching search criteria. Comes back as a feed so it can be shown like any other.
+    pub async fn search_posts(&mut self, params: &SearchPostsParams, cursor: String) -> Result<FeedCursorPair, BlueskyApiError> {
+        let mut query = params.to_query();
+        if !cursor.is_empty() {
+            query.push(("cursor", cursor));
+        }
+        let req = self.client.get(format!("{}/xrpc/app.bsky.feed.searchPosts", self.user_pds)).query(&query);
+        let req = self.make_request(req).await?;
+
+        let res: Result<SearchPostsResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+        let res = res.unwrap();
+
+        let mut feed = Vec::new();
+        for mut post in res.posts {
+            feed.push(FeedViewPost { post: self.deduplicate_post(&mut post), reply: None, reason: None, feed_context: None });
+        }
+
+        Ok(FeedCursorPair { cursor: res.cursor, feed })
+    }
+
+    /// Find actors (profiles) matching search criteria.
+    pub async fn search_actors(&mut self, q: &str, cursor: String) -> Result<(Option<String>, Vec<Arc<ProfileView>>), BlueskyApiError> {
+        let mut query = vec![("q", q.to_owned())];
+        if !cursor.is_empty() {
+            query.push(("cursor", cursor));
+        }
+        let req = self.client.get(format!("{}/xrpc/app.bsky.actor.searchActors", self.user_pds)).query(&query);
+        let req = self.make_request(req).await?;
+
+        let res: Result<SearchActorsResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+        let res = res.unwrap();
+
+        Ok((res.cursor, res.actors))
+    }
+
+    /// Handle completion, for the composer. Accounts we follow go first, the rest stay in the order the appview ranked them.
+    pub async fn search_actors_typeahead(&mut self, q: &str, limit: u32) -> Result<Vec<Arc<ProfileView>>, BlueskyApiError> {
+        let query = vec![("q", q.to_owned()), ("limit", limit.to_string())];
+        let req = self.client.get(format!("{}/xrpc/app.bsky.actor.searchActorsTypeahead", self.user_pds)).query(&query);
+        let req = self.make_request(req).await?;
+
+        let res: Result<SearchActorsTypeaheadResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+        let mut actors = res.unwrap().actors;
+        actors.sort_by_key(|actor| actor.viewer.as_ref().is_none_or(|viewer| viewer.following.is_none()));
+
+        Ok(actors)
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{TimeDelta, Utc};
+use serde::Deserialize;
+
+use crate::defs::{
+    bsky::{
+        embed::AspectRatio,
+        video::defs::{JobState, JobStatus},
+    },
+    Blob,
+};
+
+use super::{BlueskyApiError, BlueskyApiErrorMessage, ClientBackend};
+
+/// Where uploads go, and where the processing jobs get checked on
+const VIDEO_SERVICE: &str = "https://video.bsky.app";
+const JOB_CHECK_INTERVAL: Duration = Duration::from_millis(1500);
+/// About ten minutes of checking before we stop waiting on the service
+const MAX_JOB_CHECKS: u32 = 400;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServiceAuthResponse {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JobStatusResponse {
+    job_status: JobStatus,
+}
+
+/// Uploading a video that was already uploaded comes back as an error, but it still tells us which job has it
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AlreadyExistsResponse {
+    job_id: String,
+}
+
+/// Shared with the composer so it can show how the upload is going
+#[derive(Debug, Clone)]
+pub struct VideoProgress {
+    pub state: String,
+    /// 0.0-1.0
+    pub progress: f32,
+    /// The last we heard about the processing job, sending again picks this up instead of uploading twice
+    pub job: Option<JobStatus>,
+    /// Read out of the file when it got uploaded
+    pub aspect_ratio: Option<AspectRatio>,
+}
+
+impl VideoProgress {
+    pub fn new() -> Self {
+        Self { state: "Uploading".to_owned(), progress: 0.0, job: None, aspect_ratio: None }
+    }
+
+    /// Whether the service is done with it, the result is in `blob`
+    pub fn processed(&self) -> bool {
+        self.job.as_ref().is_some_and(|job| job.state == JobState::Completed)
+    }
+
+    /// The processed video, once the service is done with it
+    pub fn blob(&self) -> Option<&Blob> {
+        self.job.as_ref().filter(|job| job.state == JobState::Completed).and_then(|job| job.blob.as_ref())
+    }
+
+    pub fn update(&mut self, job: JobStatus) {
+        self.progress = job.progress.unwrap_or(0) as f32 / 100.0;
+        self.state = match &job.state {
+            JobState::Completed => "Processed".to_owned(),
+            JobState::Failed => "Failed".to_owned(),
+            JobState::InProgress(state) => match state.as_str() {
+                "JOB_STATE_CREATED" => "Queued".to_owned(),
+                "JOB_STATE_ENCODING" => "Encoding".to_owned(),
+                "JOB_STATE_SCANNING" | "JOB_STATE_SCANNED" => "Scanning".to_owned(),
+                _ => "Processing".to_owned(),
+            },
+        };
+        self.job = Some(job);
+    }
+}
+
+fn video_error(error: &str, message: String) -> BlueskyApiError {
+    BlueskyApiError::BadRequest(BlueskyApiErrorMessage { error: error.to_owned(), message })
+}
+
+async fn job_status(client: &reqwest::Client, job_id: &str) -> Result<JobStatus, BlueskyApiError> {
+    let req = client.get(format!("{}/xrpc/app.bsky.video.getJobStatus", VIDEO_SERVICE)).query(&[("jobId", job_id)]);
+    let req = ClientBackend::send_request(req).await?;
+
+    let res: Result<JobStatusResponse, serde_json::Error> = serde_json::from_str(&req);
+    if let Err(err) = res {
+        return Err(BlueskyApiError::ParseError(err, req));
+    }
+
+    Ok(res.unwrap().job_status)
+}
+
+/// Checks on the job in `progress` until the service is done with it, writing the state in as it goes.
+/// Job status doesn't need the session, so this can run on its own instead of holding up the backend.
+pub async fn wait_for_video(client: reqwest::Client, progress: Arc<Mutex<VideoProgress>>) -> Result<(), BlueskyApiError> {
+    let Some(mut job) = progress.lock().unwrap().job.clone() else {
+        return Err(video_error("VideoProcessingFailed", "The video never got uploaded".to_owned()));
+    };
+    let mut checks = 0;
+    loop {
+        match job.state {
+            JobState::Completed => return Ok(()),
+            JobState::Failed => return Err(video_error("VideoProcessingFailed", job.message.or(job.error).unwrap_or_else(|| "The video service couldn't process this video".to_owned()))),
+            JobState::InProgress(_) if checks >= MAX_JOB_CHECKS => return Err(video_error("VideoProcessingTimeout", "The video service is taking too long with this video, try sending it again later".to_owned())),
+            JobState::InProgress(_) => {}
+        }
+
+        tokio::time::sleep(JOB_CHECK_INTERVAL).await;
+        checks += 1;
+        job = job_status(&client, &job.job_id).await?;
+        progress.lock().unwrap().update(job.clone());
+    }
+}
+
+impl ClientBackend {
+    /// Get a signed token on behalf of the requesting DID for the requested service.
+    pub async fn get_service_auth(&mut self, aud: &str, lxm: &str) -> Result<String, BlueskyApiError> {
+        let exp = (Utc::now() + TimeDelta::minutes(30)).timestamp().to_string();
+        let query = vec![("aud", aud.to_owned()), ("lxm", lxm.to_owned()), ("exp", exp)];
+        let req = self.client.get(format!("{}/xrpc/com.atproto.server.getServiceAuth", self.user_pds)).query(&query);
+        let req = self.make_request(req).await?;
+
+        let res: Result<ServiceAuthResponse, serde_json::Error> = serde_json::from_str(&req);
+        if let Err(err) = res {
+            return Err(BlueskyApiError::ParseError(err, req));
+        }
+
+        Ok(res.unwrap().token)
+    }
+
+    /// Uploads a video to the video service, which processes it and puts the result in our repo.
+    pub async fn upload_video(&mut self, buffer: Vec<u8>, name: &str, mime_type: &str) -> Result<JobStatus, BlueskyApiError> {
+        // the video service writes the blob to our pds for us, so the token is for the pds, not the video service
+        let pds_host = self.user_pds.trim_start_matches("https://").trim_start_matches("http://").trim_end_matches('/');
+        let token = self.get_service_auth(&format!("did:web:{}", pds_host), "com.atproto.repo.uploadBlob").await?;
+
+        let query = vec![("did", self.did.clone()), ("name", name.to_owned())];
+        let req = self.client.post(format!("{}/xrpc/app.bsky.video.uploadVideo", VIDEO_SERVICE)).query(&query).body(buffer).header("content-type", mime_type).bearer_auth(token);
+        let req = Self::send_request(req).await?;
+
+        let res: Result<JobStatusResponse, serde_json::Error> = serde_json::from_str(&req);
+        match res {
+            Ok(res) => Ok(res.job_status),
+            Err(err) => match serde_json::from_str::<AlreadyExistsResponse>(&req) {
+                Ok(existing) => self.get_job_status(&existing.job_id).await,
+                Err(_) => Err(BlueskyApiError::ParseError(err, req)),
+            },
+        }
+    }
+
+    /// Get status details for a video processing job.
+    pub async fn get_job_status(&self, job_id: &str) -> Result<JobStatus, BlueskyApiError> {
+        job_status(&self.client, job_id).await
+    }
+
+    /// For requests that don't need the session, to run off on their own
+    pub fn http_client(&self) -> reqwest::Client {
+        self.client.clone()
+    }
+}
+
+/// Finds the display size of an mp4/mov from its first video track header, swapping for rotated (portrait phone) videos.
+pub fn mp4_aspect_ratio(buffer: &[u8]) -> Option<AspectRatio> {
+    fn boxes(buffer: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            if offset + 8 > buffer.len() {
+                return None;
+            }
+            let size = u32::from_be_bytes(buffer[offset..offset + 4].try_into().unwrap()) as usize;
+            let kind = &buffer[offset + 4..offset + 8];
+            let (header, size) = match size {
+                0 => (8, buffer.len() - offset),
+                1 if offset + 16 <= buffer.len() => (16, u64::from_be_bytes(buffer[offset + 8..offset + 16].try_into().unwrap()) as usize),
+                _ => (8, size),
+            };
+            if size < header || offset + size > buffer.len() {
+                return None;
+            }
+            let body = &buffer[offset + header..offset + size];
+            offset += size;
+            Some((kind, body))
+        })
+    }
+
+    let (_, moov) = boxes(buffer).find(|(kind, _)| *kind == b"moov")?;
+    for (_, trak) in boxes(moov).filter(|(kind, _)| *kind == b"trak") {
+        let Some((_, tkhd)) = boxes(trak).find(|(kind, _)| *kind == b"tkhd") else {
+            continue;
+        };
+        // width and height are the last two 16.16 fixed point numbers, right after the 3x3 matrix
+        if tkhd.len() < 44 {
+            continue;
+        }
+        let end = tkhd.len();
+        let width = u32::from_be_bytes(tkhd[end - 8..end - 4].try_into().unwrap()) >> 16;
+        let height = u32::from_be_bytes(tkhd[end - 4..end].try_into().unwrap()) >> 16;
+        if width == 0 || height == 0 {
+            continue; // audio track
+        }
+        let matrix_a = i32::from_be_bytes(tkhd[end - 44..end - 40].try_into().unwrap());
+        return Some(if matrix_a == 0 { AspectRatio { width: height, height: width } } else { AspectRatio { width, height } });
+    }
+    None
+}
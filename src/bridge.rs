@@ -1,10 +1,10 @@
-use crate::{backend::{
-    main::BlueskyLoginResponse, record::{BlueskyApiCreateRecordResponse, BlueskyApiDeleteRecordResponse, BlueskyApiRecord}, thread::BlueskyApiGetThreadResponse, BlueskyApiError, ClientBackend
-}, defs::{self, bsky::{actor::defs::Preference, embed::{self, AspectRatio}, feed::defs::GeneratorView}, Blob}, settings::Settings};
+use crate::{accounts::{self, Accounts, StoredSession}, backend::{
+    compose::{PendingThread, PostMedia, ThreadCreationResult}, gates::InteractionSettings, link_card::LinkCardState, chat::Conversation, main::{BlueskyLoginResponse, BlueskyLoginResponseError, LoginInformation}, notifications::NotificationList, search::SearchPostsParams, record::{BlueskyApiCreateRecordResponse, BlueskyApiDeleteRecordResponse, BlueskyApiRecord}, thread::BlueskyApiGetThreadResponse, video, BlueskyApiError, BlueskyApiErrorMessage, ClientBackend
+}, defs::bsky::{actor::defs::Preference, feed::{defs::{GeneratorView, ThreadGateView}, Post}, graph::defs::ListView}, lang::matches_languages, settings::Settings};
 use crate::defs::bsky::{actor::defs::ProfileViewDetailed, feed::defs::{FeedCursorPair, PostView}};
 use anyhow::Result;
-use image::GenericImageView;
-use std::{fs::File, io::Read, path::PathBuf, sync::{
+use chrono::Utc;
+use std::{collections::HashMap, sync::{
     mpsc::{Receiver, Sender},
     Arc, Mutex,
 }};
@@ -13,22 +13,77 @@ use crate::frontend::CursorListPair;
 
 pub enum FrontToBackMsg {
     ShutdownMessage,
-    LoginRequestStandard { handle: String, password: String },
-    LoginRequest2FA(String, String, String),
+    /// The provider is their PDS, if they gave one. Otherwise it gets looked up from the handle.
+    LoginRequestStandard { handle: String, password: String, provider: Option<String> },
+    /// Same as the standard one, with the code the PDS emailed them
+    LoginRequest2FA { handle: String, password: String, code: String, provider: Option<String> },
+    /// Handle (optional, it's a hint) and hosting provider. Opens the browser and waits for them there, without holding anything else up.
+    LoginRequestOAuth { handle: String, provider: Option<String> },
+    /// Stops waiting on the browser, if we are
+    CancelOAuthLogin,
+    /// Sent by the bridge to itself once the browser login is done, with the backend it logged in.
+    /// `id` is which login it was, only the latest one counts.
+    OAuthLoginFinished { id: u64, backend: Box<ClientBackend>, response: BlueskyLoginResponse },
+    /// Makes a saved account the active one, logging it in from the keyring if it isn't already
+    SwitchAccountRequest { did: String },
+    /// Forgets an account's session, active or not
+    LogoutRequest { did: String },
 
     GetTimelineRequest { cursor: Option<String>, limit: Option<u32> },
     GetFeedRequest { feed: String, cursor: Option<String>, limit: Option<u32> },
     GetProfileRequest { did: String },
     GetThreadRequest { uri: String },
     GetAuthorFeedRequest { did: String, cursor: String, posts: Arc<Mutex<FeedCursorPair>> },
-    GetFollowersRequest { did: String, profiles: Arc<Mutex<CursorListPair<ProfileView>>> },
+    GetFollowersRequest { did: String, cursor: String, profiles: Arc<Mutex<CursorListPair<ProfileView>>> },
+    GetFollowsRequest { did: String, cursor: String, profiles: Arc<Mutex<CursorListPair<ProfileView>>> },
+    GetLikesRequest { uri: String, cursor: String, profiles: Arc<Mutex<CursorListPair<ProfileView>>> },
+    GetRepostedByRequest { uri: String, cursor: String, profiles: Arc<Mutex<CursorListPair<ProfileView>>> },
+    GetNotificationsRequest { cursor: String, notifications: Arc<Mutex<NotificationList>> },
+    GetUnreadNotificationsRequest,
+    UpdateSeenNotificationsRequest,
 
-    CreateRecordRequest(BlueskyApiRecord),
-    CreateRecordWithMediaRequest(BlueskyApiRecord, Vec<PathBuf>),
+    GetConvosRequest { cursor: String, convos: Arc<Mutex<CursorListPair<Mutex<Conversation>>>> },
+    GetConvoForMemberRequest { did: String },
+    GetMessagesRequest { cursor: String, conversation: Arc<Mutex<Conversation>> },
+    /// Takes the conversation's draft and sends it
+    SendMessageRequest { conversation: Arc<Mutex<Conversation>> },
+    /// Applies new chat events to every loaded conversation, new ones get added to `convos`
+    PollChatLogRequest { convos: Option<Arc<Mutex<CursorListPair<Mutex<Conversation>>>>> },
+    UpdateReadRequest { conversation: Arc<Mutex<Conversation>> },
+    MuteConvoRequest { conversation: Arc<Mutex<Conversation>>, mute: bool },
+    LeaveConvoRequest { conversation: Arc<Mutex<Conversation>> },
+
+    SearchPostsRequest { params: SearchPostsParams, cursor: String, posts: Arc<Mutex<FeedCursorPair>> },
+    GetQuotesRequest { uri: String, cursor: String, posts: Arc<Mutex<FeedCursorPair>> },
+    SearchActorsRequest { query: String, cursor: String, profiles: Arc<Mutex<CursorListPair<ProfileView>>> },
+    /// For the composer's `@` completion, the results get written into the arc along with the query they're for
+    SearchActorsTypeaheadRequest { query: String, limit: u32, results: Arc<Mutex<(String, Vec<Arc<ProfileView>>)>> },
+
+    /// Posts from the composer, sent in order with each one replying to the last
+    /// `author` is the DID of a saved account to post as, the active one if it's `None`
+    /// `ungated` is an already sent post whose interaction settings didn't get written last time
+    /// `draft_id` and `draft_did` are the composer's draft, they come back with the response so it finds its way back
+    CreateThreadRequest { posts: Vec<(Post, PostMedia)>, interaction: InteractionSettings, author: Option<String>, ungated: Option<String>, draft_id: String, draft_did: Option<String> },
     CreateRecordUnderPostRequest(BlueskyApiRecord, Arc<Mutex<PostView>>),
+    /// Looks at a page for the composer, the result gets written into the arc
+    GetLinkCardRequest { uri: String, card: Arc<Mutex<LinkCardState>> },
 
     DeleteRecordRequest { rkey: String, nsid: String },
     DeleteRecordUnderPostRequest { rkey: String, nsid: String, post_mod: Arc<Mutex<PostView>> },
+    CreateRecordUnderProfileRequest(BlueskyApiRecord, Arc<Mutex<ProfileViewDetailed>>),
+    DeleteRecordUnderProfileRequest { rkey: String, nsid: String, profile_mod: Arc<Mutex<ProfileViewDetailed>> },
+
+    /// The profile is optional, posts by them get updated either way
+    MuteActorRequest { did: String, mute: bool, profile_mod: Option<Arc<Mutex<ProfileViewDetailed>>> },
+    /// Takes the uri of the thread's root post
+    MuteThreadRequest { root: String, mute: bool },
+
+    /// Writes the threadgate and postgate for one of our posts, the post gets updated if there is one
+    SetInteractionSettingsRequest { uri: String, settings: InteractionSettings, post_mod: Option<Arc<Mutex<PostView>>> },
+    /// Our curation lists, for picking who can reply. `None` in the arc until they come in
+    GetOwnListsRequest { lists: Arc<Mutex<Option<Vec<ListView>>>> },
+    /// Whether one of our posts has quotes turned off, written into the arc once its postgate's been read
+    GetQuotesDisabledRequest { uri: String, quotes_disabled: Arc<Mutex<Option<bool>>> },
 }
 
 pub enum BackToFrontMsg {
@@ -37,9 +92,145 @@ pub enum BackToFrontMsg {
     TimelineResponse(Result<FeedCursorPair, BlueskyApiError>),
     KeyringFailure(String),
     RecordCreationResponse(Result<BlueskyApiCreateRecordResponse, BlueskyApiError>),
+    /// With the draft of the composer that sent it
+    ThreadCreationResponse { draft_id: String, draft_did: Option<String>, res: ThreadCreationResult },
+    /// A thread's video is done processing (or isn't going to be), with the rest of the thread, who it's posted as and the composer's draft
+    VideoProcessedResponse { draft_id: String, draft_did: Option<String>, result: Result<(), BlueskyApiError>, pending: PendingThread, author: Option<String> },
     RecordDeletionResponse(Result<BlueskyApiDeleteRecordResponse, BlueskyApiError>),
     ProfileResponse(String, Result<ProfileViewDetailed, BlueskyApiError>),
     ThreadResponse(String, Result<BlueskyApiGetThreadResponse, BlueskyApiError>),
+    UnreadNotificationsResponse(Result<usize, BlueskyApiError>),
+    /// For requests that write their results into an `Arc` instead of getting a response, heading and error
+    RequestFailure(String, BlueskyApiError),
+    /// DID of the other member, and their conversation
+    ConvoResponse(String, Result<Arc<Mutex<Conversation>>, BlueskyApiError>),
+    /// DID of the account that got logged out
+    LoggedOut(String),
+    /// DID of the active account, whose session couldn't be refreshed. They'll have to log in again.
+    SessionExpired(String),
+}
+
+/// Puts the session in the keyring under its DID
+fn cache_login(tx: &Sender<BackToFrontMsg>, login: &LoginInformation) -> Result<()> {
+    if let Err(error) = accounts::save_session(login) {
+        tx.send(BackToFrontMsg::KeyringFailure(format!("Error when caching login: {:?}", error)))?;
+    }
+    Ok(())
+}
+
+/// Writes back a refresh token that got rotated while handling a request, and lets the frontend know if the session died
+fn sync_session(api: &mut ClientBackend, tx: &Sender<BackToFrontMsg>) -> Result<()> {
+    if let Some(login) = api.take_refreshed_login() {
+        cache_login(tx, &login)?;
+    }
+    if api.take_session_expired() {
+        tx.send(BackToFrontMsg::SessionExpired(api.did().to_owned()))?;
+    }
+    Ok(())
+}
+
+/// The feeds they've pinned, for the timeline's tabs
+async fn pinned_feeds(api: &mut ClientBackend, tx: &Sender<BackToFrontMsg>) -> Result<Vec<GeneratorView>> {
+    //TODO: USE PREFS!
+    let gen_views: Vec<GeneratorView> = match api.get_preferences().await {
+        Ok(ok) => {
+            let feeds:Vec<String> = {
+                let mut feeds = Vec::new();
+                for pref in ok {
+                    if let Preference::SavedFeedsPrefV2(val) = pref {
+                        for feed in val.items {
+                            if feed.value.starts_with("at") && feed.pinned {
+                                feeds.push(feed.value);    
+                            }
+                        }
+                        break;
+                    }
+                }
+                feeds
+            };
+
+            match api.get_feed_generators(feeds).await {
+                Ok(ok) => ok,
+                Err(err) => {
+                    tx.send(BackToFrontMsg::BackendError(format!("Feed generator pull failed.\n{:?}", err)))?;
+                    Vec::new()
+                },
+            }
+        },
+        Err(err) => {
+            tx.send(BackToFrontMsg::BackendError(format!("Preferences pull failed.\n{:?}", err)))?;
+            Vec::new()
+        },
+    };
+    Ok(gen_views)
+}
+
+/// Caches the session, remembers the account for the switcher and tells the frontend who we are, or passes along why the login didn't go through
+async fn finish_login(api: &mut ClientBackend, tx: &Sender<BackToFrontMsg>, login_response: BlueskyLoginResponse) -> Result<()> {
+    let BlueskyLoginResponse::Success(login_response) = login_response else {
+        tx.send(BackToFrontMsg::LoginResponse(login_response, None, Vec::new()))?;
+        return Ok(());
+    };
+    cache_login(tx, &login_response)?;
+    let profile = match api.get_profile_self().await {
+        Ok(p) => Some(p),
+        Err(_) => None,
+    };
+    let mut accounts = Accounts::load();
+    accounts.remember(&login_response.did, profile.as_ref());
+    accounts.save();
+    let feeds = pinned_feeds(api, tx).await?;
+
+    tx.send(BackToFrontMsg::LoginResponse(BlueskyLoginResponse::Success(login_response), profile, feeds))?;
+    Ok(())
+}
+
+/// Logs a backend in from a session out of the keyring
+async fn login_stored(backend: &mut ClientBackend, session: StoredSession) -> BlueskyLoginResponse {
+    match session.oauth {
+        Some(oauth) => backend.login_oauth_refresh(session.refresh_token, oauth).await,
+        None => backend.login_refresh(session.refresh_token).await,
+    }
+}
+
+/// Logs a backend in as one of the saved accounts
+async fn restore_session(backend: &mut ClientBackend, tx: &Sender<BackToFrontMsg>, did: &str) -> Result<BlueskyLoginResponse> {
+    Ok(match accounts::load_session(did) {
+        Ok(Some(session)) => login_stored(backend, session).await,
+        Ok(None) => BlueskyLoginResponse::Error(BlueskyLoginResponseError::InvalidToken),
+        Err(error) => {
+            tx.send(BackToFrontMsg::KeyringFailure(format!("Failed to read cached login. {:?}", error)))?;
+            BlueskyLoginResponse::Error(BlueskyLoginResponseError::InvalidToken)
+        }
+    })
+}
+
+/// Makes `backend` the active session, putting the old one aside if it's a different account
+fn activate(api: &mut ClientBackend, others: &mut HashMap<String, ClientBackend>, backend: ClientBackend) {
+    let previous = std::mem::replace(api, backend);
+    if !previous.did().is_empty() && previous.did() != api.did() {
+        others.insert(previous.did().to_owned(), previous);
+    }
+}
+
+/// Logs in with a fresh backend, so a failed login (adding an account, say) leaves the active session alone
+async fn fresh_login(api: &mut ClientBackend, others: &mut HashMap<String, ClientBackend>, mut backend: ClientBackend, login_response: BlueskyLoginResponse, tx: &Sender<BackToFrontMsg>) -> Result<()> {
+    if matches!(login_response, BlueskyLoginResponse::Success(_)) {
+        others.remove(backend.did());
+        activate(api, others, backend);
+        finish_login(api, tx, login_response).await
+    } else {
+        finish_login(&mut backend, tx, login_response).await
+    }
+}
+
+/// Drops posts that aren't in any of our content languages, if we've picked any
+fn filter_content_languages(mut page: FeedCursorPair, settings: &Arc<Mutex<Settings>>) -> FeedCursorPair {
+    let wanted = settings.lock().unwrap().content_languages.clone();
+    if !wanted.is_empty() {
+        page.feed.retain(|post| matches_languages(&post.post.lock().unwrap().record.langs, &wanted));
+    }
+    page
 }
 
 pub struct Bridge {
@@ -55,10 +246,11 @@ impl Bridge {
         let ctx_burn = ctx.clone();
         let working_indicator = Arc::new(tokio::sync::Mutex::new(false));
         let indicator_burn = working_indicator.clone();
+        let commander_burn = backend_commander.clone();
         tokio::task::spawn(async move {
             //let die_fallback_transmittter = backend_responder.clone();
             //panic::set_hook(Box::new( |_| {}));
-            let result = Self::run(backend_listener, frontend_commander, ctx_burn, settings, indicator_burn).await;
+            let result = Self::run(backend_listener, commander_burn, frontend_commander, ctx_burn, settings, indicator_burn).await;
             if let Err(result) = result {
                 panic!("Bridge failed! {}", result);
             }
@@ -67,71 +259,38 @@ impl Bridge {
         Self { frontend_listener, backend_commander, working_indicator }
     }
 
-    async fn run(rx: Receiver<FrontToBackMsg>, tx: Sender<BackToFrontMsg>, ctx: egui::Context, _settings: Arc<Mutex<Settings>>, working_indicator: Arc<tokio::sync::Mutex<bool>>) -> Result<()> {
+    /// `commander` is for handing results of things that ran on their own back to the loop
+    async fn run(rx: Receiver<FrontToBackMsg>, commander: Sender<FrontToBackMsg>, tx: Sender<BackToFrontMsg>, ctx: egui::Context, settings: Arc<Mutex<Settings>>, working_indicator: Arc<tokio::sync::Mutex<bool>>) -> Result<()> {
         let mut api = ClientBackend::new();
 
-        let vault = keyring::Entry::new("com.headassbtw.metro.bluesky", "refreshJwt");
-        if let Ok(vault) = vault {
-            if let Ok(token) = vault.get_password() {
-                'gaming: {
-                let login_response = api.login_refresh(token).await;
-                let login_response = if let BlueskyLoginResponse::Success(inf) = login_response {
-                    inf
-                } else {
-                    tx.send(BackToFrontMsg::LoginResponse(login_response, None, Vec::new()))?;
-                    break 'gaming;
-                };
-                if let Ok(vault) = keyring::Entry::new("com.headassbtw.metro.bluesky", "refreshJwt") {
-                    if let Err(error) = vault.set_password(&login_response.refresh_token) {
-                        tx.send(BackToFrontMsg::KeyringFailure(format!("Error when caching login: {:?}", error)))?;
-                    }
-                }
-                let profile = match api.get_profile_self().await {
-                    Ok(p) => Some(p),
-                    Err(_) => None,
-                };
-                //TODO: USE PREFS!
-                let gen_views: Vec<GeneratorView> = match api.get_preferences().await {
-                    Ok(ok) => {
-                        let feeds:Vec<String> = {
-                            let mut feeds = Vec::new();
-                            for pref in ok {
-                                if let Preference::SavedFeedsPrefV2(val) = pref {
-                                    for feed in val.items {
-                                        if feed.value.starts_with("at") && feed.pinned {
-                                            feeds.push(feed.value);    
-                                        }
-                                    }
-                                    break;
-                                }
-                            }
-                            feeds
-                        };
-
-                        match api.get_feed_generators(feeds).await {
-                            Ok(ok) => ok,
-                            Err(err) => {
-                                tx.send(BackToFrontMsg::BackendError(format!("Feed generator pull failed.\n{:?}", err)))?;
-                                Vec::new()
-                            },
-                        }
-                    },
-                    Err(err) => {
-                        tx.send(BackToFrontMsg::BackendError(format!("Preferences pull failed.\n{:?}", err)))?;
-                        Vec::new()
-                    },
-                };
+        // accounts that are logged in but not the active one, by DID
+        let mut others: HashMap<String, ClientBackend> = HashMap::new();
+
+        // the browser login while we're waiting on it, and how many have been started so each gets its own id
+        let mut oauth_login: Option<(u64, tokio::task::AbortHandle)> = None;
+        let mut oauth_logins: u64 = 0;
 
-                
-                
-                tx.send(BackToFrontMsg::LoginResponse(BlueskyLoginResponse::Success(login_response), profile, gen_views))?;
+        let login_response = match Accounts::load().active {
+            Some(did) => Some(restore_session(&mut api, &tx, &did).await?),
+            // from before there could be more than one account
+            None => match accounts::legacy_session() {
+                Ok(Some(session)) => Some(login_stored(&mut api, session).await),
+                Ok(None) => None,
+                Err(error) => {
+                    tx.send(BackToFrontMsg::KeyringFailure(format!("Failed to initialize keyring. {:?}", error)))?;
+                    None
+                }
+            },
+        };
+        match login_response {
+            Some(login_response) => {
+                let migrated = matches!(login_response, BlueskyLoginResponse::Success(_));
+                finish_login(&mut api, &tx, login_response).await?;
+                if migrated {
+                    accounts::delete_legacy_session();
                 }
-            } else {
-                tx.send(BackToFrontMsg::LoginResponse(BlueskyLoginResponse::Info(crate::backend::main::BlueskyLoginResponseInfo::WasntLoggedIn), None, Vec::new()))?;
             }
-        } else {
-            tx.send(BackToFrontMsg::LoginResponse(BlueskyLoginResponse::Info(crate::backend::main::BlueskyLoginResponseInfo::WasntLoggedIn), None, Vec::new()))?;
-            tx.send(BackToFrontMsg::KeyringFailure(format!("Failed to initialize keyring. {:?}", vault.err().unwrap())))?;
+            None => tx.send(BackToFrontMsg::LoginResponse(BlueskyLoginResponse::Info(crate::backend::main::BlueskyLoginResponseInfo::WasntLoggedIn), None, Vec::new()))?,
         }
 
         ctx.request_repaint();
@@ -146,32 +305,66 @@ impl Bridge {
 
             match request? {
                 FrontToBackMsg::ShutdownMessage => {drop(working); break 'outer},
-                FrontToBackMsg::LoginRequestStandard { handle, password } => {
-                    let login_response = api.login(handle, password).await;
-                    let login_response = if let BlueskyLoginResponse::Success(inf) = login_response {
-                        inf
-                    } else {
-                        tx.send(BackToFrontMsg::LoginResponse(login_response, None, Vec::new()))?;
-                        continue;
-                    };
-                    if let Ok(vault) = keyring::Entry::new("com.headassbtw.metro.bluesky", "refreshJwt") {
-                        if let Err(error) = vault.set_password(&login_response.refresh_token) {
-                            tx.send(BackToFrontMsg::KeyringFailure(format!("Error when caching login: {:?}", error)))?;
-                        }
+                FrontToBackMsg::LoginRequestStandard { handle, password, provider } => {
+                    let mut backend = ClientBackend::new();
+                    let login_response = backend.login(handle, password, provider).await;
+                    fresh_login(&mut api, &mut others, backend, login_response, &tx).await?;
+                }
+                FrontToBackMsg::LoginRequest2FA { handle, password, code, provider } => {
+                    let mut backend = ClientBackend::new();
+                    let login_response = backend.login_2fa(handle, password, code, provider).await;
+                    fresh_login(&mut api, &mut others, backend, login_response, &tx).await?;
+                }
+                FrontToBackMsg::LoginRequestOAuth { handle, provider } => {
+                    // they could take minutes in the browser, so it waits on its own and comes back as OAuthLoginFinished
+                    oauth_logins += 1;
+                    let (id, commander) = (oauth_logins, commander.clone());
+                    let task = tokio::task::spawn(async move {
+                        let mut backend = ClientBackend::new();
+                        let response = backend.login_oauth(handle, provider).await;
+                        let _ = commander.send(FrontToBackMsg::OAuthLoginFinished { id, backend: Box::new(backend), response });
+                    });
+                    if let Some((_, previous)) = oauth_login.replace((id, task.abort_handle())) {
+                        previous.abort();
                     }
-                    let profile = match api.get_profile_self().await {
-                        Ok(p) => Some(p),
-                        Err(_) => None,
-                    };
-
-                    tx.send(BackToFrontMsg::LoginResponse(BlueskyLoginResponse::Success(login_response), profile, Vec::new()))?;
                 }
-                FrontToBackMsg::LoginRequest2FA(_, _, _) => todo!(),
+                FrontToBackMsg::CancelOAuthLogin => {
+                    if let Some((_, task)) = oauth_login.take() {
+                        task.abort();
+                    }
+                }
+                FrontToBackMsg::OAuthLoginFinished { id, backend, response } => {
+                    // it could have finished right as they cancelled, or started another
+                    if oauth_login.as_ref().is_some_and(|(current, _)| *current == id) {
+                        oauth_login = None;
+                        fresh_login(&mut api, &mut others, *backend, response, &tx).await?;
+                    }
+                }
+                FrontToBackMsg::SwitchAccountRequest { did } => {
+                    let mut backend = others.remove(&did).unwrap_or_else(ClientBackend::new);
+                    let login_response = if backend.did().is_empty() { restore_session(&mut backend, &tx, &did).await? } else { BlueskyLoginResponse::Success(backend.login_information()) };
+                    fresh_login(&mut api, &mut others, backend, login_response, &tx).await?;
+                }
+                FrontToBackMsg::LogoutRequest { did } => {
+                    others.remove(&did);
+                    if api.did() == did {
+                        api = ClientBackend::new();
+                    }
+                    if let Err(error) = accounts::delete_session(&did) {
+                        tx.send(BackToFrontMsg::KeyringFailure(format!("Error when removing cached login: {:?}", error)))?;
+                    }
+                    let mut accounts = Accounts::load();
+                    accounts.forget(&did);
+                    accounts.save();
+                    tx.send(BackToFrontMsg::LoggedOut(did))?;
+                }
                 FrontToBackMsg::GetTimelineRequest { cursor, limit } => {
-                    tx.send(BackToFrontMsg::TimelineResponse(api.get_timeline(cursor, limit).await))?;
+                    let res = api.get_timeline(cursor, limit).await.map(|page| filter_content_languages(page, &settings));
+                    tx.send(BackToFrontMsg::TimelineResponse(res))?;
                 }
                 FrontToBackMsg::GetFeedRequest { feed, cursor, .. } => {
-                    tx.send(BackToFrontMsg::TimelineResponse(api.get_feed(feed, cursor).await))?;
+                    let res = api.get_feed(feed, cursor).await.map(|page| filter_content_languages(page, &settings));
+                    tx.send(BackToFrontMsg::TimelineResponse(res))?;
                 }
                 FrontToBackMsg::GetProfileRequest { did } => {
                     tx.send(BackToFrontMsg::ProfileResponse(did.clone(), api.get_profile(did).await))?;
@@ -193,76 +386,212 @@ impl Bridge {
                         poasts.feed.append(&mut res.feed);
                     }
                 }
-                FrontToBackMsg::GetFollowersRequest { did, profiles } => {
-                    let cursor = {
+                FrontToBackMsg::GetFollowersRequest { did, cursor, profiles } => match api.get_followers(did, cursor).await {
+                    Ok((cursor, mut followers)) => {
+                        let mut profiles = profiles.lock().unwrap();
+                        profiles.cursor = cursor;
+                        profiles.items.append(&mut followers);
+                    }
+                    Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to get followers".into(), err))?,
+                },
+                FrontToBackMsg::GetFollowsRequest { did, cursor, profiles } => match api.get_follows(did, cursor).await {
+                    Ok((cursor, mut follows)) => {
+                        let mut profiles = profiles.lock().unwrap();
+                        profiles.cursor = cursor;
+                        profiles.items.append(&mut follows);
+                    }
+                    Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to get follows".into(), err))?,
+                },
+                FrontToBackMsg::GetLikesRequest { uri, cursor, profiles } => match api.get_likes(uri, cursor).await {
+                    Ok((cursor, mut likes)) => {
                         let mut profiles = profiles.lock().unwrap();
-                        let cursor = profiles.cursor.clone();
-                        profiles.cursor = None;
-                        cursor.unwrap_or(String::new())
+                        profiles.cursor = cursor;
+                        profiles.items.append(&mut likes);
+                    }
+                    Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to get likes".into(), err))?,
+                },
+                FrontToBackMsg::GetRepostedByRequest { uri, cursor, profiles } => match api.get_reposted_by(uri, cursor).await {
+                    Ok((cursor, mut reposts)) => {
+                        let mut profiles = profiles.lock().unwrap();
+                        profiles.cursor = cursor;
+                        profiles.items.append(&mut reposts);
+                    }
+                    Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to get reposts".into(), err))?,
+                },
+                FrontToBackMsg::GetNotificationsRequest { cursor, notifications } => 'notifs: {
+                    let res = match api.list_notifications(Some(cursor), None).await {
+                        Ok(res) => res,
+                        Err(err) => {
+                            tx.send(BackToFrontMsg::RequestFailure("Failed to get notifications".into(), err))?;
+                            break 'notifs;
+                        }
                     };
 
-                    let (new_cursor, mut followers) = api.get_followers(did, cursor).await.unwrap();
+                    let mut uris: Vec<String> = Vec::new();
+                    for notification in res.notifications.iter() {
+                        if let Some(uri) = notification.subject_uri() && !uris.contains(uri) {
+                            uris.push(uri.clone());
+                        }
+                    }
 
-                    {
-                        let mut profiles = profiles.lock().unwrap();
-                        profiles.cursor = Some(new_cursor);
-                        profiles.items.append(&mut followers);
+                    let mut posts = HashMap::new();
+                    match api.get_posts(uris).await {
+                        Ok(res) => for post in res {
+                            let uri = post.lock().unwrap().uri.clone();
+                            posts.insert(uri, post);
+                        },
+                        // still show the notifications themselves, just without the posts
+                        Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to get notification posts".into(), err))?,
+                    }
+
+                    let mut notifications = notifications.lock().unwrap();
+                    notifications.cursor = res.cursor;
+                    notifications.append(res.notifications, &posts);
+                }
+                FrontToBackMsg::GetUnreadNotificationsRequest => {
+                    tx.send(BackToFrontMsg::UnreadNotificationsResponse(api.get_unread_count().await))?;
+                }
+                FrontToBackMsg::UpdateSeenNotificationsRequest => {
+                    match api.update_seen(Utc::now()).await {
+                        Ok(_) => tx.send(BackToFrontMsg::UnreadNotificationsResponse(Ok(0)))?,
+                        Err(err) => tx.send(BackToFrontMsg::UnreadNotificationsResponse(Err(err)))?,
                     }
                 }
 
-                FrontToBackMsg::CreateRecordRequest(record) => {
-                    tx.send(BackToFrontMsg::RecordCreationResponse(api.create_record(record).await))?;
-                }
-                // TODO: FIX THIS LATER lmao, it doesn't support alt text n whatnot
-                FrontToBackMsg::CreateRecordWithMediaRequest(record, images) => 'give_up: {
-                    let mut blobs: Vec<(Blob, Option<AspectRatio>)> = Vec::new();
-                    for image in images {
-                        let mut file = File::open(&image).expect("no file found");
-                        let metadata = std::fs::metadata(&image).expect("unable to read metadata");
-                        let mut buffer = vec![0; metadata.len() as usize];
-                        file.read(&mut buffer).expect("buffer overflow");
-
-                        //THIS FUCKING SUCKS!
-                        let img = image::load_from_memory(&buffer);
-                        let ratio = match img {
-                            Ok(img) => {
-                                Some(AspectRatio {
-                                    width: img.dimensions().0.clone(),
-                                    height: img.dimensions().1.clone(),
-                                })
-                            },
-                            Err(err) => {
-                                println!("failed to open image: {}", err);
-                                None
+                FrontToBackMsg::GetConvosRequest { cursor, convos } => {
+                    match api.list_convos(cursor).await {
+                        Ok((cursor, mut new)) => {
+                            let mut convos = convos.lock().unwrap();
+                            convos.cursor = cursor;
+                            convos.items.append(&mut new);
+                        }
+                        Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to get conversations".into(), err))?,
+                    }
+                }
+                FrontToBackMsg::GetConvoForMemberRequest { did } => {
+                    tx.send(BackToFrontMsg::ConvoResponse(did.clone(), api.get_convo_for_member(&did).await))?;
+                }
+                FrontToBackMsg::GetMessagesRequest { cursor, conversation } => {
+                    if let Err(err) = api.get_messages(&conversation, cursor).await {
+                        tx.send(BackToFrontMsg::RequestFailure("Failed to get messages".into(), err))?;
+                    }
+                }
+                FrontToBackMsg::SendMessageRequest { conversation } => {
+                    let text = conversation.lock().unwrap().draft.clone();
+                    let res = api.send_message(&conversation, text.clone()).await;
+                    let mut conversation = conversation.lock().unwrap();
+                    conversation.sending = false;
+                    match res {
+                        // only what went out, anything typed since stays
+                        Ok(_) => {
+                            if let Some(rest) = conversation.draft.strip_prefix(&text) {
+                                conversation.draft = rest.to_owned();
                             }
-                        };
+                        }
+                        Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to send message".into(), err))?,
+                    }
+                }
+                FrontToBackMsg::PollChatLogRequest { convos } => {
+                    if let Err(err) = api.poll_chat_log(convos).await {
+                        // polled, so don't pester the user about it
+                        println!("Failed to poll chat log: {:?}", err);
+                    }
+                }
+                FrontToBackMsg::UpdateReadRequest { conversation } => {
+                    if let Err(err) = api.update_read(&conversation, None).await {
+                        println!("Failed to mark conversation read: {:?}", err);
+                    }
+                }
+                FrontToBackMsg::MuteConvoRequest { conversation, mute } => {
+                    if let Err(err) = api.mute_convo(&conversation, mute).await {
+                        tx.send(BackToFrontMsg::RequestFailure(if mute { "Failed to mute conversation".into() } else { "Failed to unmute conversation".into() }, err))?;
+                    }
+                }
+                FrontToBackMsg::LeaveConvoRequest { conversation } => {
+                    if let Err(err) = api.leave_convo(&conversation).await {
+                        tx.send(BackToFrontMsg::RequestFailure("Failed to leave conversation".into(), err))?;
+                    }
+                }
 
-                        match api.upload_blob(buffer).await {
-                            Ok(res) => blobs.push((res, ratio)),
-                            Err(err) => {
-                                tx.send(BackToFrontMsg::RecordCreationResponse(Err(err)))?;
-                                break 'give_up;
-                            }
+                FrontToBackMsg::SearchPostsRequest { params, cursor, posts } => {
+                    match api.search_posts(&params, cursor).await {
+                        Ok(mut res) => {
+                            let mut posts = posts.lock().unwrap();
+                            posts.cursor = res.cursor;
+                            posts.feed.append(&mut res.feed);
+                        }
+                        Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to search posts".into(), err))?,
+                    }
+                }
+                FrontToBackMsg::GetQuotesRequest { uri, cursor, posts } => {
+                    match api.get_quotes(uri, cursor).await {
+                        Ok(mut res) => {
+                            let mut posts = posts.lock().unwrap();
+                            posts.cursor = res.cursor;
+                            posts.feed.append(&mut res.feed);
+                        }
+                        Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to get quotes".into(), err))?,
+                    }
+                }
+                FrontToBackMsg::SearchActorsRequest { query, cursor, profiles } => {
+                    match api.search_actors(&query, cursor).await {
+                        Ok((cursor, mut actors)) => {
+                            let mut profiles = profiles.lock().unwrap();
+                            profiles.cursor = cursor;
+                            profiles.items.append(&mut actors);
                         }
+                        Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to search people".into(), err))?,
                     }
-                    let mut record = record;
-                    match record {
-                        BlueskyApiRecord::Post(ref mut post) => {
-                            post.embed = Some(embed::Variant::ImagesRaw { images: {
-                                let mut vec = Vec::new();
-                                for (blob, ratio) in blobs {
-                                    vec.push(defs::bsky::embed::images::Image {
-                                        image: blob,
-                                        alt: String::new(),
-                                        aspect_ratio: ratio,
-                                    });
+                }
+                FrontToBackMsg::SearchActorsTypeaheadRequest { query, limit, results } => match api.search_actors_typeahead(&query, limit).await {
+                    Ok(actors) => *results.lock().unwrap() = (query, actors),
+                    // the suggestions just don't show up, not worth a popup mid-sentence
+                    Err(err) => println!("Typeahead for {} failed: {:?}", query, err),
+                },
+
+                FrontToBackMsg::CreateThreadRequest { posts, interaction, author, ungated, draft_id, draft_did } => {
+                    let mut res = match author.clone().filter(|author| author != api.did()) {
+                        None => api.create_thread(posts, &interaction, ungated).await,
+                        // posting as one of the other accounts, which gets logged in on the side if it isn't yet
+                        Some(author) => {
+                            let mut backend = others.remove(&author).unwrap_or_else(ClientBackend::new);
+                            let login_response = if backend.did().is_empty() { restore_session(&mut backend, &tx, &author).await? } else { BlueskyLoginResponse::Success(backend.login_information()) };
+                            let res = match login_response {
+                                BlueskyLoginResponse::Success(login) => {
+                                    cache_login(&tx, &login)?;
+                                    backend.create_thread(posts, &interaction, ungated).await
                                 }
-                                vec
-                            } }.into())
-                        },
-                        _ => todo!(),
+                                _ => ThreadCreationResult { sent: 0, reply: None, error: Some(BlueskyApiError::Unauthorized(BlueskyApiErrorMessage { error: "InvalidToken".into(), message: format!("Couldn't log in as {}, switch to it to log in again", author) })), ungated, pending: None },
+                            };
+                            if let Some(login) = backend.take_refreshed_login() {
+                                cache_login(&tx, &login)?;
+                            }
+                            if !backend.did().is_empty() && !backend.take_session_expired() {
+                                others.insert(author, backend);
+                            }
+                            res
+                        }
+                    };
+                    // processing can take minutes, so it gets waited on out of the way and the frontend sends the rest once it's done
+                    if let Some(pending) = res.pending.take() {
+                        let (client, tx, ctx, draft_id, draft_did) = (api.http_client(), tx.clone(), ctx.clone(), draft_id.clone(), draft_did.clone());
+                        tokio::task::spawn(async move {
+                            let result = video::wait_for_video(client, pending.progress.clone()).await;
+                            let _ = tx.send(BackToFrontMsg::VideoProcessedResponse { draft_id, draft_did, result, pending, author });
+                            ctx.request_repaint();
+                        });
                     }
-                    tx.send(BackToFrontMsg::RecordCreationResponse(api.create_record(record).await))?;
+                    tx.send(BackToFrontMsg::ThreadCreationResponse { draft_id, draft_did, res })?;
+                }
+                FrontToBackMsg::GetLinkCardRequest { uri, card } => {
+                    // a page without a card isn't worth bothering anyone over
+                    *card.lock().unwrap() = match api.get_link_card(&uri).await {
+                        Ok(res) => LinkCardState::Loaded(res),
+                        Err(err) => {
+                            println!("Couldn't get a link card for {}: {:?}", uri, err);
+                            LinkCardState::Failed
+                        }
+                    };
                 }
                 FrontToBackMsg::CreateRecordUnderPostRequest(record, post_mod) => match record {
                     BlueskyApiRecord::Like(record) => match api.create_record(BlueskyApiRecord::Like(record)).await {
@@ -291,8 +620,12 @@ impl Bridge {
                     },
                     _ => tx.send(BackToFrontMsg::RecordCreationResponse(Err(BlueskyApiError::NotImplemented)))?
                 },
-                FrontToBackMsg::DeleteRecordRequest { .. } => {
-                    tx.send(BackToFrontMsg::RecordDeletionResponse(Err(BlueskyApiError::NotImplemented)))?;
+                FrontToBackMsg::DeleteRecordRequest { rkey, nsid } => {
+                    let res = api.delete_record(rkey.clone(), nsid.clone()).await;
+                    if res.is_ok() && nsid == "app.bsky.feed.post" {
+                        api.mark_post_deleted(&rkey);
+                    }
+                    tx.send(BackToFrontMsg::RecordDeletionResponse(res))?;
                 }
                 FrontToBackMsg::DeleteRecordUnderPostRequest{ rkey, nsid, post_mod } => {
                     println!("deleting {}", rkey);
@@ -323,7 +656,99 @@ impl Bridge {
                         Err(err) => tx.send(BackToFrontMsg::RecordDeletionResponse(Err(err)))?,
                     }
                 }
+                FrontToBackMsg::CreateRecordUnderProfileRequest(record, profile_mod) => match record {
+                    BlueskyApiRecord::Follow(record) => match api.create_record(BlueskyApiRecord::Follow(record)).await {
+                        Ok(res) => {
+                            let mut profile = profile_mod.lock().unwrap();
+                            if let Some(viewer) = &mut profile.viewer {
+                                viewer.following = Some(res.uri);
+                            }
+                            if let Some(count) = &mut profile.followers_count {
+                                *count += 1;
+                            }
+                        }
+                        Err(err) => tx.send(BackToFrontMsg::RecordCreationResponse(Err(err)))?,
+                    },
+                    BlueskyApiRecord::Block(record) => match api.create_record(BlueskyApiRecord::Block(record)).await {
+                        Ok(res) => {
+                            let mut profile = profile_mod.lock().unwrap();
+                            if let Some(viewer) = &mut profile.viewer {
+                                viewer.blocking = Some(res.uri);
+                            }
+                        }
+                        Err(err) => tx.send(BackToFrontMsg::RecordCreationResponse(Err(err)))?,
+                    },
+                    _ => tx.send(BackToFrontMsg::RecordCreationResponse(Err(BlueskyApiError::NotImplemented)))?
+                },
+                FrontToBackMsg::DeleteRecordUnderProfileRequest{ rkey, nsid, profile_mod } => {
+                    match api.delete_record(rkey, nsid.clone()).await {
+                        Ok(_) => match nsid.as_str() {
+                            "app.bsky.graph.follow" => {
+                                let mut profile = profile_mod.lock().unwrap();
+                                if let Some(viewer) = &mut profile.viewer {
+                                    viewer.following = None;
+                                }
+                                if let Some(count) = &mut profile.followers_count {
+                                    *count = count.saturating_sub(1);
+                                }
+                            }
+                            "app.bsky.graph.block" => {
+                                let mut profile = profile_mod.lock().unwrap();
+                                if let Some(viewer) = &mut profile.viewer {
+                                    viewer.blocking = None;
+                                }
+                            }
+                            _ => {
+                                tx.send(BackToFrontMsg::RecordDeletionResponse(Err(BlueskyApiError::NotImplemented)))?;
+                            }
+                        },
+                        Err(err) => tx.send(BackToFrontMsg::RecordDeletionResponse(Err(err)))?,
+                    }
+                }
+                FrontToBackMsg::MuteActorRequest { did, mute, profile_mod } => match api.mute_actor(&did, mute).await {
+                    Ok(_) => {
+                        if let Some(profile_mod) = profile_mod
+                            && let Some(viewer) = &mut profile_mod.lock().unwrap().viewer
+                        {
+                            viewer.muted = Some(mute);
+                        }
+                    }
+                    Err(err) => tx.send(BackToFrontMsg::RequestFailure(if mute { "Failed to mute account" } else { "Failed to unmute account" }.into(), err))?,
+                },
+                FrontToBackMsg::MuteThreadRequest { root, mute } => {
+                    if let Err(err) = api.mute_thread(&root, mute).await {
+                        tx.send(BackToFrontMsg::RequestFailure(if mute { "Failed to mute thread" } else { "Failed to unmute thread" }.into(), err))?;
+                    }
+                }
+
+                FrontToBackMsg::SetInteractionSettingsRequest { uri, settings, post_mod } => match api.set_interaction_settings(&uri, &settings).await {
+                    Ok(threadgate) => {
+                        if let Some(post_mod) = post_mod {
+                            let mut post = post_mod.lock().unwrap();
+                            post.threadgate = threadgate.map(|gate| ThreadGateView { uri: None, cid: None, record: Some(gate), lists: None });
+                            if let Some(viewer) = &mut post.viewer {
+                                viewer.embedding_disabled = Some(settings.quotes_disabled);
+                            }
+                        }
+                    }
+                    Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to update who can interact with your post".into(), err))?,
+                },
+                FrontToBackMsg::GetOwnListsRequest { lists } => {
+                    *lists.lock().unwrap() = Some(match api.get_own_lists().await {
+                        Ok(res) => res,
+                        Err(err) => {
+                            // the picker just goes without lists
+                            println!("Couldn't get our lists: {:?}", err);
+                            Vec::new()
+                        }
+                    });
+                }
+                FrontToBackMsg::GetQuotesDisabledRequest { uri, quotes_disabled } => match api.get_quotes_disabled(&uri).await {
+                    Ok(res) => *quotes_disabled.lock().unwrap() = Some(res),
+                    Err(err) => tx.send(BackToFrontMsg::RequestFailure("Failed to get who can quote your post".into(), err))?,
+                },
             }
+            sync_session(&mut api, &tx)?;
             // if we processed anhything, we want the frontend to do it as well, this is the closest to doing that we can get.
             // i COULD probably do something with mutexes but that's janky and my dog is making it very annoying to write code.
             ctx.request_repaint();
@@ -9,3 +9,14 @@ pub struct View {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thumb: Option<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// What actually goes in a post record, a link card with its thumbnail already uploaded.
+pub struct External {
+    pub uri: String,
+    pub title: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb: Option<crate::defs::Blob>,
+}
@@ -24,11 +24,11 @@ pub enum Variant {
     #[serde(rename = "app.bsky.embed.images")]
     ImagesRaw { images: Vec<images::Image> },
     #[serde(rename = "app.bsky.embed.video")]
-    VideoRaw(serde_json::Value),
+    VideoRaw(video::Video),
     #[serde(rename = "app.bsky.embed.external")]
-    ExternalRaw(serde_json::Value),
+    ExternalRaw { external: external::External },
     #[serde(rename = "app.bsky.embed.record")]
-    RecordRaw(serde_json::Value),
+    RecordRaw(record::RecordRef),
     #[serde(rename = "app.bsky.embed.recordWithMedia")]
     RecordWithMediaRaw(record_with_media::RecordWithMediaRaw),
 }
@@ -26,6 +26,13 @@ pub enum Variant {
     PackView(StarterPackViewBasic),
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// What actually goes in a post record, a reference to the quoted record.
+pub struct RecordRef {
+    pub record: crate::defs::bsky::feed::StrongRef,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Record {
@@ -28,4 +28,20 @@ pub struct RecordWithMedia {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct RecordWithMediaRaw(serde_json::Value);
\ No newline at end of file
+#[serde(tag = "$type")]
+pub enum MediaRawVariant {
+	#[serde(rename = "app.bsky.embed.images")]
+	Images { images: Vec<crate::defs::bsky::embed::images::Image> },
+	#[serde(rename = "app.bsky.embed.video")]
+	Video(crate::defs::bsky::embed::video::Video),
+	#[serde(rename = "app.bsky.embed.external")]
+	External { external: crate::defs::bsky::embed::external::External },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// What actually goes in a post record, quoting a record alongside some media.
+pub struct RecordWithMediaRaw {
+	pub record: crate::defs::bsky::embed::record::RecordRef,
+	pub media: MediaRawVariant,
+}
\ No newline at end of file
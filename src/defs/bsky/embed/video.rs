@@ -13,3 +13,26 @@ pub struct View {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aspect_ratio: Option<super::AspectRatio>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Caption {
+    /// BCP-47 language code
+    pub lang: String,
+    /// A WebVTT file
+    pub file: crate::defs::Blob,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// What actually goes in a post record, the uploaded video and everything describing it.
+pub struct Video {
+    /// The mp4 that came back from the video service
+    pub video: crate::defs::Blob,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub captions: Option<Vec<Caption>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<super::AspectRatio>,
+}
@@ -31,6 +31,10 @@ pub struct PostView {
     pub labels: Option<Vec<serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub threadgate: Option<ThreadGateView>,
+
+    /// Not part of the lexicon, set when we delete it so every view holding it stops showing it
+    #[serde(skip)]
+    pub deleted: bool,
 }
 
 impl PostView {
@@ -46,7 +46,7 @@ pub struct Post {
     pub tags: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "$type")]
 pub enum ThreatGateAllow {
@@ -79,4 +79,27 @@ pub struct ThreadGate {
     /// List of hidden reply URIs.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hidden_replies: Option<Vec<String>>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "$type")]
+pub enum PostGateEmbeddingRule {
+    /// Disables embedding of this post.
+    #[serde(rename = "app.bsky.feed.postgate#disableRule")]
+    Disable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Record defining interaction rules for a post. The record key (rkey) of the postgate record must match the record key of the post, and that record must be in the same repository.
+pub struct PostGate {
+    pub created_at: DateTime<Utc>,
+    /// Reference (AT-URI) to the post record.
+    pub post: String,
+    /// List of AT-URIs embedding this post that the author has detached from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detached_embedding_uris: Option<Vec<String>>,
+    /// List of rules defining who can embed this post. If value is an empty array or is undefined, no particular rules apply and anyone can embed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_rules: Option<Vec<PostGateEmbeddingRule>>,
+}
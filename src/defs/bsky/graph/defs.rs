@@ -1,10 +1,37 @@
 use serde::{self, Deserialize, Serialize};
 
+/// A curation list, for threadgates and feeds of its members
+pub const CURATELIST: &str = "app.bsky.graph.defs#curatelist";
+
 #[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
-pub struct ListViewBasic(serde_json::Value);
+#[serde(rename_all = "camelCase")]
+pub struct ListViewBasic {
+    pub uri: String,
+    pub cid: String,
+    pub name: String,
+    /// `app.bsky.graph.defs#modlist`, `#curatelist` or `#referencelist`
+    pub purpose: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_item_count: Option<u32>,
+}
 
 #[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
-pub struct ListView(serde_json::Value);
+#[serde(rename_all = "camelCase")]
+pub struct ListView {
+    pub uri: String,
+    pub cid: String,
+    pub name: String,
+    /// `app.bsky.graph.defs#modlist`, `#curatelist` or `#referencelist`
+    pub purpose: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_item_count: Option<u32>,
+}
 
 #[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
 pub struct StarterPackViewBasic(serde_json::Value);
@@ -1 +1,22 @@
 pub mod defs;
+
+use chrono::{DateTime, Utc};
+use serde::{self, Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// Record declaring a social 'follow' relationship of another account. Duplicate follows will be ignored by the AppView.
+pub struct Follow {
+    /// DID of the account being followed
+    pub subject: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// Record declaring a 'block' relationship against another account. NOTE: blocks are public in Bluesky; see blog posts for details.
+pub struct Block {
+    /// DID of the account to be blocked.
+    pub subject: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{self, Deserialize, Serialize};
+
+use crate::defs::bsky::actor::defs::ProfileView;
+
+/// Expected values are 'like', 'repost', 'follow', 'mention', 'reply', 'quote', and 'starterpack-joined'.
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Reason {
+    Like,
+    Repost,
+    Follow,
+    Mention,
+    Reply,
+    Quote,
+    StarterpackJoined,
+    /// New reasons get added server-side every so often, don't fail the whole page over them.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Technically `app.bsky.notification.listNotifications#notification`, there's no shared defs for it.
+#[derive(std::fmt::Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub uri: String,
+    pub cid: String,
+    pub author: ProfileView,
+    pub reason: Reason,
+    /// The post that was liked/reposted/quoted, if applicable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason_subject: Option<String>,
+    pub record: serde_json::Value,
+    pub is_read: bool,
+    pub indexed_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<serde_json::Value>>,
+}
+
+impl Notification {
+    /// The URI of the post this notification should show, if it has one.
+    /// Likes and reposts point at our post, replies/mentions/quotes are the post itself.
+    pub fn subject_uri(&self) -> Option<&String> {
+        match self.reason {
+            Reason::Like | Reason::Repost => self.reason_subject.as_ref(),
+            Reason::Mention | Reason::Reply | Reason::Quote => Some(&self.uri),
+            _ => None,
+        }
+    }
+}
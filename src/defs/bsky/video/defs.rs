@@ -0,0 +1,30 @@
+use serde::{self, Deserialize, Serialize};
+
+/// Where a video processing job is at. The video service has sent other values than the lexicon lists, so it's kept open.
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum JobState {
+    #[serde(rename = "JOB_STATE_COMPLETED")]
+    Completed,
+    #[serde(rename = "JOB_STATE_FAILED")]
+    Failed,
+    #[serde(untagged)]
+    InProgress(String),
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatus {
+    pub job_id: String,
+    pub did: String,
+    pub state: JobState,
+    /// Progress within the current processing state, 0-100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<u8>,
+    /// Only there once the job is done
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob: Option<crate::defs::Blob>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
@@ -0,0 +1,36 @@
+use serde::{self, Deserialize, Serialize};
+
+use crate::defs::bsky::actor::defs::{ProfileAssociated, ViewerState};
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileViewBasic {
+    pub did: String,
+    pub handle: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub associated: Option<ProfileAssociated>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viewer: Option<ViewerState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<serde_json::Value>>,
+    /// Set when the actor cannot actively participate in converations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chat_disabled: Option<bool>,
+}
+
+impl ProfileViewBasic {
+    /// Same as the app.bsky one, display name if it's there and not empty, handle otherwise.
+    pub fn easy_name(&self) -> &str {
+        if let Some(dn) = &self.display_name
+            && !dn.is_empty()
+        {
+            return dn;
+        }
+        &self.handle
+    }
+}
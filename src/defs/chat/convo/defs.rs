@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use serde::{self, Deserialize, Serialize};
+
+use crate::defs::{bsky::richtext::Facet, chat::actor::defs::ProfileViewBasic};
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageRef {
+    pub did: String,
+    pub convo_id: String,
+    pub message_id: String,
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageInput {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets: Option<Vec<Facet>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed: Option<serde_json::Value>,
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageViewSender {
+    pub did: String,
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageView {
+    pub id: String,
+    pub rev: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets: Option<Vec<Facet>>,
+    /// Only ever app.bsky.embed.record#view, left untyped until we render those in messages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed: Option<serde_json::Value>,
+    pub sender: MessageViewSender,
+    pub sent_at: DateTime<Utc>,
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedMessageView {
+    pub id: String,
+    pub rev: String,
+    pub sender: MessageViewSender,
+    pub sent_at: DateTime<Utc>,
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "$type")]
+pub enum MessageVariant {
+    #[serde(rename = "chat.bsky.convo.defs#messageView")]
+    Message(MessageView),
+    #[serde(rename = "chat.bsky.convo.defs#deletedMessageView")]
+    Deleted(DeletedMessageView),
+    #[serde(other)]
+    Unknown,
+}
+
+impl MessageVariant {
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            MessageVariant::Message(message) => Some(&message.id),
+            MessageVariant::Deleted(message) => Some(&message.id),
+            MessageVariant::Unknown => None,
+        }
+    }
+
+    pub fn sender(&self) -> Option<&str> {
+        match self {
+            MessageVariant::Message(message) => Some(&message.sender.did),
+            MessageVariant::Deleted(message) => Some(&message.sender.did),
+            MessageVariant::Unknown => None,
+        }
+    }
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConvoStatus {
+    Request,
+    Accepted,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvoView {
+    pub id: String,
+    pub rev: String,
+    pub members: Vec<ProfileViewBasic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_message: Option<MessageVariant>,
+    pub muted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ConvoStatus>,
+    pub unread_count: usize,
+}
+
+impl ConvoView {
+    /// Everyone in the conversation but `you`, which for now is always one person.
+    pub fn others<'a>(&'a self, you: &'a str) -> impl Iterator<Item = &'a ProfileViewBasic> {
+        self.members.iter().filter(move |member| member.did != you)
+    }
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogBeginConvo {
+    pub rev: String,
+    pub convo_id: String,
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogMessage {
+    pub rev: String,
+    pub convo_id: String,
+    pub message: MessageVariant,
+}
+
+#[derive(std::fmt::Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "$type")]
+pub enum LogVariant {
+    #[serde(rename = "chat.bsky.convo.defs#logBeginConvo")]
+    BeginConvo(LogBeginConvo),
+    #[serde(rename = "chat.bsky.convo.defs#logAcceptConvo")]
+    AcceptConvo(LogBeginConvo),
+    #[serde(rename = "chat.bsky.convo.defs#logLeaveConvo")]
+    LeaveConvo(LogBeginConvo),
+    #[serde(rename = "chat.bsky.convo.defs#logMuteConvo")]
+    MuteConvo(LogBeginConvo),
+    #[serde(rename = "chat.bsky.convo.defs#logUnmuteConvo")]
+    UnmuteConvo(LogBeginConvo),
+    #[serde(rename = "chat.bsky.convo.defs#logCreateMessage")]
+    CreateMessage(LogMessage),
+    #[serde(rename = "chat.bsky.convo.defs#logDeleteMessage")]
+    DeleteMessage(LogMessage),
+    #[serde(rename = "chat.bsky.convo.defs#logReadMessage")]
+    ReadMessage(LogMessage),
+    /// Reactions and whatever else gets added, we don't do anything with these yet
+    #[serde(other)]
+    Unknown,
+}
@@ -1 +1,2 @@
-
+pub mod actor;
+pub mod convo;
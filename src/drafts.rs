@@ -0,0 +1,133 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::blob::{ImageAttachment, VideoAttachment},
+    defs::bsky::feed::{defs::PostView, ReplyRef, StrongRef},
+};
+
+/// One post of a saved thread. Link cards aren't kept, they get looked up again when it's opened.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DraftPost {
+    pub text: String,
+    pub images: Vec<ImageAttachment>,
+    pub video: Option<VideoAttachment>,
+}
+
+/// Whatever's in the composer, saved as it gets typed so closing it (or the app) doesn't lose anything
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Draft {
+    /// Also the file name
+    pub id: String,
+    pub updated_at: DateTime<Utc>,
+    pub posts: Vec<DraftPost>,
+    pub reply: Option<ReplyRef>,
+    pub quote: Option<StrongRef>,
+    /// Kept loose, so a post view that won't read back in only costs the preview and not the whole draft
+    pub quote_preview: Option<serde_json::Value>,
+}
+
+/// Each account gets its own folder, by DID
+fn drafts_dir(did: &str) -> Option<PathBuf> {
+    let proj_dirs = ProjectDirs::from("dev", "headassbtw", "com.headassbtw.metro.bluesky")?;
+    // colons don't go in windows paths
+    let dir = proj_dirs.data_local_dir().join("drafts").join(did.replace(':', "_"));
+    if !dir.exists()
+        && let Err(err) = fs::create_dir_all(&dir)
+    {
+        println!("Could not create drafts folder: {}", err);
+        return None;
+    }
+    Some(dir)
+}
+
+impl Draft {
+    /// Millisecond timestamps, nobody's opening two composers in the same one
+    pub fn new_id() -> String {
+        Utc::now().timestamp_millis().to_string()
+    }
+
+    /// Nothing worth keeping around
+    pub fn is_empty(&self) -> bool {
+        self.posts.iter().all(|post| post.text.trim().is_empty() && post.images.is_empty() && post.video.is_none())
+    }
+
+    /// Whether saving `other` over this would change anything
+    pub fn same_content(&self, other: &Draft) -> bool {
+        self.posts == other.posts && self.reply == other.reply && self.quote == other.quote
+    }
+
+    pub fn quote_preview(&self) -> Option<PostView> {
+        self.quote_preview.clone().and_then(|preview| serde_json::from_value(preview).ok())
+    }
+
+    /// Writes it out with `did`'s drafts, or gets rid of it if there's nothing in it
+    pub fn save(&self, did: &str) {
+        if self.is_empty() {
+            Draft::delete(did, &self.id);
+            return;
+        }
+        let Some(dir) = drafts_dir(did) else { return };
+        let res = serde_json::to_string(self).map_err(|err| err.to_string()).and_then(|json| fs::write(dir.join(format!("{}.json", self.id)), json).map_err(|err| err.to_string()));
+        if let Err(err) = res {
+            println!("Failed to save draft {}: {}", self.id, err);
+        }
+    }
+
+    pub fn delete(did: &str, id: &str) {
+        let Some(dir) = drafts_dir(did) else { return };
+        let path = dir.join(format!("{}.json", id));
+        if path.exists()
+            && let Err(err) = fs::remove_file(&path)
+        {
+            println!("Failed to delete draft {}: {}", id, err);
+        }
+    }
+
+    pub fn load(did: &str, id: &str) -> Option<Draft> {
+        let dir = drafts_dir(did)?;
+        let json = fs::read_to_string(dir.join(format!("{}.json", id))).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Takes the first `sent` posts out of a saved draft, for when its composer closed before they finished sending.
+    /// Whatever's left carries on from `reply` like it would in the composer, so opening it again doesn't post anything twice.
+    pub fn trim_sent(did: &str, id: &str, sent: usize, reply: Option<ReplyRef>) {
+        if sent == 0 {
+            return;
+        }
+        let Some(mut draft) = Draft::load(did, id) else { return };
+        draft.posts.drain(..sent.min(draft.posts.len()));
+        draft.reply = reply;
+        draft.quote = None;
+        draft.quote_preview = None;
+        draft.updated_at = Utc::now();
+        draft.save(did);
+    }
+
+    /// Every draft `did` has saved, newest first. Ones that don't parse get skipped, not deleted.
+    pub fn list(did: &str) -> Vec<Draft> {
+        let Some(dir) = drafts_dir(did) else { return Vec::new() };
+        let Ok(entries) = fs::read_dir(&dir) else { return Vec::new() };
+
+        let mut rtn: Vec<Draft> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let json = fs::read_to_string(entry.path()).ok()?;
+                match serde_json::from_str(&json) {
+                    Ok(draft) => Some(draft),
+                    Err(err) => {
+                        println!("Failed to read draft {}: {}", entry.path().display(), err);
+                        None
+                    }
+                }
+            })
+            .collect();
+        rtn.sort_by_key(|draft| std::cmp::Reverse(draft.updated_at));
+        rtn
+    }
+}
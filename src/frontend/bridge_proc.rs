@@ -1,9 +1,17 @@
+use std::sync::{Arc, Mutex};
+
 use crate::{
-    backend::main::{BlueskyLoginResponseError, BlueskyLoginResponseInfo},
+    accounts::Accounts,
+    backend::{
+        gates::InteractionSettings,
+        main::{BlueskyLoginResponseError, BlueskyLoginResponseInfo},
+    },
+    drafts::Draft,
     frontend::{
+        flyouts::composer::ComposerPost,
         main::{ClientFrontend, ClientFrontendFlyoutVariant, ClientFrontendModalVariant},
         pages::{timeline::FrontendTimelineView, FrontendMainView},
-        modals::login::LoginModal
+        modals::{interaction_settings::InteractionSettingsEditor, login::LoginModal}
     },
 };
 
@@ -16,17 +24,28 @@ impl ClientFrontend {
                     self.info_modal("Backend Error", &err);
                 }
                 crate::bridge::BackToFrontMsg::LoginResponse(bluesky_login_response, profile, feeds) => {
-                    self.profile = profile;
                     match bluesky_login_response {
-                        crate::backend::main::BlueskyLoginResponse::Success(_) => {
+                        crate::backend::main::BlueskyLoginResponse::Success(info) => {
+                            // switching back to an account picks up where it was
+                            let restored = self.switch_account(&info.did);
+                            self.profile = profile;
                             self.active = true;
                             self.authenticated = true;
-                            self.view_stack.set(FrontendMainView::Timeline(FrontendTimelineView::new(feeds)));
+                            if !restored {
+                                self.view_stack.set(FrontendMainView::Timeline(FrontendTimelineView::new(feeds)));
+                            }
                             self.modal.close();
                         }
                         crate::backend::main::BlueskyLoginResponse::Info(variant) => match variant {
                             BlueskyLoginResponseInfo::WasntLoggedIn => self.active = true,
-                            BlueskyLoginResponseInfo::TwoFactorTokenRequired => self.info_modal("Login Error", "Your account has two-factor authenticaiton enabled. This is currently not supported."),
+                            BlueskyLoginResponseInfo::TwoFactorTokenRequired => {
+                                if let Some(ClientFrontendModalVariant::LoginModal(login_modal)) = &mut self.modal.main {
+                                    // asking again without a code sends a fresh one
+                                    login_modal.error_msg = if login_modal.two_factor { "A new code has been sent to your email.".into() } else { String::new() };
+                                    login_modal.two_factor = true;
+                                    login_modal.code = String::new();
+                                }
+                            }
                         },
                         crate::backend::main::BlueskyLoginResponse::Error(variant) => match variant {
                             BlueskyLoginResponseError::Generic(reason) => self.info_modal("Generic Backend Error", &reason),
@@ -34,13 +53,23 @@ impl ClientFrontend {
                             BlueskyLoginResponseError::InvalidRequest => self.info_modal("Invalid Request", ""),
                             BlueskyLoginResponseError::ExpiredToken |
                             BlueskyLoginResponseError::InvalidToken => {
-                                self.modal.set(ClientFrontendModalVariant::LoginModal(LoginModal {
-                                    username: String::new(),
-                                    password: String::new(),
-                                    password_dots: true,
-                                    error_msg:  "Cached login was invalid or expired. Plese log in again.".into(),
-                                    interactive: true,
-                                }));
+                                // mid-login, that's the emailed code and not our cached session
+                                if let Some(ClientFrontendModalVariant::LoginModal(login_modal)) = &mut self.modal.main
+                                    && login_modal.two_factor
+                                {
+                                    login_modal.error_msg = if matches!(variant, BlueskyLoginResponseError::ExpiredToken) { "That code has expired. Clear it and log in again to get a new one.".into() } else { "That code is incorrect.".into() };
+                                } else {
+                                    self.modal.set(ClientFrontendModalVariant::LoginModal(LoginModal {
+                                        username: String::new(),
+                                        password: String::new(),
+                                        password_dots: true,
+                                        provider: String::new(),
+                                        error_msg:  "Cached login was invalid or expired. Plese log in again.".into(),
+                                        interactive: true,
+                                        two_factor: false,
+                                        code: String::new(),
+                                    }));
+                                }
                             },
                             BlueskyLoginResponseError::AccountTakenDown => self.info_modal("Account Taken Down", ""),
                             BlueskyLoginResponseError::AccountSuspended => self.info_modal("Account Suspended", ""),
@@ -85,29 +114,95 @@ impl ClientFrontend {
                     }
                     Err(err) => self.error_modal("Failed to get timeline", err),
                 },
+                crate::bridge::BackToFrontMsg::LoggedOut(did) => {
+                    self.inactive_accounts.remove(&did);
+                    self.account_settings.remove(&did);
+                    if did == self.active_did {
+                        self.active_did.clear();
+                        // carry on as whoever's left, if anyone
+                        if let Some(next) = Accounts::load().accounts.first() {
+                            self.backend.backend_commander.send(crate::bridge::FrontToBackMsg::SwitchAccountRequest { did: next.did.clone() }).unwrap();
+                        } else {
+                            self.authenticated = false;
+                            self.profile = None;
+                            self.unread_notifications = 0;
+                            self.view_stack.set(FrontendMainView::Login());
+                        }
+                    }
+                }
+                crate::bridge::BackToFrontMsg::SessionExpired(did) => {
+                    if did == self.active_did && !matches!(self.modal.main, Some(ClientFrontendModalVariant::LoginModal(_))) {
+                        let mut login_modal = LoginModal::new();
+                        if let Some(profile) = &self.profile {
+                            login_modal.username = profile.handle.clone();
+                        }
+                        login_modal.error_msg = "Your session expired. Please log in again.".into();
+                        self.modal.set(ClientFrontendModalVariant::LoginModal(login_modal));
+                    }
+                }
                 crate::bridge::BackToFrontMsg::KeyringFailure(reason) => self.info_modal("OS Keyring Failure", &reason),
-                crate::bridge::BackToFrontMsg::RecordCreationResponse(data) => match data {
-                    Ok(_) => {
-                        if let Some(flyout) = &mut self.flyout.main {
-                            match flyout {
-                                ClientFrontendFlyoutVariant::PostComposerFlyout(flyout) => {
-                                    flyout.draft = String::new();
-                                    flyout.sending = false;
-                                    self.flyout.close();
+                crate::bridge::BackToFrontMsg::RecordCreationResponse(data) => {
+                    if let Err(err) = data {
+                        self.error_modal("Failed to create record", err)
+                    }
+                }
+                crate::bridge::BackToFrontMsg::ThreadCreationResponse { draft_id, draft_did, res } => {
+                    match &mut self.flyout.main {
+                        Some(ClientFrontendFlyoutVariant::PostComposerFlyout(flyout)) if flyout.draft_id == draft_id => {
+                            flyout.posts.drain(..res.sent.min(flyout.posts.len()));
+                            // so the draft on disk loses what went out too
+                            flyout.edited = true;
+                            // nothing went wrong but there's posts left, so they're waiting on a video, and come back in VideoProcessedResponse
+                            let waiting = res.error.is_none() && !flyout.posts.is_empty();
+                            if !waiting {
+                                flyout.sending = false;
+                                flyout.video_progress = None;
+                            }
+                            flyout.ungated = res.ungated.clone();
+                            if flyout.posts.is_empty() {
+                                if let Some(did) = &flyout.draft_did {
+                                    Draft::delete(did, &flyout.draft_id);
+                                }
+                                // it still draws while it slides away
+                                flyout.posts.push(ComposerPost::new());
+                                self.flyout.close();
+                            } else if res.sent > 0 {
+                                // if we fail partway, don't make the user re-type the rest (ask me how i know).
+                                // it carries on from the last post that went out, which already has the quote and the threadgate
+                                flyout.reply = res.reply.clone();
+                                flyout.quote = None;
+                                flyout.quote_preview = None;
+                                flyout.active = 0;
+                                // unless the threadgate is what didn't go on, then it's kept to give it another go when they send again
+                                let root_ungated = flyout.ungated.as_ref().is_some_and(|uri| flyout.reply.as_ref().is_some_and(|reply| reply.root.uri == *uri));
+                                if !root_ungated {
+                                    let quotes_disabled = flyout.interaction.settings.quotes_disabled;
+                                    flyout.interaction = InteractionSettingsEditor::new(InteractionSettings { reply_allow: None, quotes_disabled }, false);
                                 }
                             }
                         }
+                        // the composer that sent it is gone, so its saved draft shouldn't keep what already went out
+                        _ => {
+                            if let Some(did) = &draft_did {
+                                Draft::trim_sent(did, &draft_id, res.sent, res.reply.clone());
+                            }
+                        }
+                    }
+                    if let Some(err) = res.error {
+                        let heading = if res.ungated.is_some() { "Posted, but couldn't apply who can reply and quote" } else { "Failed to send post" };
+                        self.error_modal(heading, err);
                     }
+                }
+                crate::bridge::BackToFrontMsg::VideoProcessedResponse { draft_id, draft_did, result, pending, author } => match result {
+                    Ok(()) => self.backend.backend_commander.send(crate::bridge::FrontToBackMsg::CreateThreadRequest { posts: pending.posts, interaction: pending.interaction, author, ungated: None, draft_id, draft_did }).unwrap(),
                     Err(err) => {
-                        // if we fail a send, don't make the user re-type it (ask me how i know)
-                        if let Some(flyout) = &mut self.flyout.main {
-                            match flyout {
-                                ClientFrontendFlyoutVariant::PostComposerFlyout(flyout) => {
-                                    flyout.sending = false;
-                                }
-                            }
+                        if let Some(ClientFrontendFlyoutVariant::PostComposerFlyout(flyout)) = &mut self.flyout.main
+                            && flyout.draft_id == draft_id
+                        {
+                            flyout.sending = false;
+                            flyout.video_progress = None;
                         }
-                        self.error_modal("Failed to create record", err)
+                        self.error_modal("Failed to post video", err);
                     }
                 },
                 crate::bridge::BackToFrontMsg::ProfileResponse(id, profile) => {
@@ -117,7 +212,7 @@ impl ClientFrontend {
                                 if data.id_cmp == id {
                                     match profile {
                                         Ok(profile) => {
-                                            data.profile_data = Some(profile);
+                                            data.profile_data = Some(Arc::new(Mutex::new(profile)));
                                             data.loading = false;
                                         }
                                         Err(err) => self.error_modal("Failed to get profile", err),
@@ -133,6 +228,27 @@ impl ClientFrontend {
                         self.error_modal("Failed to delete record", err)
                     }
                 }
+                crate::bridge::BackToFrontMsg::UnreadNotificationsResponse(res) => match res {
+                    Ok(count) => self.unread_notifications = count,
+                    // this gets polled, a modal every minute would be obnoxious
+                    Err(err) => println!("Failed to get unread notifications: {:?}", err),
+                },
+                crate::bridge::BackToFrontMsg::RequestFailure(heading, err) => self.error_modal(&heading, err),
+                crate::bridge::BackToFrontMsg::ConvoResponse(did, res) => {
+                    if let Some(page) = self.view_stack.top() {
+                        match page {
+                            FrontendMainView::Chat(data) => {
+                                if data.id_cmp == did {
+                                    match res {
+                                        Ok(conversation) => data.conversation = Some(conversation),
+                                        Err(err) => self.error_modal("Failed to open conversation", err),
+                                    }
+                                }
+                            }
+                            _ => println!("bridge target missed"),
+                        }
+                    }
+                }
                 crate::bridge::BackToFrontMsg::ThreadResponse(uri, res) => {
                     if let Some(page) = self.view_stack.top() {
                         match page {
@@ -0,0 +1,68 @@
+use egui::{Layout, Ui, Visuals};
+
+use crate::{
+    accounts::Accounts,
+    bridge::{Bridge, FrontToBackMsg},
+    frontend::{
+        main::{ClientFrontendFlyoutVariant, ClientFrontendModal, ClientFrontendModalVariant},
+        modals::login::LoginModal,
+    },
+    image::ImageCache,
+};
+
+use super::composer::render_mini_profile;
+
+/// The account switcher, opened from "You"
+pub struct AccountsFlyout {
+    accounts: Accounts,
+    /// Set when they ask for their profile, the frontend opens it since the flyout can't get at the pages
+    pub open_profile: Option<String>,
+}
+
+impl AccountsFlyout {
+    pub fn new() -> Self {
+        Self { accounts: Accounts::load(), open_profile: None }
+    }
+}
+
+impl ClientFrontendFlyoutVariant {
+    /// Gives back whether the flyout should close
+    pub fn accounts(ui: &mut Ui, data: &mut AccountsFlyout, image: &ImageCache, backend: &Bridge, modal: &mut ClientFrontendModal) -> bool {
+        *ui.visuals_mut() = Visuals::light();
+        let mut close = false;
+        let mut forget: Option<String> = None;
+
+        for account in &data.accounts.accounts {
+            let active = data.accounts.active.as_ref() == Some(&account.did);
+            ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+                render_mini_profile(ui, image, &account.avatar, &account.display_name, &account.handle);
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(if active { "Log Out" } else { "Remove" }).clicked() {
+                        backend.backend_commander.send(FrontToBackMsg::LogoutRequest { did: account.did.clone() }).unwrap();
+                        forget = Some(account.did.clone());
+                        close = active;
+                    }
+                    if active {
+                        if ui.button("Profile").clicked() {
+                            data.open_profile = Some(account.did.clone());
+                        }
+                    } else if ui.button("Switch").clicked() {
+                        backend.backend_commander.send(FrontToBackMsg::SwitchAccountRequest { did: account.did.clone() }).unwrap();
+                        close = true;
+                    }
+                });
+            });
+        }
+        if let Some(did) = forget {
+            data.accounts.forget(&did);
+        }
+
+        ui.separator();
+        ui.weak("Each account keeps its own pages and settings. Switching back picks up where you left off.");
+        if ui.button("Add Account").clicked() {
+            modal.set(ClientFrontendModalVariant::LoginModal(LoginModal::new()));
+            close = true;
+        }
+        close
+    }
+}
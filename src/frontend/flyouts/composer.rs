@@ -1,48 +1,294 @@
-use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 use chrono::Utc;
 use egui::{pos2, vec2, Align2, Color32, FontId, Layout, Rect, Rounding, TextEdit, TextStyle, Ui, Visuals};
 use rfd::FileDialog;
 
 use crate::{
-    backend::record::BlueskyApiRecord,
+    accounts::{Accounts, SavedAccount},
+    backend::{
+        blob::{ImageAttachment, VideoAttachment},
+        gates::InteractionSettings,
+        compose::PostMedia,
+        link_card::LinkCardState,
+        record::{link_detector, tag_detector},
+        video::VideoProgress,
+    },
     bridge::Bridge,
+    drafts::{Draft, DraftPost},
     defs::bsky::{
-        actor::defs::ProfileViewDetailed,
-        feed::{self, ReplyRef},
+        actor::defs::{ProfileView, ProfileViewDetailed},
+        embed::{self, record::RecordRef},
+        feed::{self, defs::PostView, ReplyRef, StrongRef},
+        richtext::Feature,
     },
-    frontend::{circle_button, main::ClientFrontendFlyoutVariant},
+    frontend::{circle_button, main::ClientFrontendFlyoutVariant, modals::interaction_settings::InteractionSettingsEditor, viewers::offset_time},
     image::ImageCache,
+    lang::{detect_language, language_name, LANGUAGES, MAX_POST_LANGUAGES},
+    settings::Settings,
     widgets::spinner::SegoeBootSpinner,
     BSKY_BLUE,
 };
 
-pub struct ComposerFlyout {
+/// One post of the thread being written
+pub struct ComposerPost {
     pub draft: String,
+    images: Vec<ImageAttachment>,
+    /// Which image's alt text is being edited, if any
+    editing_alt: Option<usize>,
+    /// Can't go alongside images
+    video: Option<VideoAttachment>,
+    /// The first link in the draft and when it last changed, the card waits for it to settle
+    link_candidate: Option<(String, f64)>,
+    /// Which link the card is for. Stays set when the card gets removed, so it doesn't come right back
+    link_card_uri: Option<String>,
+    link_card: Option<Arc<Mutex<LinkCardState>>>,
+}
+
+pub struct ComposerFlyout {
+    /// Never empty. Everything after the first gets sent as a reply to the one before it.
+    pub posts: Vec<ComposerPost>,
+    /// The post the attachment buttons, emoji picker and suggestions go to
+    pub active: usize,
     pub sending: bool,
     emoji_picker: bool,
     emoji_search: String,
-    images: Vec<PathBuf>,
+    /// Only there while a post with a video is sending
+    pub video_progress: Option<Arc<Mutex<VideoProgress>>>,
+    /// What the first post replies to
     pub reply: Option<ReplyRef>,
+    /// Goes on the first post
+    pub quote: Option<StrongRef>,
+    /// The quoted post, just for showing what's being quoted
+    pub quote_preview: Option<Arc<Mutex<PostView>>>,
+    /// The `@` or `#` word at the caret and when it last changed, handle lookups wait for it to settle
+    completion: Option<(CompletionToken, f64)>,
+    /// Which suggestion the arrow keys are on
+    completion_selected: usize,
+    /// Escape puts the suggestions away until the caret moves to another word, this is where that word starts
+    completion_dismissed: Option<usize>,
+    /// What the last handle lookup was for, so the same one doesn't go out every frame
+    typeahead_query: String,
+    /// Written by the backend, along with the query it's for
+    typeahead: Arc<Mutex<(String, Vec<Arc<ProfileView>>)>>,
+    /// Who can reply and quote, written out once the post is
+    pub interaction: InteractionSettingsEditor,
+    /// A post that went out last time without its interaction settings, they get written before the rest is sent
+    pub ungated: Option<String>,
+    interaction_open: bool,
+    languages_open: bool,
+    /// Where this gets autosaved to
+    pub draft_id: String,
+    /// Whose drafts it goes with, the account that was active when it was opened
+    pub draft_did: Option<String>,
+    /// What's on disk, so it only gets written when something changes
+    saved: Option<Draft>,
+    /// Something in the draft changed this frame, so it's worth checking against what's saved
+    pub edited: bool,
+    /// The drafts list, loaded when it gets opened
+    drafts: Option<Vec<Draft>>,
+    /// One of the other saved accounts to post as, the active one when it's `None`
+    pub author: Option<SavedAccount>,
+    /// The saved accounts, loaded the first time the composer's drawn
+    accounts: Option<Vec<SavedAccount>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompletionKind {
+    Mention,
+    Tag,
+}
+
+/// A word being completed, by char index like egui's cursors. `start` is the `@` or `#`, `end` is the caret.
+#[derive(Debug, Clone, PartialEq)]
+struct CompletionToken {
+    kind: CompletionKind,
+    start: usize,
+    end: usize,
+    query: String,
+}
+
+enum Suggestion {
+    Actor(Arc<ProfileView>),
+    Tag(String),
+}
+
+impl Suggestion {
+    fn text(&self) -> String {
+        match self {
+            Suggestion::Actor(actor) => format!("@{}", actor.handle),
+            Suggestion::Tag(tag) => format!("#{}", tag),
+        }
+    }
+}
+
+/// Finds the `@handle` or `#tag` the caret is at the end of. Same rules as the detectors for where one can start.
+fn completion_token(text: &str, caret: usize) -> Option<CompletionToken> {
+    let chars: Vec<char> = text.chars().collect();
+    if caret > chars.len() || chars.get(caret).is_some_and(|chr| !chr.is_whitespace()) {
+        return None;
+    }
+
+    let start = chars[..caret].iter().rposition(|chr| chr.is_whitespace() || *chr == '@' || *chr == '#')?;
+    let kind = match chars[start] {
+        '@' => CompletionKind::Mention,
+        '#' => CompletionKind::Tag,
+        _ => return None,
+    };
+    if start > 0 && !chars[start - 1].is_whitespace() && !(kind == CompletionKind::Mention && chars[start - 1] == '(') {
+        return None;
+    }
+
+    let query: String = chars[start + 1..caret].iter().collect();
+    if kind == CompletionKind::Mention && !query.chars().all(|chr| chr.is_ascii_alphanumeric() || chr == '.' || chr == '-') {
+        return None;
+    }
+    Some(CompletionToken { kind, start, end: caret, query })
+}
+
+/// What the official app limits alt text to
+const MAX_ALT_LENGTH: usize = 2000;
+/// Seconds a link has to sit still before we go look at it, so half-typed URLs don't get fetched
+const LINK_CARD_DELAY: f64 = 1.0;
+/// Seconds between keystrokes before we ask for matching handles
+const TYPEAHEAD_DELAY: f64 = 0.3;
+const MAX_SUGGESTIONS: usize = 8;
+/// How many of our own hashtags to remember for completion
+const MAX_RECENT_TAGS: usize = 50;
+
+impl ComposerPost {
+    pub fn new() -> Self {
+        Self { draft: String::new(), images: Vec::new(), editing_alt: None, video: None, link_candidate: None, link_card_uri: None, link_card: None }
+    }
+
+    fn too_long(&self) -> bool {
+        self.draft.len() > 300
+    }
+
+    fn missing_alt(&self) -> bool {
+        self.images.iter().any(|image| image.alt.trim().is_empty())
+    }
+
+    /// What goes in the embed slot. Images and videos win over the card.
+    fn media(&self, progress: &Arc<Mutex<VideoProgress>>) -> PostMedia {
+        if let Some(video) = &self.video {
+            return PostMedia::Video(video.clone(), progress.clone());
+        }
+        if !self.images.is_empty() {
+            return PostMedia::Images(self.images.clone());
+        }
+        match self.link_card.as_ref().map(|card| card.lock().unwrap().clone()) {
+            Some(LinkCardState::Loaded(card)) => PostMedia::LinkCard(card),
+            _ => PostMedia::None,
+        }
+    }
 }
 
 impl ComposerFlyout {
     pub fn new() -> Self {
-        Self { draft: String::new(), sending: false, emoji_picker: false, emoji_search: String::new(), images: Vec::new(), reply: None }
+        Self { posts: vec![ComposerPost::new()], active: 0, sending: false, emoji_picker: false, emoji_search: String::new(), video_progress: None, reply: None, quote: None, quote_preview: None, completion: None, completion_selected: 0, completion_dismissed: None, typeahead_query: String::new(), typeahead: Arc::new(Mutex::new((String::new(), Vec::new()))), interaction: InteractionSettingsEditor::new(InteractionSettings::default(), true), ungated: None, interaction_open: false, languages_open: false, draft_id: Draft::new_id(), draft_did: None, saved: None, edited: false, drafts: None, author: None, accounts: None }
     }
 
     pub fn with_reply(reply: ReplyRef) -> Self {
-        Self { draft: String::new(), sending: false, emoji_picker: false, emoji_search: String::new(), images: Vec::new(), reply: Some(reply) }
+        Self { reply: Some(reply), interaction: InteractionSettingsEditor::new(InteractionSettings::default(), false), ..Self::new() }
+    }
+
+    pub fn with_quote(post: Arc<Mutex<PostView>>) -> Self {
+        let quote = {
+            let post = post.lock().unwrap();
+            StrongRef { uri: post.uri.clone(), cid: post.cid.clone() }
+        };
+        Self { quote: Some(quote), quote_preview: Some(post), ..Self::new() }
+    }
+
+    /// Picks a saved draft back up, it keeps saving to the same file
+    pub fn from_draft(draft: Draft) -> Self {
+        let mut posts: Vec<ComposerPost> = draft.posts.iter().cloned().map(|post| ComposerPost { draft: post.text, images: post.images, video: post.video, ..ComposerPost::new() }).collect();
+        if posts.is_empty() {
+            posts.push(ComposerPost::new());
+        }
+        let quote_preview = draft.quote_preview().map(|post| Arc::new(Mutex::new(post)));
+        let interaction = InteractionSettingsEditor::new(InteractionSettings::default(), draft.reply.is_none());
+        Self { posts, reply: draft.reply.clone(), quote: draft.quote.clone(), quote_preview, interaction, draft_id: draft.id.clone(), saved: Some(draft), ..Self::new() }
+    }
+
+    /// Writes the draft out if it changed since last time, or deletes it if it's been emptied
+    fn autosave(&mut self) {
+        if !std::mem::take(&mut self.edited) {
+            return;
+        }
+        let Some(did) = self.draft_did.clone() else { return };
+        let posts: Vec<DraftPost> = self.posts.iter().map(|post| DraftPost { text: post.draft.clone(), images: post.images.clone(), video: post.video.clone() }).collect();
+        let mut draft = Draft { id: self.draft_id.clone(), updated_at: Utc::now(), posts, reply: self.reply.clone(), quote: self.quote.clone(), quote_preview: None };
+        if self.saved.as_ref().is_some_and(|saved| saved.same_content(&draft)) || (self.saved.is_none() && draft.is_empty()) {
+            return;
+        }
+        draft.quote_preview = self.quote_preview.as_ref().and_then(|preview| serde_json::to_value(&*preview.lock().unwrap()).ok());
+        draft.save(&did);
+        self.saved = Some(draft);
+    }
+
+    fn render_drafts(ui: &mut Ui, data: &mut ComposerFlyout) {
+        let Some(drafts) = &mut data.drafts else { return };
+        let mut open: Option<usize> = None;
+        let mut delete: Option<usize> = None;
+        egui::Frame::none().stroke(egui::Stroke::new(1.0, Color32::GRAY)).inner_margin(8.0).show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.label(egui::RichText::new("Drafts").strong());
+            // the one that's open is already on screen
+            if !drafts.iter().any(|draft| draft.id != data.draft_id) {
+                ui.weak("Nothing saved yet.");
+            }
+            for (idx, draft) in drafts.iter().enumerate() {
+                if draft.id == data.draft_id {
+                    continue;
+                }
+                ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                    if circle_button(ui, "\u{E0C7}", 12.0, 12.0).on_hover_text("Delete Draft").clicked() {
+                        delete = Some(idx);
+                    }
+                    if ui.button("Open").clicked() {
+                        open = Some(idx);
+                    }
+                    ui.with_layout(Layout::top_down(egui::Align::Min), |ui| {
+                        let text = draft.posts.first().map(|post| post.text.trim()).unwrap_or_default();
+                        ui.add(egui::Label::new(egui::RichText::new(if text.is_empty() { "(no text)" } else { text }).color(Color32::BLACK)).truncate());
+
+                        let mut details = vec![offset_time(draft.updated_at)];
+                        if draft.posts.len() > 1 {
+                            details.push(format!("{} posts", draft.posts.len()));
+                        }
+                        if draft.reply.is_some() {
+                            details.push("reply".to_owned());
+                        }
+                        if draft.quote.is_some() {
+                            details.push("quote".to_owned());
+                        }
+                        ui.weak(details.join(" · "));
+                    });
+                });
+            }
+        });
+
+        if let Some(idx) = delete {
+            if let Some(did) = &data.draft_did {
+                Draft::delete(did, &drafts.remove(idx).id);
+            }
+        } else if let Some(idx) = open {
+            // what was here already got saved, it'll show up in the list
+            *data = ComposerFlyout { draft_did: data.draft_did.clone(), ..ComposerFlyout::from_draft(drafts.remove(idx)) };
+        }
     }
 }
 
-fn render_mini_profile(ui: &mut Ui, image: &ImageCache, avatar: &Option<String>, display_name: &Option<String>, handle: &String) -> egui::Response {
+pub(super) fn render_mini_profile(ui: &mut Ui, image: &ImageCache, avatar: &Option<String>, display_name: &Option<String>, handle: &String) -> egui::Response {
     ui.with_layout(Layout::left_to_right(egui::Align::Min), |user| {
         let space = user.allocate_space(vec2(40.0, 40.0));
         let tex = if let Some(avatar) = &avatar {
             match image.get_image(&avatar) {
                 crate::image::LoadableImage::Unloaded | crate::image::LoadableImage::Loading => None,
                 crate::image::LoadableImage::Loaded(texture_id, _) => Some(texture_id),
+                crate::image::LoadableImage::Animated(anim) => Some(anim.frame(user, space.1)),
             }
         } else {
             None
@@ -77,46 +323,502 @@ fn char_name(chr: char) -> String {
     special_char_name(chr).map(|s| s.to_owned()).or_else(|| unicode_names2::name(chr).map(|name| name.to_string().to_lowercase())).unwrap_or_else(|| "unknown".to_owned())
 }
 
+impl ComposerPost {
+    /// Keeps the link card in step with the first link in the draft
+    fn update_link_card(ui: &Ui, data: &mut ComposerPost, backend: &Bridge) {
+        let first_link = link_detector(data.draft.clone()).into_iter().flat_map(|facet| facet.features).find_map(|feature| match feature {
+            Feature::Link(link) if link.uri.starts_with("http") => Some(link.uri),
+            _ => None,
+        });
+
+        let now = ui.input(|i| i.time);
+        if data.link_candidate.as_ref().map(|(uri, _)| uri) != first_link.as_ref() {
+            data.link_candidate = first_link.map(|uri| (uri, now));
+        }
+
+        let Some((uri, since)) = &data.link_candidate else {
+            // the link's gone, so is its card
+            data.link_card = None;
+            data.link_card_uri = None;
+            return;
+        };
+        if data.link_card_uri.as_ref() == Some(uri) {
+            return;
+        }
+
+        let waited = now - since;
+        if waited < LINK_CARD_DELAY {
+            ui.ctx().request_repaint_after_secs((LINK_CARD_DELAY - waited) as f32);
+            return;
+        }
+
+        let card = Arc::new(Mutex::new(LinkCardState::Loading));
+        backend.backend_commander.send(crate::bridge::FrontToBackMsg::GetLinkCardRequest { uri: uri.clone(), card: card.clone() }).unwrap();
+        data.link_card_uri = Some(uri.clone());
+        data.link_card = Some(card);
+    }
+
+    /// Gives back whether anything that goes in the draft changed
+    fn render_attachments(&mut self, ui: &mut Ui, img_cache: &ImageCache) -> bool {
+        let mut changed = false;
+        if self.images.len() > 0 {
+            egui::ScrollArea::horizontal().id_salt(ui.id().with("images")).show(ui, |ui| {
+                ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui| {
+                    let mut rm: Option<usize> = None;
+                    for (idx, attachment) in self.images.iter().enumerate() {
+                        let (_, rect) = ui.allocate_space(vec2(160.0, 90.0));
+                        ui.painter().rect_filled(rect, Rounding::ZERO, BSKY_BLUE);
+                        ui.painter().text(rect.center() - vec2(0.0, 4.0), Align2::CENTER_CENTER, idx, FontId::proportional(30.0), Color32::WHITE);
+
+                        let close_button = ui.allocate_rect(rect.shrink(4.0).with_min_x(rect.right() - 34.0).with_max_y(rect.top() + 34.0), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
+                        ui.painter().rect_filled(close_button.rect, Rounding::ZERO, Color32::from_black_alpha(128));
+                        ui.painter().text(close_button.rect.center() - vec2(0.0, 1.0), Align2::CENTER_CENTER, "\u{E0C7}", FontId::new(16.0, egui::FontFamily::Name("Segoe Symbols".into())), Color32::WHITE);
+
+                        if close_button.clicked() {
+                            rm = Some(idx);
+                        }
+
+                        let alt_button = ui.allocate_rect(rect.shrink(4.0).with_max_x(rect.left() + 44.0).with_min_y(rect.bottom() - 24.0), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
+                        ui.painter().rect_filled(alt_button.rect, Rounding::ZERO, Color32::from_black_alpha(128));
+                        ui.painter().text(alt_button.rect.left_center() + vec2(8.0, -1.0), Align2::LEFT_CENTER, if attachment.alt.is_empty() { "+ ALT" } else { "ALT" }, FontId::proportional(14.0), Color32::WHITE);
+
+                        if alt_button.on_hover_text(if attachment.alt.is_empty() { "Add a description" } else { attachment.alt.as_str() }).clicked() {
+                            self.editing_alt = if self.editing_alt == Some(idx) { None } else { Some(idx) };
+                        }
+                    }
+
+                    if let Some(rm) = rm {
+                        self.images.remove(rm);
+                        self.editing_alt = None;
+                        changed = true;
+                    }
+                });
+            });
+
+            if let Some(attachment) = self.editing_alt.and_then(|idx| self.images.get_mut(idx)) {
+                let name = attachment.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                ui.label(egui::RichText::new(format!("Alt text for {}", name)).color(Color32::BLACK));
+                changed |= TextEdit::multiline(&mut attachment.alt).desired_width(ui.cursor().width()).desired_rows(3).text_color(Color32::BLACK).hint_text("Describe this image for people who can't see it").char_limit(MAX_ALT_LENGTH).show(ui).response.changed();
+                ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui| {
+                    ui.weak(format!("{}/{}", attachment.alt.chars().count(), MAX_ALT_LENGTH));
+                    ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                        if ui.button("Done").clicked() {
+                            self.editing_alt = None;
+                        }
+                    });
+                });
+            }
+        }
+
+        if let Some(video) = &mut self.video {
+            let mut remove = false;
+            egui::Frame::none().stroke(egui::Stroke::new(1.0, Color32::GRAY)).inner_margin(8.0).show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui| {
+                    let name = video.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                    ui.label(egui::RichText::new(format!("\u{E116} {}", name)).color(Color32::BLACK));
+                    ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                        if circle_button(ui, "\u{E0C7}", 12.0, 12.0).on_hover_text("Remove Video").clicked() {
+                            remove = true;
+                        }
+                    });
+                });
+                changed |= TextEdit::multiline(&mut video.alt).desired_width(ui.cursor().width()).desired_rows(2).text_color(Color32::BLACK).hint_text("Describe this video for people who can't see it").char_limit(MAX_ALT_LENGTH).show(ui).response.changed();
+
+                let mut rm: Option<usize> = None;
+                for (idx, (lang, path)) in video.captions.iter_mut().enumerate() {
+                    ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+                        changed |= ui.add(TextEdit::singleline(lang).desired_width(40.0).text_color(Color32::BLACK).hint_text("en")).changed();
+                        ui.label(egui::RichText::new(path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()).color(Color32::DARK_GRAY));
+                        ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("Remove").clicked() {
+                                rm = Some(idx);
+                            }
+                        });
+                    });
+                }
+                if let Some(rm) = rm {
+                    video.captions.remove(rm);
+                    changed = true;
+                }
+
+                // the lexicon allows 20, nobody's going to hit that
+                if video.captions.len() < 20 && ui.button("Add captions").clicked() {
+                    if let Some(file) = FileDialog::new().add_filter("WebVTT", &["vtt"]).pick_file() {
+                        video.captions.push(("en".to_owned(), file));
+                        changed = true;
+                    }
+                }
+            });
+            if remove {
+                self.video = None;
+                changed = true;
+            }
+        }
+
+        // images and videos take the embed slot, the card only goes out without them
+        if self.images.is_empty() && self.video.is_none() && let Some(card) = &self.link_card {
+            let state = card.lock().unwrap().clone();
+            let mut remove = false;
+            match state {
+                LinkCardState::Loading => {
+                    ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+                        let (_, rect) = ui.allocate_space(vec2(20.0, 20.0));
+                        SegoeBootSpinner::new().size(20.0).color(BSKY_BLUE).paint_at(ui, rect);
+                        ui.weak("Loading link preview...");
+                    });
+                }
+                LinkCardState::Loaded(card) => {
+                    egui::Frame::none().stroke(egui::Stroke::new(1.0, Color32::GRAY)).inner_margin(8.0).show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+                        ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui| {
+                            if let Some(image) = &card.image {
+                                let (_, rect) = ui.allocate_space(vec2(60.0, 60.0));
+                                let texture = match img_cache.get_image(image) {
+                                    crate::image::LoadableImage::Loaded(texture_id, _) => Some(texture_id),
+                                    crate::image::LoadableImage::Animated(anim) => Some(anim.frame(ui, rect)),
+                                    _ => None,
+                                };
+                                match texture {
+                                    Some(texture_id) => ui.painter().image(texture_id, rect, Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE),
+                                    None => ui.painter().rect_filled(rect, Rounding::ZERO, Color32::LIGHT_GRAY),
+                                };
+                            }
+                            ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                                if circle_button(ui, "\u{E0C7}", 12.0, 12.0).on_hover_text("Remove Link Preview").clicked() {
+                                    remove = true;
+                                }
+                                ui.with_layout(Layout::top_down(egui::Align::Min), |ui| {
+                                    ui.add(egui::Label::new(egui::RichText::new(&card.title).color(Color32::BLACK).font(FontId::new(16.0, egui::FontFamily::Name("Segoe Light".into())))).truncate());
+                                    if !card.description.is_empty() {
+                                        ui.add(egui::Label::new(egui::RichText::new(&card.description).color(Color32::DARK_GRAY)).truncate());
+                                    }
+                                    ui.add(egui::Label::new(egui::RichText::new(&card.uri).color(Color32::GRAY).small()).truncate());
+                                });
+                            });
+                        });
+                    });
+                }
+                LinkCardState::Failed => {}
+            }
+            if remove {
+                self.link_card = None;
+            }
+        }
+        changed
+    }
+}
+
+impl ComposerFlyout {
+    /// Keeps track of the word at the caret, and looks up handles once it stops changing
+    fn update_completion(ui: &Ui, data: &mut ComposerFlyout, caret: Option<usize>, backend: &Bridge) {
+        let token = caret.and_then(|caret| completion_token(&data.posts[data.active].draft, caret));
+        let now = ui.input(|i| i.time);
+        if data.completion.as_ref().map(|(token, _)| token) != token.as_ref() {
+            data.completion_selected = 0;
+            data.completion = token.map(|token| (token, now));
+        }
+
+        let Some((token, since)) = &data.completion else {
+            data.completion_dismissed = None;
+            return;
+        };
+        if data.completion_dismissed.is_some_and(|start| start != token.start) {
+            data.completion_dismissed = None;
+        }
+        if token.kind != CompletionKind::Mention || token.query.is_empty() || data.typeahead_query == token.query {
+            return;
+        }
+
+        let waited = now - since;
+        if waited < TYPEAHEAD_DELAY {
+            ui.ctx().request_repaint_after_secs((TYPEAHEAD_DELAY - waited) as f32);
+            return;
+        }
+
+        backend.backend_commander.send(crate::bridge::FrontToBackMsg::SearchActorsTypeaheadRequest { query: token.query.clone(), limit: MAX_SUGGESTIONS as u32, results: data.typeahead.clone() }).unwrap();
+        data.typeahead_query = token.query.clone();
+    }
+
+    fn suggestions(&self, recent_tags: &[String]) -> Vec<Suggestion> {
+        let Some((token, _)) = &self.completion else { return Vec::new() };
+        if self.completion_dismissed == Some(token.start) {
+            return Vec::new();
+        }
+        let query = token.query.to_lowercase();
+
+        match token.kind {
+            CompletionKind::Mention => {
+                if query.is_empty() {
+                    return Vec::new();
+                }
+                let results = self.typeahead.lock().unwrap();
+                // older results can stand in while the new ones load, as long as they still fit
+                let stale = results.0.to_lowercase() != query;
+                results.1.iter().filter(|actor| !stale || actor.handle.to_lowercase().starts_with(&query)).take(MAX_SUGGESTIONS).map(|actor| Suggestion::Actor(actor.clone())).collect()
+            }
+            CompletionKind::Tag => recent_tags
+                .iter()
+                .filter(|tag| {
+                    let tag = tag.to_lowercase();
+                    tag.starts_with(&query) && tag != query
+                })
+                .take(MAX_SUGGESTIONS)
+                .map(|tag| Suggestion::Tag(tag.clone()))
+                .collect(),
+        }
+    }
+
+    /// Swaps the word at the caret for the suggestion and puts the caret after it
+    fn complete(&mut self, ctx: &egui::Context, id: egui::Id, suggestion: &Suggestion) {
+        let Some((token, _)) = self.completion.take() else { return };
+        let draft = &mut self.posts[self.active].draft;
+        let byte = |idx: usize| draft.char_indices().nth(idx).map(|(byte, _)| byte).unwrap_or(draft.len());
+        let (start, end) = (byte(token.start), byte(token.end));
+
+        let mut text = suggestion.text();
+        if !draft[end..].starts_with(char::is_whitespace) {
+            text.push(' ');
+        }
+        draft.replace_range(start..end, &text);
+        self.edited = true;
+        // it's done, don't offer the same thing again if the caret's still on it
+        self.completion_dismissed = Some(token.start);
+
+        if let Some(mut state) = egui::text_edit::TextEditState::load(ctx, id) {
+            state.cursor.set_char_range(Some(egui::text_selection::CCursorRange::one(egui::text::CCursor::new(token.start + text.chars().count()))));
+            state.store(ctx, id);
+        }
+        ctx.memory_mut(|mem| mem.request_focus(id));
+    }
+}
+
+/// What this account posts in, English until it's picked something else
+fn post_languages(settings: &Settings, did: &Option<String>) -> Vec<String> {
+    did.as_ref().and_then(|did| settings.post_languages.get(did)).cloned().unwrap_or_else(|| vec!["en".to_owned()])
+}
+
+/// Puts the tags from a post at the front of the recent ones
+fn remember_tags(settings: &Arc<Mutex<Settings>>, text: &str) {
+    let tags: Vec<String> = tag_detector(text).into_iter().flat_map(|facet| facet.features).filter_map(|feature| match feature {
+        Feature::Tag(tag) => Some(tag.tag),
+        _ => None,
+    }).collect();
+    if tags.is_empty() {
+        return;
+    }
+
+    let mut settings = settings.lock().unwrap();
+    for tag in tags.into_iter().rev() {
+        settings.recent_tags.retain(|recent| recent.to_lowercase() != tag.to_lowercase());
+        settings.recent_tags.insert(0, tag);
+    }
+    settings.recent_tags.truncate(MAX_RECENT_TAGS);
+}
+
 impl ClientFrontendFlyoutVariant {
-    pub fn post_composer(ui: &mut Ui, data: &mut ComposerFlyout, profile: &Option<ProfileViewDetailed>, img_cache: &ImageCache, backend: &Bridge) {
+    pub fn post_composer(ui: &mut Ui, data: &mut ComposerFlyout, profile: &Option<ProfileViewDetailed>, img_cache: &ImageCache, backend: &Bridge, settings: &Arc<Mutex<Settings>>) {
         let center = ui.cursor().center();
+        let accounts = data.accounts.get_or_insert_with(|| Accounts::load().accounts).clone();
+        let you = profile.as_ref().map(|profile| profile.did.clone());
+        let did = data.author.as_ref().map(|author| author.did.clone()).or(you.clone());
+        if data.draft_did.is_none() {
+            data.draft_did = you.clone();
+        }
+        let require_alt_text = settings.lock().unwrap().require_alt_text;
         *ui.visuals_mut() = Visuals::light();
         ui.add_enabled_ui(!data.sending, |ui| {
             let right_limit = ui.cursor().right();
-            ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui| {
-                let res = if let Some(profile) = &profile { render_mini_profile(ui, img_cache, &profile.avatar, &profile.display_name, &profile.handle) } else { ui.label(egui::RichText::new("Unknown Profile").color(Color32::BLACK).font(FontId::new(16.0, egui::FontFamily::Name("Segoe Light".into())))) };
-                ui.painter().text(pos2(right_limit, res.rect.center().y), Align2::RIGHT_CENTER, format!("{}", 300 as i16 - data.draft.len() as i16), FontId::proportional(12.0), if data.draft.len() > 300 { Color32::RED } else { Color32::GRAY });
-            });
+            ComposerFlyout::render_drafts(ui, data);
 
-            let draft = TextEdit::multiline(&mut data.draft);
-            draft.desired_width(ui.cursor().width()).text_color(Color32::BLACK).hint_text("Write Here").frame(false).font(TextStyle::Body).show(ui);
+            let draft_base = ui.id().with("draft");
+            let post_count = data.posts.len();
+            let mut remove_post: Option<usize> = None;
+            for idx in 0..post_count {
+                if idx > 0 {
+                    ui.separator();
+                }
+                ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui| {
+                    let res = if idx > 0 {
+                        ui.weak(format!("{}/{}", idx + 1, post_count))
+                    } else if let Some(author) = &data.author {
+                        render_mini_profile(ui, img_cache, &author.avatar, &author.display_name, &author.handle)
+                    } else if let Some(profile) = &profile {
+                        render_mini_profile(ui, img_cache, &profile.avatar, &profile.display_name, &profile.handle)
+                    } else {
+                        ui.label(egui::RichText::new("Unknown Profile").color(Color32::BLACK).font(FontId::new(16.0, egui::FontFamily::Name("Segoe Light".into()))))
+                    };
+                    let mut counter_right = right_limit;
+                    if idx > 0 {
+                        ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                            let remove = circle_button(ui, "\u{E0C7}", 12.0, 12.0).on_hover_text("Remove Post");
+                            counter_right = remove.rect.left() - 8.0;
+                            if remove.clicked() {
+                                remove_post = Some(idx);
+                            }
+                        });
+                    } else if accounts.len() > 1 {
+                        ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                            let selected = did.clone().unwrap_or_default();
+                            let combo = egui::ComboBox::from_id_salt("post as").selected_text("Post as").width(80.0).show_ui(ui, |ui| {
+                                for account in &accounts {
+                                    if ui.selectable_label(account.did == selected, format!("@{}", account.handle)).clicked() {
+                                        data.author = if Some(&account.did) == you.as_ref() { None } else { Some(account.clone()) };
+                                    }
+                                }
+                            });
+                            counter_right = combo.response.rect.left() - 8.0;
+                        });
+                    }
+                    let post = &data.posts[idx];
+                    ui.painter().text(pos2(counter_right, res.rect.center().y), Align2::RIGHT_CENTER, format!("{}", 300 as i16 - post.draft.len() as i16), FontId::proportional(12.0), if post.too_long() { Color32::RED } else { Color32::GRAY });
+                });
 
-            if data.images.len() > 0 {
-                egui::ScrollArea::horizontal().show(ui, |ui| {
-                    ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui| {
-                        let mut rm: Option<usize> = None;
-                        for (idx, _path) in data.images.iter().enumerate() {
-                            let (_, rect) = ui.allocate_space(vec2(160.0, 90.0));
-                            ui.painter().rect_filled(rect, Rounding::ZERO, BSKY_BLUE);
-                            ui.painter().text(rect.center() - vec2(0.0, 4.0), Align2::CENTER_CENTER, idx, FontId::proportional(30.0), Color32::WHITE);
+                let draft_id = draft_base.with(idx);
+                if idx == data.active {
+                    let suggestions = data.suggestions(&settings.lock().unwrap().recent_tags);
+                    // the suggestions get the keys before the text box can turn them into newlines
+                    if !suggestions.is_empty() && ui.memory(|mem| mem.has_focus(draft_id)) {
+                        let (down, up, enter, escape) = ui.input_mut(|i| (i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown), i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp), i.consume_key(egui::Modifiers::NONE, egui::Key::Enter), i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)));
+                        let selected = data.completion_selected.min(suggestions.len() - 1);
+                        data.completion_selected = if down {
+                            (selected + 1) % suggestions.len()
+                        } else if up {
+                            (selected + suggestions.len() - 1) % suggestions.len()
+                        } else {
+                            selected
+                        };
+                        if enter {
+                            data.complete(ui.ctx(), draft_id, &suggestions[data.completion_selected]);
+                        } else if escape {
+                            data.completion_dismissed = data.completion.as_ref().map(|(token, _)| token.start);
+                        }
+                    }
+                }
 
-                            let close_button = ui.allocate_rect(rect.shrink(4.0).with_min_x(rect.right() - 34.0).with_max_y(rect.top() + 34.0), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
-                            ui.painter().rect_filled(close_button.rect, Rounding::ZERO, Color32::from_black_alpha(128));
-                            ui.painter().text(close_button.rect.center() - vec2(0.0, 1.0), Align2::CENTER_CENTER, "\u{E0C7}", FontId::new(16.0, egui::FontFamily::Name("Segoe Symbols".into())), Color32::WHITE);
+                let draft = TextEdit::multiline(&mut data.posts[idx].draft).id(draft_id);
+                let output = draft.desired_width(ui.cursor().width()).text_color(Color32::BLACK).hint_text(if idx == 0 { "Write Here" } else { "Keep going" }).frame(false).font(TextStyle::Body).show(ui);
+                data.edited |= output.response.changed();
+                if output.response.has_focus() && data.active != idx {
+                    data.active = idx;
+                    data.completion = None;
+                    data.completion_dismissed = None;
+                }
 
-                            if close_button.clicked() {
-                                rm = Some(idx);
+                if idx == data.active {
+                    let caret = output.cursor_range.filter(|range| range.is_empty()).map(|range| range.primary.ccursor.index);
+                    ComposerFlyout::update_completion(ui, data, caret, backend);
+                    let suggestions = data.suggestions(&settings.lock().unwrap().recent_tags);
+                    if !suggestions.is_empty() {
+                        let mut picked: Option<usize> = None;
+                        egui::Frame::none().stroke(egui::Stroke::new(1.0, Color32::GRAY)).show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.style_mut().spacing.item_spacing.y = 0.0;
+                            for (idx, suggestion) in suggestions.iter().enumerate() {
+                                let fill = if idx == data.completion_selected { BSKY_BLUE.gamma_multiply(0.25) } else { Color32::TRANSPARENT };
+                                let row = egui::Frame::none().fill(fill).inner_margin(4.0).show(ui, |ui| {
+                                    ui.set_width(ui.available_width());
+                                    match suggestion {
+                                        Suggestion::Actor(actor) => {
+                                            render_mini_profile(ui, img_cache, &actor.avatar, &actor.display_name, &actor.handle);
+                                        }
+                                        Suggestion::Tag(tag) => {
+                                            ui.label(egui::RichText::new(format!("#{}", tag)).color(Color32::BLACK).font(FontId::new(16.0, egui::FontFamily::Name("Segoe Light".into()))));
+                                        }
+                                    }
+                                });
+                                let row = ui.interact(row.response.rect, draft_id.with("suggestion").with(idx), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
+                                if row.hovered() {
+                                    data.completion_selected = idx;
+                                }
+                                if row.clicked() {
+                                    picked = Some(idx);
+                                }
                             }
-
-                            let alt_button = ui.allocate_rect(rect.shrink(4.0).with_max_x(rect.left() + 44.0).with_min_y(rect.bottom() - 24.0), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
-                            ui.painter().rect_filled(alt_button.rect, Rounding::ZERO, Color32::from_black_alpha(128));
-                            ui.painter().text(alt_button.rect.left_center() + vec2(8.0, -1.0), Align2::CENTER_CENTER, "+ ALT", FontId::proportional(14.0), Color32::WHITE);
+                        });
+                        if let Some(idx) = picked {
+                            data.complete(ui.ctx(), draft_id, &suggestions[idx]);
                         }
+                    }
+                }
 
-                        if let Some(rm) = rm {
-                            data.images.remove(rm);
-                        }
+                ComposerPost::update_link_card(ui, &mut data.posts[idx], backend);
+                data.edited |= ui.push_id(idx, |ui| data.posts[idx].render_attachments(ui, img_cache)).inner;
+
+                // the quote goes on the first post, so it shows under it
+                if idx == 0 && let Some(preview) = &data.quote_preview {
+                    let mut remove = false;
+                    egui::Frame::none().stroke(egui::Stroke::new(1.0, Color32::GRAY)).inner_margin(8.0).show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+                        let post = preview.lock().unwrap();
+                        ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui| {
+                            render_mini_profile(ui, img_cache, &post.author.avatar, &post.author.display_name, &post.author.handle);
+                            ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                                if circle_button(ui, "\u{E0C7}", 12.0, 12.0).on_hover_text("Remove Quote").clicked() {
+                                    remove = true;
+                                }
+                            });
+                        });
+                        ui.add(egui::Label::new(egui::RichText::new(&post.record.text).color(Color32::DARK_GRAY)).truncate());
                     });
+                    if remove {
+                        data.quote = None;
+                        data.quote_preview = None;
+                        data.edited = true;
+                    }
+                }
+            }
+
+            if let Some(idx) = remove_post {
+                data.posts.remove(idx);
+                data.edited = true;
+                if data.active >= idx {
+                    data.active = data.active.saturating_sub(1);
+                }
+                data.completion = None;
+            }
+
+            if data.languages_open {
+                egui::Frame::none().stroke(egui::Stroke::new(1.0, Color32::GRAY)).inner_margin(8.0).show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    let mut settings = settings.lock().unwrap();
+                    ui.label(egui::RichText::new("Post languages").strong());
+                    ui.weak(format!("Up to {}, remembered for this account.", MAX_POST_LANGUAGES));
+
+                    let mut picked = post_languages(&settings, &did);
+                    let mut changed = false;
+                    egui::ScrollArea::vertical().id_salt(ui.id().with("languages")).max_height(150.0).show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            for (code, name) in LANGUAGES {
+                                let mut on = picked.iter().any(|lang| lang == code);
+                                if ui.add_enabled(on || picked.len() < MAX_POST_LANGUAGES, egui::Checkbox::new(&mut on, *name)).changed() {
+                                    picked.retain(|lang| lang != code);
+                                    if on {
+                                        picked.push(code.to_string());
+                                    }
+                                    changed = true;
+                                }
+                            }
+                        });
+                    });
+                    if changed && let Some(did) = &did {
+                        settings.post_languages.insert(did.clone(), picked);
+                    }
+
+                    ui.checkbox(&mut settings.detect_post_language, "Detect from what I write");
+                    if settings.detect_post_language {
+                        match detect_language(&data.posts[data.active].draft) {
+                            Some(lang) => ui.weak(format!("This post looks like {}.", language_name(lang))),
+                            None => ui.weak("Can't tell what this post is in yet, it'll go out as the ones picked above."),
+                        };
+                    }
+                });
+            }
+
+            if data.interaction_open {
+                egui::Frame::none().stroke(egui::Stroke::new(1.0, Color32::GRAY)).inner_margin(8.0).show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    data.interaction.render(ui, backend);
                 });
             }
 
@@ -147,7 +849,8 @@ impl ClientFrontendFlyoutVariant {
                                 ui.painter().text(sense.rect.center() - vec2(0.0, 5.0), Align2::CENTER_CENTER, format!("{}", chr.0), FontId::monospace(24.0), col);
 
                                 if sense.on_hover_text(char_name(chr.0)).clicked() {
-                                    data.draft.push(chr.0);
+                                    data.posts[data.active].draft.push(chr.0);
+                                    data.edited = true;
                                 }
                             }
                         });
@@ -157,7 +860,8 @@ impl ClientFrontendFlyoutVariant {
             }
 
             ui.with_layout(Layout::left_to_right(egui::Align::Min), |buttons| {
-                buttons.add_enabled_ui(data.images.len() < 4, |buttons| 'picker_logic: {
+                let post = &mut data.posts[data.active];
+                buttons.add_enabled_ui(post.images.len() < 4 && post.video.is_none(), |buttons| 'picker_logic: {
                     if circle_button(buttons, "\u{E114}", 15.0, 15.0).on_hover_text("Upload Image").clicked() {
                         let files = FileDialog::new()
                             .add_filter("image", &["png", "jpg", "jpeg", "webp"])
@@ -169,12 +873,25 @@ impl ClientFrontendFlyoutVariant {
                         }
                         let files = files.unwrap();
 
-                        let pre_count = data.images.len().clone(); // this needs to be cached, else it will live update
+                        let pre_count = post.images.len().clone(); // this needs to be cached, else it will live update
                         for (idx, file) in files.into_iter().enumerate() {
                             if idx > (3 - pre_count) {
                                 break 'picker_logic;
                             }
-                            data.images.push(file);
+                            post.images.push(ImageAttachment { path: file, alt: String::new() });
+                            data.edited = true;
+                        }
+                    }
+                });
+
+                buttons.add_enabled_ui(post.images.is_empty() && post.video.is_none(), |buttons| {
+                    if circle_button(buttons, "\u{E116}", 15.0, 15.0).on_hover_text("Upload Video").clicked() {
+                        let file = FileDialog::new()
+                            .add_filter("video", &["mp4", "mov", "webm", "mpeg", "mpg"])
+                            .pick_file();
+                        if let Some(file) = file {
+                            post.video = Some(VideoAttachment { path: file, alt: String::new(), captions: Vec::new() });
+                            data.edited = true;
                         }
                     }
                 });
@@ -183,28 +900,84 @@ impl ClientFrontendFlyoutVariant {
                     data.emoji_picker = !data.emoji_picker;
                 }
 
+                let interaction = circle_button(buttons, "\u{E192}", 15.0, 15.0).on_hover_text("Who can reply and quote");
+                if !data.interaction.settings.is_default() {
+                    buttons.painter().circle_filled(interaction.rect.center_bottom() + vec2(0.0, 4.0), 2.0, BSKY_BLUE);
+                }
+                if interaction.clicked() {
+                    data.interaction_open = !data.interaction_open;
+                }
+
+                let language_hover = {
+                    let settings = settings.lock().unwrap();
+                    let picked = post_languages(&settings, &did).iter().map(|lang| language_name(lang).to_owned()).collect::<Vec<String>>().join(", ");
+                    let picked = if picked.is_empty() { "none".to_owned() } else { picked };
+                    if settings.detect_post_language { format!("Post language: detected, or {}", picked) } else { format!("Post language: {}", picked) }
+                };
+                if circle_button(buttons, "\u{E128}", 15.0, 15.0).on_hover_text(language_hover).clicked() {
+                    data.languages_open = !data.languages_open;
+                }
+
+                if circle_button(buttons, "\u{E160}", 15.0, 15.0).on_hover_text("Drafts").clicked() {
+                    data.drafts = if data.drafts.is_some() { None } else { data.draft_did.as_deref().map(Draft::list) };
+                }
+
+                if circle_button(buttons, "\u{E109}", 15.0, 15.0).on_hover_text("Add to Thread").clicked() {
+                    data.posts.insert(data.active + 1, ComposerPost::new());
+                    data.edited = true;
+                    data.active += 1;
+                    data.completion = None;
+                    buttons.memory_mut(|mem| mem.request_focus(draft_base.with(data.active)));
+                }
+
                 let send_button_rect = buttons.cursor().with_min_x(right_limit - 90.0).with_max_x(right_limit).with_max_y(buttons.cursor().top() + 30.0);
-                let send_button = buttons.add_enabled_ui(data.draft.len() > 0, |buttons| buttons.allocate_rect(send_button_rect, egui::Sense::click())).inner.on_hover_cursor(egui::CursorIcon::PointingHand);
-                buttons.painter().rect_filled(send_button_rect, Rounding::ZERO, BSKY_BLUE.gamma_multiply(if data.draft.len() > 0 && data.draft.len() <= 300 { 1.0 } else { 0.5 }));
+                let missing_alt = require_alt_text && data.posts.iter().any(ComposerPost::missing_alt);
+                let sendable = data.posts.iter().all(|post| post.draft.len() > 0 && !post.too_long()) && !missing_alt;
+                let send_button = buttons.add_enabled_ui(sendable, |buttons| buttons.allocate_rect(send_button_rect, egui::Sense::click())).inner.on_hover_cursor(egui::CursorIcon::PointingHand);
+                let send_button = if missing_alt { send_button.on_disabled_hover_text("Every image needs alt text") } else { send_button };
+                buttons.painter().rect_filled(send_button_rect, Rounding::ZERO, BSKY_BLUE.gamma_multiply(if sendable { 1.0 } else { 0.5 }));
 
-                buttons.painter().text(send_button_rect.center() - vec2(0.0, 2.0), Align2::CENTER_CENTER, "Post", FontId::proportional(10.0), Color32::WHITE);
+                buttons.painter().text(send_button_rect.center() - vec2(0.0, 2.0), Align2::CENTER_CENTER, if data.posts.len() > 1 { "Post All" } else { "Post" }, FontId::proportional(10.0), Color32::WHITE);
 
                 if send_button.clicked() {
-                    let mut languages: Vec<String> = Vec::new();
-                    languages.push("en".to_owned());
+                    // only the one with the video (if any) ever writes to this
+                    let progress = Arc::new(Mutex::new(VideoProgress::new()));
+                    let (picked, detect) = {
+                        let settings = settings.lock().unwrap();
+                        (post_languages(&settings, &did), settings.detect_post_language)
+                    };
+                    let mut posts = Vec::new();
+                    for (idx, post) in data.posts.iter().enumerate() {
+                        let languages = match detect_language(&post.draft) {
+                            Some(lang) if detect => vec![lang.to_owned()],
+                            _ => picked.clone(),
+                        };
 
-                    let record = BlueskyApiRecord::Post(feed::Post { text: data.draft.clone(), created_at: Utc::now(), facets: None, reply: data.reply.clone(), embed: None, langs: Some(languages), labels: None, tags: None });
-                    if data.images.len() > 0 {
-                        backend.backend_commander.send(crate::bridge::FrontToBackMsg::CreateRecordWithMediaRequest(record, data.images.clone())).unwrap();
-                    } else {
-                        backend.backend_commander.send(crate::bridge::FrontToBackMsg::CreateRecordRequest(record)).unwrap();
+                        // media gets attached next to the quote by the backend, once it's uploaded
+                        let embed = if idx == 0 { data.quote.as_ref().map(|quote| Arc::new(embed::Variant::RecordRaw(RecordRef { record: quote.clone() }))) } else { None };
+                        let reply = if idx == 0 { data.reply.clone() } else { None };
+                        let record = feed::Post { text: post.draft.clone(), created_at: Utc::now(), facets: None, reply, embed, langs: if languages.is_empty() { None } else { Some(languages) }, labels: None, tags: None };
+                        posts.push((record, post.media(&progress)));
+                        remember_tags(settings, &post.draft);
+                    }
+                    if data.posts.iter().any(|post| post.video.is_some()) {
+                        data.video_progress = Some(progress);
                     }
+                    backend.backend_commander.send(crate::bridge::FrontToBackMsg::CreateThreadRequest { posts, interaction: data.interaction.settings.clone(), author: data.author.as_ref().map(|author| author.did.clone()), ungated: data.ungated.clone(), draft_id: data.draft_id.clone(), draft_did: data.draft_did.clone() }).unwrap();
                     data.sending = true;
                 }
             });
         });
+        data.autosave();
         if data.sending {
             SegoeBootSpinner::new().size(60.0).color(BSKY_BLUE).paint_at(ui, Rect::from_center_size(center, vec2(60.0, 60.0)));
+            if let Some(progress) = &data.video_progress {
+                let progress = progress.lock().unwrap();
+                let bar_rect = Rect::from_center_size(center + vec2(0.0, 60.0), vec2(ui.max_rect().width(), 20.0));
+                ui.put(bar_rect, egui::ProgressBar::new(progress.progress).text(egui::RichText::new(&progress.state).color(Color32::BLACK)));
+                // progress comes in from the backend on its own, keep checking on it
+                ui.ctx().request_repaint_after_secs(0.5);
+            }
         }
     }
 }
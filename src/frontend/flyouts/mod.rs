@@ -1 +1,3 @@
+pub mod accounts;
 pub mod composer;
+pub mod settings;
@@ -0,0 +1,38 @@
+use egui::{Color32, FontId, Ui, Visuals};
+
+use crate::{frontend::main::ClientFrontendFlyoutVariant, lang::LANGUAGES, settings::Settings};
+
+fn section_header(ui: &mut Ui, text: &str) {
+    ui.add_space(8.0);
+    ui.label(egui::RichText::new(text).color(Color32::BLACK).font(FontId::new(20.0, egui::FontFamily::Name("Segoe Light".into()))));
+}
+
+impl ClientFrontendFlyoutVariant {
+    pub fn settings(ui: &mut Ui, settings: &mut Settings) {
+        *ui.visuals_mut() = Visuals::light();
+
+        section_header(ui, "Media");
+        ui.checkbox(&mut settings.autoplay_animations, "Autoplay animated images");
+        ui.weak("When off, GIFs and animated avatars stay on their first frame.");
+
+        section_header(ui, "Posting");
+        ui.checkbox(&mut settings.require_alt_text, "Require alt text on images");
+        ui.weak("Posts with images won't send until every image has a description.");
+        ui.checkbox(&mut settings.detect_post_language, "Detect the language of my posts");
+        ui.weak("Posts get tagged with whatever they look like they're written in, or the languages picked in the composer if it can't tell.");
+
+        section_header(ui, "Content languages");
+        ui.weak("Only show posts in these languages in the timeline and feeds. With none picked, everything shows. Posts that don't say what language they're in always show, and feeds that are already loaded catch up when they're refreshed.");
+        ui.horizontal_wrapped(|ui| {
+            for (code, name) in LANGUAGES {
+                let mut on = settings.content_languages.iter().any(|lang| lang == code);
+                if ui.checkbox(&mut on, *name).changed() {
+                    settings.content_languages.retain(|lang| lang != code);
+                    if on {
+                        settings.content_languages.push(code.to_string());
+                    }
+                }
+            }
+        });
+    }
+}
@@ -1,15 +1,15 @@
 use egui::{pos2, style::HandleShape, vec2, Align2, Button, Color32, FontFamily, FontId, Id, LayerId, Layout, Margin, Rect, Rounding, Shadow, Stroke, TextStyle, UiBuilder, UiStackInfo};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     sync::{Arc, Mutex},
 };
 
-use crate::{backend::BlueskyApiError, bridge::Bridge, defs::bsky::actor::defs::ProfileViewDetailed, image::ImageCache, settings::Settings, widgets::spinner::SegoeBootSpinner, BSKY_BLUE};
+use crate::{backend::{BlueskyApiError, SESSION_EXPIRED}, bridge::Bridge, defs::bsky::actor::defs::ProfileViewDetailed, image::ImageCache, settings::Settings, widgets::spinner::SegoeBootSpinner, BSKY_BLUE};
 
 use super::{
-    flyouts::composer::ComposerFlyout,
+    flyouts::{accounts::AccountsFlyout, composer::ComposerFlyout},
     modals::important_error::ImportantErrorModal,
-    pages::{FrontendMainView, FrontendMainViewStack},
+    pages::{profile::FrontendProfileView, FrontendMainView, FrontendMainViewStack},
 };
 #[derive(serde::Deserialize, serde::Serialize)]
 pub enum ClientFrontendPage {
@@ -24,10 +24,14 @@ pub enum ClientFrontendModalVariant {
     LoginModal(crate::frontend::modals::login::LoginModal),
     ImportantErrorModal(crate::frontend::modals::important_error::ImportantErrorModal),
     DeceptiveLink(crate::frontend::modals::deceptive_link::DeceptiveLinkModal),
+    DeletePost(crate::frontend::modals::delete_post::DeletePostModal),
+    InteractionSettings(crate::frontend::modals::interaction_settings::InteractionSettingsModal),
 }
 
 pub enum ClientFrontendFlyoutVariant {
     PostComposerFlyout(ComposerFlyout),
+    SettingsFlyout,
+    AccountsFlyout(AccountsFlyout),
 }
 
 pub struct ClientFrontendFlyout {
@@ -63,10 +67,25 @@ pub struct ClientFrontend {
     pub active: bool,
     pub authenticated: bool,
     pub profile: Option<ProfileViewDetailed>,
+    pub unread_notifications: usize,
 
     pub view_stack: FrontendMainViewStack,
 
     pub settings: Arc<Mutex<Settings>>,
+
+    /// DID of the account we're showing, empty until someone logs in
+    pub active_did: String,
+    /// What the other accounts had going when we switched away from them
+    pub inactive_accounts: HashMap<String, AccountState>,
+    /// Settings for every account but the active one, whose are in `settings`
+    pub account_settings: HashMap<String, Settings>,
+}
+
+/// An account's pages and such, put aside while another one's active
+pub struct AccountState {
+    pub view_stack: FrontendMainViewStack,
+    pub profile: Option<ProfileViewDetailed>,
+    pub unread_notifications: usize,
 }
 
 impl ClientFrontend {
@@ -165,6 +184,7 @@ impl ClientFrontend {
 
         let settings: Settings = if let Some(storage) = cc.storage { eframe::get_value(storage, "Settings").unwrap_or_default() } else { Settings::default() };
         let settings = Arc::new(Mutex::new(settings));
+        let account_settings: HashMap<String, Settings> = if let Some(storage) = cc.storage { eframe::get_value(storage, "AccountSettings").unwrap_or_default() } else { HashMap::new() };
 
         Self {
             ctx: cc.egui_ctx.clone(),
@@ -177,8 +197,46 @@ impl ClientFrontend {
             active: false,
             authenticated: false,
             profile: None,
+            unread_notifications: 0,
             view_stack: FrontendMainViewStack::new(cc.egui_ctx.clone(), FrontendMainView::Login()),
             settings,
+            active_did: String::new(),
+            inactive_accounts: HashMap::new(),
+            account_settings,
+        }
+    }
+
+    /// Puts away everything that belongs to the account that was active, and brings back `did`'s if it's been active before.
+    /// Gives back whether its pages came back, otherwise it needs a fresh timeline.
+    pub fn switch_account(&mut self, did: &str) -> bool {
+        if self.active_did == did {
+            return false;
+        }
+        self.image.set_namespace(did);
+        let previous = std::mem::replace(&mut self.active_did, did.to_owned());
+        let settings = self.account_settings.remove(did);
+        let state = self.inactive_accounts.remove(did);
+
+        if previous.is_empty() {
+            // starting up, what got loaded is the last active account's already
+            if let Some(settings) = settings {
+                *self.settings.lock().unwrap() = settings;
+            }
+        } else {
+            let old = std::mem::replace(&mut *self.settings.lock().unwrap(), settings.unwrap_or_default());
+            self.account_settings.insert(previous.clone(), old);
+            let view_stack = std::mem::replace(&mut self.view_stack, FrontendMainViewStack::new(self.ctx.clone(), FrontendMainView::Login()));
+            self.inactive_accounts.insert(previous, AccountState { view_stack, profile: self.profile.take(), unread_notifications: std::mem::take(&mut self.unread_notifications) });
+        }
+
+        match state {
+            Some(state) => {
+                self.view_stack = state.view_stack;
+                self.profile = state.profile;
+                self.unread_notifications = state.unread_notifications;
+                true
+            }
+            None => false,
         }
     }
 }
@@ -225,6 +283,12 @@ impl ClientFrontend {
     }
 
     pub fn error_modal(&mut self, heading: &str, err: BlueskyApiError) {
+        // the login modal's already up for this one, it'd only get covered
+        if let BlueskyApiError::Unauthorized(err) = &err
+            && err.error == SESSION_EXPIRED
+        {
+            return;
+        }
         let body = match err {
             BlueskyApiError::BadRequest(err) => format!("Bad Request\n{}\n{}", err.error, err.message),
             BlueskyApiError::Unauthorized(err) => format!("Unauthorized\n{}\n{}", err.error, err.message),
@@ -283,10 +347,18 @@ impl ClientFrontendFlyout {
         self.ctx.animate_bool_with_time(Id::new("flyout shift"), false, 0.0);
     }
 
+    /// Doesn't, while the composer is sending, it has to be around to hear back about it
     pub fn close(&mut self) {
+        if self.busy() {
+            return;
+        }
         self.closing = true;
     }
 
+    fn busy(&self) -> bool {
+        matches!(&self.main, Some(ClientFrontendFlyoutVariant::PostComposerFlyout(flyout)) if flyout.sending)
+    }
+
     /// Should everything under the flyout be interactable?
     /// ALSO RUNS ANIMAITON LOGIC!
     /// params: should render, should let underneath interact, state
@@ -302,16 +374,26 @@ impl ClientFrontendFlyout {
         return (true, state < 0.2, state);
     }
 
-    pub fn render(&mut self, ui: &mut egui::Ui, profile: &Option<ProfileViewDetailed>, backend: &Bridge, image: &ImageCache) -> &str {
+    pub fn render(&mut self, ui: &mut egui::Ui, profile: &Option<ProfileViewDetailed>, backend: &Bridge, image: &ImageCache, settings: &Arc<Mutex<Settings>>, modal: &mut ClientFrontendModal) -> &str {
         if let Some(flyout) = &mut self.main {
             match flyout {
                 ClientFrontendFlyoutVariant::PostComposerFlyout(data) => {
-                    ClientFrontendFlyoutVariant::post_composer(ui, data, profile, image, backend);
+                    ClientFrontendFlyoutVariant::post_composer(ui, data, profile, image, backend, settings);
                     if data.reply.is_some() {
                         return "Reply";
                     }
                     return "New Post";
                 }
+                ClientFrontendFlyoutVariant::SettingsFlyout => {
+                    ClientFrontendFlyoutVariant::settings(ui, &mut settings.lock().unwrap());
+                    return "Settings";
+                }
+                ClientFrontendFlyoutVariant::AccountsFlyout(data) => {
+                    if ClientFrontendFlyoutVariant::accounts(ui, data, image, backend, modal) {
+                        self.closing = true;
+                    }
+                    return "Accounts";
+                }
             }
         }
         "Unhandled Flyout"
@@ -338,6 +420,11 @@ impl eframe::App for ClientFrontend {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         let settings: &Settings = &self.settings.lock().unwrap();
         eframe::set_value(storage, "Settings", settings);
+        let mut account_settings: HashMap<&String, &Settings> = self.account_settings.iter().collect();
+        if !self.active_did.is_empty() {
+            account_settings.insert(&self.active_did, settings);
+        }
+        eframe::set_value(storage, "AccountSettings", &account_settings);
     }
 
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
@@ -411,7 +498,7 @@ impl eframe::App for ClientFrontend {
             if self.active {
                 ui.add_enabled_ui(self.modal.main.is_none() && (self.flyout.get_animation_state().1), |contents| {
                     let close_requested = (self.modal.main.is_none() && flyout_anim_state.1) && go_back;
-                    self.view_stack.render(contents, &self.profile, close_requested, &self.backend, &self.image, &mut self.flyout, &mut self.modal);
+                    self.view_stack.render(contents, &self.profile, close_requested, self.unread_notifications, &self.backend, &self.image, &mut self.flyout, &mut self.modal);
                 });
             } else {
                 puffin::profile_scope!("Loading Screen");
@@ -456,10 +543,6 @@ impl eframe::App for ClientFrontend {
                     let back_button = ui.allocate_rect(back_button_rect, egui::Sense::click());
                     ui.painter().text(back_button_rect.center(), Align2::CENTER_CENTER, "\u{E0BA}", FontId::new(40.0, egui::FontFamily::Name("Segoe Symbols".into())), Color32::WHITE);
 
-                    if back_button.clicked() || (go_back && self.modal.main.is_none()) {
-                        self.flyout.close();
-                    }
-
                     if !flyout_anim_state.1 {
                         let click_off_rect = ctx.screen_rect().with_max_x(content_rect.left());
                         if ui.allocate_rect(click_off_rect, egui::Sense::click()).clicked() {
@@ -470,9 +553,21 @@ impl eframe::App for ClientFrontend {
                     ui.style_mut().visuals.selection.bg_fill = Color32::from_rgb(144, 209, 255); // default light mode selection fill
 
                     ui.allocate_new_ui(content, |flyout_contents| {
-                        let title = self.flyout.render(flyout_contents, &self.profile, &self.backend, &self.image);
+                        let title = self.flyout.render(flyout_contents, &self.profile, &self.backend, &self.image, &self.settings, &mut self.modal);
                         flyout_contents.painter().text(back_button_rect.right_bottom() + vec2(20.0, 0.0), Align2::LEFT_BOTTOM, title, FontId::new(30.0, egui::FontFamily::Name("Segoe Light".into())), Color32::WHITE);
                     });
+                    if let Some(ClientFrontendFlyoutVariant::AccountsFlyout(data)) = &mut self.flyout.main
+                        && let Some(did) = data.open_profile.take()
+                    {
+                        self.view_stack.propose(FrontendMainView::Profile(FrontendProfileView::new(did)));
+                        self.flyout.close();
+                    }
+
+                    // the contents get first go at escape, the composer uses it to put away its suggestions
+                    let escape = ui.input(|r| r.key_pressed(egui::Key::Escape));
+                    if back_button.clicked() || (escape && self.modal.main.is_none()) {
+                        self.flyout.close();
+                    }
                 });
             }
 
@@ -510,6 +605,16 @@ impl eframe::App for ClientFrontend {
                             self.deceptive_link_modal(modal_contents);
                         });
                     }
+                    ClientFrontendModalVariant::DeletePost(_) => {
+                        ui.allocate_new_ui(content, |modal_contents| {
+                            self.delete_post_modal(modal_contents);
+                        });
+                    }
+                    ClientFrontendModalVariant::InteractionSettings(_) => {
+                        ui.allocate_new_ui(content, |modal_contents| {
+                            self.interaction_settings_modal(modal_contents);
+                        });
+                    }
                 };
             }
         });
@@ -0,0 +1,53 @@
+use egui::{vec2, Color32, FontId, Layout};
+
+use crate::{bridge::FrontToBackMsg, frontend::main::ClientFrontend};
+
+pub struct DeletePostModal {
+    /// AT-URI of the post, has to be one of ours
+    pub uri: String,
+    /// Just the start of it, so you know what you're deleting
+    pub text: String,
+}
+
+impl DeletePostModal {
+    pub fn new(uri: String, text: String) -> Self {
+        Self { uri, text }
+    }
+}
+
+impl ClientFrontend {
+    pub fn delete_post_modal(&mut self, ui: &mut egui::Ui) {
+        puffin::profile_function!();
+        let data = if let Some(data) = &mut self.modal.main {
+            match data {
+                crate::frontend::main::ClientFrontendModalVariant::DeletePost(data) => data,
+                _ => panic!("Wrong modal!"),
+            }
+        } else {
+            return;
+        };
+
+        ui.heading(egui::RichText::new("Delete this post?").size(20.0).color(Color32::WHITE).font(FontId::new(20.0, egui::FontFamily::Name("Segoe Light".into()))));
+        ui.label("If you remove this post, you won't be able to recover it.");
+        if !data.text.is_empty() {
+            ui.add(egui::Label::new(egui::RichText::new(&data.text).weak()).truncate());
+        }
+
+        let mut close: bool = false;
+        ui.with_layout(Layout::bottom_up(egui::Align::Min), |ui| {
+            ui.with_layout(Layout::right_to_left(egui::Align::Max), |ui| {
+                if ui.add_sized(vec2(90.0, 32.0), egui::Button::new("Cancel")).clicked() {
+                    close = true;
+                }
+                if ui.add_sized(vec2(90.0, 32.0), egui::Button::new("Delete")).clicked() {
+                    let rkey = data.uri.split("/").last().unwrap().to_owned();
+                    self.backend.backend_commander.send(FrontToBackMsg::DeleteRecordRequest { rkey, nsid: "app.bsky.feed.post".to_owned() }).unwrap();
+                    close = true;
+                }
+            });
+        });
+        if close {
+            self.modal.close();
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::sync::{Arc, Mutex};
+
+use egui::{vec2, Color32, FontId, Layout, Ui};
+
+use crate::{
+    backend::gates::InteractionSettings,
+    bridge::{Bridge, FrontToBackMsg},
+    defs::bsky::{
+        feed::{defs::PostView, ThreatGateAllow},
+        graph::defs::ListView,
+    },
+    frontend::main::ClientFrontend,
+    widgets::spinner::SegoeBootSpinner,
+    BSKY_BLUE,
+};
+
+/// The lexicon caps threadgates at this many rules
+const MAX_REPLY_RULES: usize = 5;
+
+/// Picks who can reply and quote. Used by the composer, and by the modal for posts that are already out.
+pub struct InteractionSettingsEditor {
+    pub settings: InteractionSettings,
+    /// Only the root of a thread gets a threadgate, replies just follow it
+    replies_gateable: bool,
+    lists: Arc<Mutex<Option<Vec<ListView>>>>,
+    lists_requested: bool,
+}
+
+impl InteractionSettingsEditor {
+    pub fn new(settings: InteractionSettings, replies_gateable: bool) -> Self {
+        Self { settings, replies_gateable, lists: Arc::new(Mutex::new(None)), lists_requested: false }
+    }
+
+    fn has_rule(&self, rule: &ThreatGateAllow) -> bool {
+        self.settings.reply_allow.as_ref().is_some_and(|rules| rules.contains(rule))
+    }
+
+    fn set_rule(&mut self, rule: ThreatGateAllow, on: bool) {
+        let rules = self.settings.reply_allow.get_or_insert_with(Vec::new);
+        rules.retain(|existing| *existing != rule);
+        if on {
+            rules.push(rule);
+        }
+    }
+
+    fn rule_checkbox(&mut self, ui: &mut Ui, rule: ThreatGateAllow, label: String) {
+        let mut on = self.has_rule(&rule);
+        let full = self.settings.reply_allow.as_ref().is_some_and(|rules| rules.len() >= MAX_REPLY_RULES);
+        if ui.add_enabled(on || !full, egui::Checkbox::new(&mut on, label)).changed() {
+            self.set_rule(rule, on);
+        }
+    }
+
+    pub fn render(&mut self, ui: &mut Ui, backend: &Bridge) {
+        ui.label(egui::RichText::new("Who can reply").strong());
+        if !self.replies_gateable {
+            ui.weak("Replies follow whatever the start of the thread allows.");
+        } else {
+            if !self.lists_requested {
+                backend.backend_commander.send(FrontToBackMsg::GetOwnListsRequest { lists: self.lists.clone() }).unwrap();
+                self.lists_requested = true;
+            }
+
+            ui.horizontal(|ui| {
+                if ui.radio(self.settings.reply_allow.is_none(), "Everyone").clicked() {
+                    self.settings.reply_allow = None;
+                }
+                if ui.radio(self.settings.reply_allow.as_ref().is_some_and(|rules| rules.is_empty()), "Nobody").clicked() {
+                    self.settings.reply_allow = Some(Vec::new());
+                }
+            });
+            ui.weak("Or only these people:");
+            self.rule_checkbox(ui, ThreatGateAllow::Mention, "Mentioned users".to_owned());
+            self.rule_checkbox(ui, ThreatGateAllow::Follower, "Your followers".to_owned());
+            self.rule_checkbox(ui, ThreatGateAllow::Following, "People you follow".to_owned());
+
+            let lists = self.lists.lock().unwrap().clone();
+            match lists {
+                None => {
+                    ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+                        let (_, rect) = ui.allocate_space(vec2(16.0, 16.0));
+                        SegoeBootSpinner::new().size(16.0).color(BSKY_BLUE).paint_at(ui, rect);
+                        ui.weak("Loading your lists...");
+                    });
+                }
+                Some(lists) => {
+                    let mut entries: Vec<(String, String)> = lists.into_iter().map(|list| (list.uri, list.name)).collect();
+                    // a list that's gone still shows up if the post uses it, so it can be taken off
+                    for rule in self.settings.reply_allow.clone().unwrap_or_default() {
+                        if let ThreatGateAllow::List { list } = rule
+                            && !entries.iter().any(|(uri, _)| *uri == list)
+                        {
+                            let name = list.split("/").last().unwrap_or_default().to_owned();
+                            entries.push((list, name));
+                        }
+                    }
+                    for (list, name) in entries {
+                        self.rule_checkbox(ui, ThreatGateAllow::List { list }, format!("Members of {}", name));
+                    }
+                }
+            }
+        }
+
+        ui.add_space(8.0);
+        let mut quotes_allowed = !self.settings.quotes_disabled;
+        if ui.checkbox(&mut quotes_allowed, "Allow quote posts").changed() {
+            self.settings.quotes_disabled = !quotes_allowed;
+        }
+    }
+}
+
+pub struct InteractionSettingsModal {
+    /// Has to be one of ours
+    pub post: Arc<Mutex<PostView>>,
+    pub editor: InteractionSettingsEditor,
+    /// Read from the postgate, the view doesn't have it. Saving waits for it, so an unchanged save doesn't clear it.
+    quotes_disabled: Arc<Mutex<Option<bool>>>,
+    quotes_requested: bool,
+    quotes_loaded: bool,
+}
+
+impl InteractionSettingsModal {
+    /// Takes the view too, since whoever opens this is usually holding its lock
+    pub fn new(post_mod: Arc<Mutex<PostView>>, post: &PostView) -> Self {
+        let editor = InteractionSettingsEditor::new(InteractionSettings::from_post(post), post.record.reply.is_none());
+        Self { post: post_mod, editor, quotes_disabled: Arc::new(Mutex::new(None)), quotes_requested: false, quotes_loaded: false }
+    }
+}
+
+impl ClientFrontend {
+    pub fn interaction_settings_modal(&mut self, ui: &mut egui::Ui) {
+        puffin::profile_function!();
+        let data = if let Some(data) = &mut self.modal.main {
+            match data {
+                crate::frontend::main::ClientFrontendModalVariant::InteractionSettings(data) => data,
+                _ => panic!("Wrong modal!"),
+            }
+        } else {
+            return;
+        };
+
+        if !data.quotes_requested {
+            let uri = data.post.lock().unwrap().uri.clone();
+            self.backend.backend_commander.send(FrontToBackMsg::GetQuotesDisabledRequest { uri, quotes_disabled: data.quotes_disabled.clone() }).unwrap();
+            data.quotes_requested = true;
+        }
+        if !data.quotes_loaded
+            && let Some(quotes_disabled) = *data.quotes_disabled.lock().unwrap()
+        {
+            data.editor.settings.quotes_disabled = quotes_disabled;
+            data.quotes_loaded = true;
+        }
+
+        ui.heading(egui::RichText::new("Interaction settings").size(20.0).color(Color32::WHITE).font(FontId::new(20.0, egui::FontFamily::Name("Segoe Light".into()))));
+        egui::ScrollArea::vertical().max_height(ui.available_height() - 50.0).show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            data.editor.render(ui, &self.backend);
+        });
+
+        let mut close: bool = false;
+        ui.with_layout(Layout::bottom_up(egui::Align::Min), |ui| {
+            ui.with_layout(Layout::right_to_left(egui::Align::Max), |ui| {
+                if ui.add_sized(vec2(90.0, 32.0), egui::Button::new("Cancel")).clicked() {
+                    close = true;
+                }
+                if ui.add_enabled(data.quotes_loaded, egui::Button::new("Save").min_size(vec2(90.0, 32.0))).clicked() {
+                    let uri = data.post.lock().unwrap().uri.clone();
+                    self.backend.backend_commander.send(FrontToBackMsg::SetInteractionSettingsRequest { uri, settings: data.editor.settings.clone(), post_mod: Some(data.post.clone()) }).unwrap();
+                    close = true;
+                }
+            });
+        });
+        if close {
+            self.modal.close();
+        }
+    }
+}
@@ -6,13 +6,18 @@ pub struct LoginModal {
     pub username: String,
     pub password: String,
     pub password_dots: bool,
+    /// Their PDS, for when their handle doesn't lead to it (like logging in with an email)
+    pub provider: String,
     pub error_msg: String,
     pub interactive: bool,
+    /// The account has email 2FA, and the PDS has sent them a code
+    pub two_factor: bool,
+    pub code: String,
 }
 
 impl LoginModal {
     pub fn new() -> Self {
-        Self { username: "".to_owned(), password: "".to_owned(), password_dots: true, error_msg: "".to_owned(), interactive: true }
+        Self { username: "".to_owned(), password: "".to_owned(), password_dots: true, provider: String::new(), error_msg: "".to_owned(), interactive: true, two_factor: false, code: String::new() }
     }
 }
 
@@ -47,17 +52,48 @@ impl ClientFrontend {
             if ui.put(show_pw_rect, egui::Button::new("").frame(false).min_size(vec2(show_pw_rect.height(), show_pw_rect.height()))).clicked() {
                 data.password_dots = !data.password_dots;
             }
+            ui.style_mut().override_font_id = None;
+
+            TextEdit::singleline(&mut data.provider).min_size(vec2(390.0, 32.0)).font(FontId::proportional(11.0)).vertical_align(egui::Align::Center).margin(Margin::symmetric(10.0, 0.0)).hint_text("Hosting provider (optional)").show(ui);
+
+            if data.two_factor {
+                ui.label(RichText::new("Check your email for a sign in code and enter it below.").color(Color32::WHITE));
+                TextEdit::singleline(&mut data.code).min_size(vec2(390.0, 32.0)).font(FontId::proportional(11.0)).vertical_align(egui::Align::Center).margin(Margin::symmetric(10.0, 0.0)).hint_text("XXXXX-XXXXX").show(ui);
+            }
         });
         let mut close: bool = false;
         ui.with_layout(Layout::bottom_up(egui::Align::Min), |ui| {
             ui.with_layout(Layout::right_to_left(egui::Align::Max), |ui| {
                 if ui.add_sized(vec2(90.0, 32.0), egui::Button::new("Cancel")).clicked() {
+                    // they might have gone off to the browser first
+                    self.backend.backend_commander.send(crate::bridge::FrontToBackMsg::CancelOAuthLogin).unwrap();
                     close = true;
                 }
+                let provider = if data.provider.trim().is_empty() { None } else { Some(data.provider.trim().to_owned()) };
                 if ui.add_sized(vec2(90.0, 32.0), egui::Button::new("Log In")).clicked() {
-                    self.backend.backend_commander.send(crate::bridge::FrontToBackMsg::LoginRequestStandard{
-                        handle: data.username.clone(),
-                        password: data.password.clone()
+                    self.backend.backend_commander.send(crate::bridge::FrontToBackMsg::CancelOAuthLogin).unwrap();
+                    if data.two_factor && !data.code.trim().is_empty() {
+                        self.backend.backend_commander.send(crate::bridge::FrontToBackMsg::LoginRequest2FA {
+                            handle: data.username.clone(),
+                            password: data.password.clone(),
+                            code: data.code.trim().to_owned(),
+                            provider: provider.clone(),
+                        }).unwrap();
+                    } else {
+                        // without a code, the PDS sends a new one
+                        self.backend.backend_commander.send(crate::bridge::FrontToBackMsg::LoginRequestStandard{
+                            handle: data.username.clone(),
+                            password: data.password.clone(),
+                            provider: provider.clone(),
+                        }).unwrap();
+                    }
+                }
+                // no password needed, they log in on their provider's own page
+                if ui.add_sized(vec2(90.0, 32.0), egui::Button::new("Use Browser")).on_hover_text("Log in through your hosting provider in your browser").clicked() {
+                    data.error_msg = "Finish logging in from your browser.".to_owned();
+                    self.backend.backend_commander.send(crate::bridge::FrontToBackMsg::LoginRequestOAuth {
+                        handle: data.username.trim().to_owned(),
+                        provider,
                     }).unwrap();
                 }
             });
@@ -1,3 +1,5 @@
 pub mod important_error;
 pub mod login;
-pub mod deceptive_link;
\ No newline at end of file
+pub mod deceptive_link;
+pub mod delete_post;
+pub mod interaction_settings;
\ No newline at end of file
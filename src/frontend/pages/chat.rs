@@ -0,0 +1,171 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::Local;
+use egui::{vec2, Button, Color32, FontId, Frame, Layout, Margin, Rounding, ScrollArea, Ui};
+
+use crate::{
+    backend::chat::Conversation,
+    bridge::{Bridge, FrontToBackMsg},
+    defs::{bsky::actor::defs::ProfileViewDetailed, chat::convo::defs::MessageVariant},
+    frontend::{
+        circle_button,
+        pages::{convos::CHAT_POLL_INTERVAL, profile::FrontendProfileView, FrontendMainView},
+        viewers::profile_picture::profile_picture_viewer,
+    },
+    image::ImageCache,
+    widgets::{click_context_menu, spinner::SegoeBootSpinner},
+    BSKY_BLUE,
+};
+
+use super::{MainViewProposition, ViewStackReturnInfo};
+
+/// chat.bsky.convo.defs#messageInput maxGraphemes
+const MESSAGE_MAX_LENGTH: usize = 1000;
+
+pub struct FrontendChatView {
+    /// DID of the other member when opened from a profile, so the ConvoResponse can find its way here
+    pub id_cmp: String,
+    pub conversation: Option<Arc<Mutex<Conversation>>>,
+    pub loading: bool,
+    last_poll: Option<f64>,
+    /// Set once updateRead goes out, cleared when the server agrees there's nothing unread
+    read_sent: bool,
+}
+
+impl FrontendChatView {
+    pub fn new(conversation: Arc<Mutex<Conversation>>) -> Self {
+        Self { id_cmp: String::new(), conversation: Some(conversation), loading: false, last_poll: None, read_sent: false }
+    }
+
+    /// For when we only know who we want to talk to, the conversation gets looked up (or made) by the backend
+    pub fn for_member(did: String) -> Self {
+        Self { id_cmp: did, conversation: None, loading: false, last_poll: None, read_sent: false }
+    }
+
+    fn message_viewer(ui: &mut Ui, message: &MessageVariant, you: &str) {
+        let (text, sent_at, sender) = match message {
+            MessageVariant::Message(message) => (egui::RichText::new(&message.text), message.sent_at, &message.sender.did),
+            MessageVariant::Deleted(message) => (egui::RichText::new("Message deleted").italics(), message.sent_at, &message.sender.did),
+            MessageVariant::Unknown => return,
+        };
+        let yours = sender == you;
+        let (layout, fill, text) = if yours {
+            (Layout::right_to_left(egui::Align::Min), BSKY_BLUE, text.color(Color32::WHITE))
+        } else {
+            (Layout::left_to_right(egui::Align::Min), ui.visuals().extreme_bg_color, text)
+        };
+
+        ui.with_layout(layout, |ui| {
+            ui.set_max_width(ui.available_width() * 0.7);
+            Frame::none().fill(fill).rounding(Rounding::ZERO).inner_margin(Margin::symmetric(12.0, 8.0)).show(ui, |ui| {
+                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Wrap);
+                ui.label(text);
+            }).response.on_hover_text(sent_at.with_timezone(&Local).format("%B %-d, %Y at %-I:%M %p").to_string());
+        });
+    }
+
+    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, backend: &Bridge, image: &ImageCache, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
+        puffin::profile_function!();
+        let you = you.as_ref().map(|you| you.did.as_str()).unwrap_or_default();
+
+        let Some(conversation) = self.conversation.clone() else {
+            SegoeBootSpinner::new().size(200.0).color(BSKY_BLUE).paint_at(ui, ui.ctx().screen_rect());
+            if !self.loading {
+                backend.backend_commander.send(FrontToBackMsg::GetConvoForMemberRequest { did: self.id_cmp.clone() }).unwrap();
+                self.loading = true;
+            }
+            return ViewStackReturnInfo { title: Some("Messages".into()), render_back_button: true, handle_back_logic: true, force_back: false };
+        };
+
+        let mut convo = conversation.lock().unwrap();
+        if convo.left {
+            return ViewStackReturnInfo { title: None, render_back_button: true, handle_back_logic: true, force_back: true };
+        }
+        let other = convo.convo.others(you).next().cloned();
+        let title = other.as_ref().map(|other| other.easy_name().to_owned()).unwrap_or("Messages".into());
+
+        // header, who you're talking to and the conversation options
+        ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+            if let Some(other) = &other {
+                if profile_picture_viewer(ui, &other.avatar, [30.0, 30.0], image).clicked() {
+                    new_view.set(FrontendMainView::Profile(FrontendProfileView::new(other.did.clone())));
+                }
+                ui.weak(format!("@{}", other.handle));
+            }
+            if convo.convo.muted {
+                ui.weak(egui::RichText::new("\u{E198}").family(egui::FontFamily::Name("Segoe Symbols".into())));
+            }
+            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                let muted = convo.convo.muted;
+                click_context_menu::click_context_menu(circle_button(ui, "\u{E0C2}", 10.0, 15.0), |guh| {
+                    guh.spacing_mut().item_spacing.y = 0.0;
+                    if guh.add(Button::new(if muted { "Unmute conversation" } else { "Mute conversation" }).min_size(guh.spacing().interact_size)).clicked() {
+                        backend.backend_commander.send(FrontToBackMsg::MuteConvoRequest { conversation: conversation.clone(), mute: !muted }).unwrap();
+                    }
+                    if guh.add(Button::new("Leave conversation").min_size(guh.spacing().interact_size)).clicked() {
+                        backend.backend_commander.send(FrontToBackMsg::LeaveConvoRequest { conversation: conversation.clone() }).unwrap();
+                    }
+                });
+            });
+        });
+
+        let input_height = 50.0;
+        ScrollArea::vertical()
+            .hscroll(false)
+            .max_width(ui.cursor().width())
+            .max_height(ui.available_height() - input_height)
+            .stick_to_bottom(true)
+            .id_salt(format!("{}_ChatScrollview", convo.convo.id))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                if convo.cursor.is_some() {
+                    ui.with_layout(Layout::top_down(egui::Align::Center), |spinner| {
+                        let spinner_rect = spinner.add_sized(vec2(40.0, 40.0), SegoeBootSpinner::new().size(40.0).color(BSKY_BLUE)).rect;
+                        if spinner.is_rect_visible(spinner_rect)
+                            && let Some(cursor) = convo.cursor.take()
+                        {
+                            backend.backend_commander.send(FrontToBackMsg::GetMessagesRequest { cursor, conversation: conversation.clone() }).unwrap();
+                        }
+                    });
+                }
+
+                ui.style_mut().spacing.item_spacing.y = 6.0;
+                for message in convo.messages.iter() {
+                    Self::message_viewer(ui, message, you);
+                }
+            });
+
+        ui.add_space(8.0);
+        ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+            let can_send = !convo.sending && !convo.draft.trim().is_empty() && convo.draft.chars().count() <= MESSAGE_MAX_LENGTH;
+            let send = ui.add_enabled_ui(can_send, |ui| circle_button(ui, "\u{E122}", 15.0, 15.0).on_hover_text("Send")).inner;
+            let sending = convo.sending;
+            let input = ui.add_enabled(!sending, egui::TextEdit::singleline(&mut convo.draft).hint_text("Write a message").font(FontId::proportional(16.0)).desired_width(ui.available_width()));
+            let enter = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if can_send && (send.clicked() || enter) {
+                convo.sending = true;
+                backend.backend_commander.send(FrontToBackMsg::SendMessageRequest { conversation: conversation.clone() }).unwrap();
+                input.request_focus();
+            }
+        });
+
+        // the initial load counts as reading it, and so does anything that comes in while we're looking
+        let loaded = convo.cursor.as_ref().is_none_or(|cursor| !cursor.is_empty());
+        if convo.convo.unread_count == 0 {
+            self.read_sent = false;
+        } else if loaded && !self.read_sent {
+            backend.backend_commander.send(FrontToBackMsg::UpdateReadRequest { conversation: conversation.clone() }).unwrap();
+            self.read_sent = true;
+        }
+        drop(convo);
+
+        let now = ui.input(|i| i.time);
+        if self.last_poll.is_none_or(|last| now - last > CHAT_POLL_INTERVAL) {
+            backend.backend_commander.send(FrontToBackMsg::PollChatLogRequest { convos: None }).unwrap();
+            self.last_poll = Some(now);
+        }
+        ui.ctx().request_repaint_after_secs(CHAT_POLL_INTERVAL as f32);
+
+        ViewStackReturnInfo { title: Some(title), render_back_button: true, handle_back_logic: true, force_back: false }
+    }
+}
@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex};
+
+use egui::{pos2, vec2, Align2, Color32, FontId, Layout, Rect, ScrollArea, Stroke, Ui, UiBuilder};
+
+use crate::{
+    backend::chat::Conversation,
+    bridge::{Bridge, FrontToBackMsg},
+    defs::{bsky::actor::defs::ProfileViewDetailed, chat::convo::defs::{ConvoStatus, MessageVariant}},
+    frontend::{pages::{chat::FrontendChatView, FrontendMainView}, viewers::profile_picture::profile_picture_viewer, CursorListPair},
+    image::ImageCache,
+    widgets::spinner::SegoeBootSpinner,
+    BSKY_BLUE,
+};
+
+use super::{MainViewProposition, ViewStackReturnInfo};
+
+/// How often the chat log gets polled while a chat page is open, in seconds
+pub const CHAT_POLL_INTERVAL: f64 = 5.0;
+
+pub struct FrontendConvosView {
+    pub(crate) convos: Arc<Mutex<CursorListPair<Mutex<Conversation>>>>,
+    last_poll: Option<f64>,
+}
+
+impl FrontendConvosView {
+    pub fn new() -> Self {
+        Self { convos: Arc::new(Mutex::new(CursorListPair { cursor: Some(String::new()), items: Vec::new() })), last_poll: None }
+    }
+
+    fn convo_viewer(ui: &mut Ui, conversation: &Arc<Mutex<Conversation>>, you: &str, image: &ImageCache, new_view: &mut MainViewProposition) {
+        let convo = &conversation.lock().unwrap().convo;
+        let Some(other) = convo.others(you).next() else {
+            return;
+        };
+
+        let res = ui.allocate_new_ui(UiBuilder::new().layout(Layout::left_to_right(egui::Align::Min)).sense(egui::Sense::click()), |ui| {
+            profile_picture_viewer(ui, &other.avatar, [60.0, 60.0], image);
+            ui.with_layout(Layout::top_down(egui::Align::Min), |ui| {
+                ui.style_mut().spacing.item_spacing.y = 4.0;
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(other.easy_name()).font(FontId::proportional(20.0)));
+                    ui.weak(egui::RichText::new(format!("@{}", other.handle)).font(FontId::new(20.0, egui::FontFamily::Name("Segoe Light".into()))));
+                    if convo.muted {
+                        ui.weak(egui::RichText::new("\u{E198}").family(egui::FontFamily::Name("Segoe Symbols".into())));
+                    }
+                    if convo.status == Some(ConvoStatus::Request) {
+                        ui.label(egui::RichText::new("Request").color(BSKY_BLUE));
+                    }
+                });
+
+                let preview = match &convo.last_message {
+                    Some(MessageVariant::Message(message)) => {
+                        let text = message.text.lines().next().unwrap_or_default().to_owned();
+                        if message.sender.did == you { format!("You: {}", text) } else { text }
+                    }
+                    Some(MessageVariant::Deleted(_)) => "Message deleted".to_owned(),
+                    Some(MessageVariant::Unknown) | None => String::new(),
+                };
+                let preview = egui::RichText::new(preview);
+                ui.add(egui::Label::new(if convo.unread_count > 0 { preview.strong() } else { preview.weak() }).truncate());
+            });
+        });
+
+        let rect = res.response.rect;
+        if convo.unread_count > 0 {
+            let badge = Rect::from_center_size(pos2(ui.max_rect().right() - 20.0, rect.center().y), vec2(24.0, 24.0));
+            ui.painter().circle_filled(badge.center(), 12.0, BSKY_BLUE);
+            ui.painter().text(badge.center(), Align2::CENTER_CENTER, convo.unread_count.min(99), FontId::proportional(12.0), Color32::WHITE);
+        }
+        if res.response.hovered() {
+            ui.painter().rect_stroke(rect.expand(4.0), 0.0, Stroke::new(1.0, ui.visuals().weak_text_color()));
+        }
+        if res.response.on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
+            new_view.set(FrontendMainView::Chat(FrontendChatView::new(conversation.clone())));
+        }
+    }
+
+    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, backend: &Bridge, image: &ImageCache, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
+        puffin::profile_function!();
+        let you = you.as_ref().map(|you| you.did.as_str()).unwrap_or_default();
+
+        ScrollArea::vertical().hscroll(false).max_width(ui.cursor().width()).id_salt("ConvosScrollview").show(ui, |ui| {
+            ui.style_mut().spacing.item_spacing.y = 16.0;
+            let convos = self.convos.lock().unwrap();
+            for conversation in convos.items.iter() {
+                if conversation.lock().unwrap().left {
+                    continue;
+                }
+                Self::convo_viewer(ui, conversation, you, image, new_view);
+            }
+            drop(convos);
+
+            ui.with_layout(Layout::top_down(egui::Align::Center), |spinner| {
+                let spinner_rect = spinner.add_sized(vec2(40.0, 40.0), SegoeBootSpinner::new().size(40.0).color(BSKY_BLUE)).rect;
+                if spinner.is_rect_visible(spinner_rect) {
+                    let cursor = self.convos.lock().unwrap().cursor.take();
+                    if let Some(cursor) = cursor {
+                        backend.backend_commander.send(FrontToBackMsg::GetConvosRequest { cursor, convos: self.convos.clone() }).unwrap();
+                    }
+                }
+            });
+        });
+
+        let now = ui.input(|i| i.time);
+        if self.last_poll.is_none_or(|last| now - last > CHAT_POLL_INTERVAL) {
+            backend.backend_commander.send(FrontToBackMsg::PollChatLogRequest { convos: Some(self.convos.clone()) }).unwrap();
+            self.last_poll = Some(now);
+        }
+        ui.ctx().request_repaint_after_secs(CHAT_POLL_INTERVAL as f32);
+
+        ViewStackReturnInfo { title: Some("Messages".into()), render_back_button: true, handle_back_logic: true, force_back: false }
+    }
+}
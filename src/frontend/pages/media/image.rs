@@ -22,11 +22,17 @@ impl FrontendMediaImageView {
 
     pub fn render(&mut self, ui: &mut egui::Ui, image: &ImageCache) {
         let fuck = ui.ctx().screen_rect();
-        match image.get_image(&self.uri) {
-            LoadableImage::Unloaded | LoadableImage::Loading => {
+        let loaded = match image.get_image(&self.uri) {
+            LoadableImage::Unloaded | LoadableImage::Loading => None,
+            LoadableImage::Loaded(texture_id, size) => Some((texture_id, size)),
+            // it's the whole screen, it's always visible
+            LoadableImage::Animated(anim) => Some((anim.frame(ui, fuck), anim.size())),
+        };
+        match loaded {
+            None => {
                 SegoeBootSpinner::new().color(BSKY_BLUE).size(200.0).paint_at(ui, fuck);
             }
-            LoadableImage::Loaded(texture_id, size) => {
+            Some((texture_id, size)) => {
                 let response = ui.allocate_rect(ui.ctx().screen_rect(), egui::Sense::click_and_drag());
 
                 if response.dragged() {
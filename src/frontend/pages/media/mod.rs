@@ -1,7 +1,7 @@
 pub mod image;
 pub mod video;
 
-use egui::{pos2, vec2, Align2, Color32, FontId, Rounding};
+use egui::{vec2, Align2, Color32, FontId, Rounding};
 use image::FrontendMediaImageView;
 use video::FrontendMediaVideoView;
 
@@ -22,8 +22,8 @@ impl FrontendMediaViewVariant {
             FrontendMediaViewVariant::Image(data) => {
                 data.render(ui, image);
             }
-            FrontendMediaViewVariant::Video(_) => {
-                ui.painter().text(pos2(ui.cursor().center().x, ui.ctx().screen_rect().center().y), Align2::CENTER_CENTER, "Videos are not supported yet", FontId::proportional(50.0), ui.visuals().text_color());
+            FrontendMediaViewVariant::Video(data) => {
+                data.render(ui);
             }
         }
         // this is down here so it's always above the image or video
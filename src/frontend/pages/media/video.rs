@@ -1 +1,184 @@
-pub struct FrontendMediaVideoView {}
+use std::time::{Duration, Instant};
+
+use egui::{pos2, vec2, Align2, Color32, FontId, Rect, Rounding, Sense};
+
+use crate::{
+    defs::bsky::embed::video,
+    video::{PlayerStatus, VideoPlayer},
+    widgets::spinner::SegoeBootSpinner,
+    BSKY_BLUE,
+};
+
+/// How long the controls stick around after the mouse stops moving
+const CONTROLS_TIMEOUT: Duration = Duration::from_secs(3);
+const CONTROLS_HEIGHT: f32 = 60.0;
+
+pub struct FrontendMediaVideoView {
+    video: video::View,
+    player: VideoPlayer,
+    show_captions: bool,
+    /// Where the seek bar is being dragged to, only seeks on release
+    scrub: Option<f64>,
+    last_interaction: Instant,
+}
+
+fn format_time(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn symbol_button(ui: &mut egui::Ui, rect: Rect, glyph: &str, enabled: bool) -> egui::Response {
+    let sense = if enabled { Sense::click() } else { Sense::hover() };
+    let response = ui.allocate_rect(rect, sense);
+    let color = if !enabled {
+        Color32::GRAY
+    } else if response.hovered() {
+        BSKY_BLUE
+    } else {
+        Color32::WHITE
+    };
+    ui.painter().circle_stroke(rect.center(), rect.height() / 2.0 - 2.0, egui::Stroke::new(2.0, color));
+    ui.painter().text(rect.center(), Align2::CENTER_CENTER, glyph, FontId::new(rect.height() / 2.0, egui::FontFamily::Name("Segoe Symbols".into())), color);
+    if enabled { response.on_hover_cursor(egui::CursorIcon::PointingHand) } else { response }
+}
+
+impl FrontendMediaVideoView {
+    pub fn new(ctx: egui::Context, video: video::View) -> Self {
+        let player = VideoPlayer::new(ctx, &video.playlist);
+        Self { video, player, show_captions: true, scrub: None, last_interaction: Instant::now() }
+    }
+
+    pub fn render(&mut self, ui: &mut egui::Ui) {
+        let screen = ui.ctx().screen_rect();
+        self.player.tick(ui.ctx());
+
+        if ui.input(|i| i.pointer.delta() != egui::Vec2::ZERO || i.pointer.any_down()) {
+            self.last_interaction = Instant::now();
+        }
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Space)) {
+            self.player.toggle_playback();
+            self.last_interaction = Instant::now();
+        }
+
+        let status = self.player.status();
+        if let PlayerStatus::Failed(err) = &status {
+            ui.painter().text(screen.center(), Align2::CENTER_CENTER, format!("Couldn't play this video\n{}", err), FontId::proportional(30.0), Color32::WHITE);
+            return;
+        }
+
+        let frame_size = if let Some((_, size)) = self.player.frame() {
+            size
+        } else if let Some(ratio) = &self.video.aspect_ratio {
+            vec2(ratio.width as f32, ratio.height as f32)
+        } else {
+            vec2(16.0, 9.0)
+        };
+
+        let frame_ratio = frame_size.x / frame_size.y;
+        let view_ratio = screen.width() / screen.height();
+        let video_rect = if frame_ratio > view_ratio {
+            // wider than the view
+            Rect::from_center_size(screen.center(), vec2(screen.width(), screen.width() / frame_ratio))
+        } else {
+            // taller than the view
+            Rect::from_center_size(screen.center(), vec2(frame_ratio * screen.height(), screen.height()))
+        };
+
+        let video_response = ui.allocate_rect(screen, Sense::click());
+        if video_response.clicked() {
+            self.player.toggle_playback();
+        }
+
+        if let Some((texture_id, _)) = self.player.frame() {
+            ui.painter().image(texture_id, video_rect, Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
+        }
+        if matches!(status, PlayerStatus::Loading) || self.player.is_buffering() {
+            SegoeBootSpinner::new().color(BSKY_BLUE).size(100.0).paint_at(ui, Rect::from_center_size(screen.center(), vec2(100.0, 100.0)));
+        }
+
+        let show_controls = !self.player.is_playing() || self.scrub.is_some() || self.last_interaction.elapsed() < CONTROLS_TIMEOUT;
+        if show_controls && self.player.is_playing() {
+            // so they actually go away when the time's up
+            ui.ctx().request_repaint_after(CONTROLS_TIMEOUT);
+        }
+
+        let bar = Rect::from_min_max(pos2(screen.left(), screen.bottom() - CONTROLS_HEIGHT), screen.right_bottom());
+        let caption_bottom = if show_controls { bar.top() } else { screen.bottom() } - 20.0;
+
+        if self.show_captions
+            && let Some(caption) = self.player.caption()
+        {
+            let galley = ui.painter().layout(caption, FontId::proportional(28.0), Color32::WHITE, screen.width() * 0.8);
+            let text_rect = Rect::from_center_size(pos2(screen.center().x, caption_bottom - galley.size().y / 2.0), galley.size());
+            ui.painter().rect_filled(text_rect.expand(6.0), Rounding::ZERO, Color32::from_black_alpha(192));
+            ui.painter().galley(text_rect.left_top(), galley, Color32::WHITE);
+        }
+
+        if !show_controls {
+            return;
+        }
+
+        ui.painter().rect_filled(bar, Rounding::ZERO, Color32::from_black_alpha(192));
+        let button_size = vec2(40.0, 40.0);
+        let mut x = bar.left() + 40.0;
+
+        let play_glyph = if self.player.is_playing() { "\u{E103}" } else { "\u{E102}" };
+        if symbol_button(ui, Rect::from_min_size(pos2(x, bar.center().y - 20.0), button_size), play_glyph, true).clicked() {
+            self.player.toggle_playback();
+        }
+        x += button_size.x + 20.0;
+
+        let duration = self.player.duration();
+        let shown_time = self.scrub.unwrap_or(self.player.position());
+        let time_text = format!("{} / {}", format_time(shown_time), format_time(duration));
+        let time_rect = ui.painter().text(pos2(x, bar.center().y), Align2::LEFT_CENTER, time_text, FontId::proportional(18.0), Color32::WHITE);
+        x = time_rect.right() + 20.0;
+
+        // right side, laid out backwards
+        let mut right = bar.right() - 40.0;
+
+        let has_audio = self.player.has_audio();
+        let mute_glyph = if self.player.is_muted() || !has_audio { "\u{E198}" } else { "\u{E15D}" };
+        right -= button_size.x;
+        let mute = symbol_button(ui, Rect::from_min_size(pos2(right, bar.center().y - 20.0), button_size), mute_glyph, has_audio);
+        if mute.clicked() {
+            self.player.set_muted(!self.player.is_muted());
+        }
+        if !has_audio {
+            mute.on_hover_text("No audio output");
+        }
+
+        if self.player.has_captions() {
+            right -= button_size.x + 10.0;
+            let captions = symbol_button(ui, Rect::from_min_size(pos2(right, bar.center().y - 20.0), button_size), "\u{E190}", true);
+            if self.show_captions {
+                ui.painter().circle_filled(captions.rect.center_bottom() + vec2(0.0, 4.0), 2.0, BSKY_BLUE);
+            }
+            if captions.clicked() {
+                self.show_captions = !self.show_captions;
+            }
+        }
+        right -= 20.0;
+
+        let seek_rect = Rect::from_min_max(pos2(x, bar.center().y - 10.0), pos2(right, bar.center().y + 10.0));
+        if seek_rect.width() > 0.0 && duration > 0.0 {
+            let seek = ui.allocate_rect(seek_rect, Sense::click_and_drag()).on_hover_cursor(egui::CursorIcon::PointingHand);
+            let track = Rect::from_center_size(seek_rect.center(), vec2(seek_rect.width(), 4.0));
+
+            if let Some(pointer) = seek.interact_pointer_pos() {
+                let t = ((pointer.x - track.left()) / track.width()).clamp(0.0, 1.0) as f64;
+                self.scrub = Some(t * duration);
+            }
+            if (seek.drag_stopped() || seek.clicked())
+                && let Some(time) = self.scrub.take()
+            {
+                self.player.seek(time);
+            }
+
+            let progress = (self.scrub.unwrap_or(self.player.position()) / duration).clamp(0.0, 1.0) as f32;
+            ui.painter().rect_filled(track, Rounding::ZERO, Color32::from_white_alpha(64));
+            ui.painter().rect_filled(track.with_max_x(track.left() + track.width() * progress), Rounding::ZERO, BSKY_BLUE);
+            ui.painter().rect_filled(Rect::from_center_size(pos2(track.left() + track.width() * progress, track.center().y), vec2(6.0, 20.0)), Rounding::ZERO, Color32::WHITE);
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use egui::{pos2, vec2, Align2, FontId, Rect, Ui, UiBuilder};
+use chat::FrontendChatView;
+use convos::FrontendConvosView;
 use media::FrontendMediaViewVariant;
+use notifications::FrontendNotificationsView;
 use profile::FrontendProfileView;
 use profile_list::FrontendProfileListVariant;
+use quotes::FrontendQuotesView;
+use search::FrontendSearchView;
+use tag::FrontendTagView;
 use thread::FrontendThreadView;
 use timeline::FrontendTimelineView;
 
@@ -9,12 +15,18 @@ use crate::{bridge::Bridge, defs::bsky::actor::defs::ProfileViewDetailed, image:
 
 use super::main::{ClientFrontendFlyout, ClientFrontendModal};
 
+pub mod chat;
+pub mod convos;
 pub mod landing;
 pub mod media;
+pub mod notifications;
 pub mod profile;
 pub mod thread;
 pub mod timeline;
 pub mod profile_list;
+pub mod search;
+pub mod tag;
+pub mod quotes;
 
 pub enum FrontendMainView {
     Login(),
@@ -23,6 +35,12 @@ pub enum FrontendMainView {
     Profile(FrontendProfileView),
     Media(FrontendMediaViewVariant),
     ProfileList(FrontendProfileListVariant),
+    Notifications(FrontendNotificationsView),
+    Convos(FrontendConvosView),
+    Chat(FrontendChatView),
+    Search(FrontendSearchView),
+    Tag(FrontendTagView),
+    Quotes(FrontendQuotesView),
 }
 
 pub struct ViewStackReturnInfo {
@@ -78,6 +96,11 @@ impl FrontendMainViewStack {
         self.stack.push(to);
     }
 
+    /// Pushes a page from outside the pages, it slides in on the next frame
+    pub fn propose(&mut self, to: FrontendMainView) {
+        self.propose.set(to);
+    }
+
     pub fn pop(&mut self) {
         if self.stack.len() < 2 {
             return;
@@ -87,7 +110,7 @@ impl FrontendMainViewStack {
         self.stack.pop();
     }
 
-    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, close_requested: bool, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, modal: &mut ClientFrontendModal) {
+    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, close_requested: bool, unread_notifications: usize, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, modal: &mut ClientFrontendModal) {
         if let Some(guh) = self.propose.0.take() {
             self.ctx.animate_bool_with_time("FrontendMainViewStackSlide".into(), false, 0.0);
             self.ctx.animate_bool_with_time("FrontendMainViewStackTitleSlide".into(), false, 0.0);
@@ -108,11 +131,17 @@ impl FrontendMainViewStack {
                 FrontendMainView::landing(&mut view, modal);
                 ViewStackReturnInfo { title: None, render_back_button: false, handle_back_logic: false, force_back: false }
             }
-            FrontendMainView::Timeline(data) =>    data.render(&mut view, you, modal, backend, image, flyout, &mut self.propose),
-            FrontendMainView::Thread(data) =>      data.render(&mut view,      modal, backend, image, flyout, &mut self.propose),
-            FrontendMainView::Profile(data) =>     data.render(&mut view,      modal, backend, image, flyout, &mut self.propose),
+            FrontendMainView::Timeline(data) =>    data.render(&mut view, you, unread_notifications, modal, backend, image, flyout, &mut self.propose),
+            FrontendMainView::Thread(data) =>      data.render(&mut view, you, modal, backend, image, flyout, &mut self.propose),
+            FrontendMainView::Profile(data) =>     data.render(&mut view, you, modal, backend, image, flyout, &mut self.propose),
             FrontendMainView::Media(data) =>       data.render(&mut view,                      image,         &mut self.propose),
             FrontendMainView::ProfileList(data) => data.render(&mut view,             backend, image,         &mut self.propose),
+            FrontendMainView::Notifications(data) => data.render(&mut view, you,    modal, backend, image, flyout, &mut self.propose),
+            FrontendMainView::Convos(data) =>      data.render(&mut view, you,        backend, image,         &mut self.propose),
+            FrontendMainView::Chat(data) =>        data.render(&mut view, you,        backend, image,         &mut self.propose),
+            FrontendMainView::Search(data) =>      data.render(&mut view, you, modal, backend, image, flyout, &mut self.propose),
+            FrontendMainView::Tag(data) =>         data.render(&mut view, you, modal, backend, image, flyout, &mut self.propose),
+            FrontendMainView::Quotes(data) =>      data.render(&mut view, you, modal, backend, image, flyout, &mut self.propose),
         };
 
         if let Some(title) = &inf.title {
@@ -0,0 +1,141 @@
+use std::sync::{Arc, Mutex};
+
+use egui::{vec2, Color32, FontId, Layout, ScrollArea, Stroke, Ui, UiBuilder};
+
+use crate::{
+    backend::notifications::{NotificationGroup, NotificationList},
+    bridge::{Bridge, FrontToBackMsg},
+    defs::bsky::{actor::defs::ProfileViewDetailed, notification::defs::Reason},
+    frontend::{
+        main::{ClientFrontendFlyout, ClientFrontendModal},
+        pages::{profile::FrontendProfileView, thread::FrontendThreadView, FrontendMainView},
+        viewers::{post::post_viewer, profile_picture::profile_picture_viewer},
+    },
+    image::ImageCache,
+    widgets::spinner::SegoeBootSpinner,
+    BSKY_BLUE,
+};
+
+use super::{MainViewProposition, ViewStackReturnInfo};
+
+pub struct FrontendNotificationsView {
+    pub notifications: Arc<Mutex<NotificationList>>,
+    /// updateSeen only needs to go out once per visit
+    seen_sent: bool,
+}
+
+impl FrontendNotificationsView {
+    pub fn new() -> Self {
+        Self { notifications: Arc::new(Mutex::new(NotificationList::new())), seen_sent: false }
+    }
+
+    fn reason_text(group: &NotificationGroup) -> (&'static str, &'static str) {
+        match group.reason {
+            Reason::Like => ("\u{E209}", "liked your post"),
+            Reason::Repost => ("\u{E207}", "reposted your post"),
+            Reason::Follow => ("\u{E1E2}", "followed you"),
+            Reason::Mention => ("\u{E206}", "mentioned you"),
+            Reason::Reply => ("\u{E206}", "replied to you"),
+            Reason::Quote => ("\u{E207}", "quoted your post"),
+            Reason::StarterpackJoined => ("\u{E125}", "signed up with your starter pack"),
+            Reason::Unknown => ("\u{E11B}", "did something we don't understand yet"),
+        }
+    }
+
+    fn render_group(ui: &mut Ui, group: &NotificationGroup, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) {
+        // replies, mentions and quotes are posts in their own right, so show them like one
+        if matches!(group.reason, Reason::Reply | Reason::Mention | Reason::Quote)
+            && let Some(post) = &group.subject
+        {
+            let (icon, text) = Self::reason_text(group);
+            ui.style_mut().spacing.item_spacing.y = 2.0;
+            ui.with_layout(Layout::left_to_right(egui::Align::TOP), |name| {
+                name.allocate_space(vec2(60.0, 2.0));
+                name.style_mut().spacing.item_spacing.x = 0.0;
+                name.weak(format!("{} ", icon));
+                if name.link(egui::RichText::new(group.authors[0].display_name.clone().filter(|dn| !dn.is_empty()).unwrap_or(group.authors[0].handle.clone())).color(name.visuals().weak_text_color())).clicked() {
+                    new_view.set(FrontendMainView::Profile(FrontendProfileView::new(group.authors[0].did.clone())));
+                }
+                name.weak(format!(" {}", text));
+            });
+            ui.style_mut().spacing.item_spacing.y = 10.0;
+            let res = post_viewer(ui, post.clone(), false, you, modal, backend, image, flyout, new_view);
+            if !group.is_read {
+                ui.painter().vline(res.rect.left() - 10.0, res.rect.y_range(), Stroke::new(4.0, BSKY_BLUE));
+            }
+            return;
+        }
+
+        let res = ui.allocate_new_ui(UiBuilder::new().layout(Layout::left_to_right(egui::Align::Min)), |ui| {
+            let (icon, text) = Self::reason_text(group);
+            let (_, icon_rect) = ui.allocate_space(vec2(60.0, 30.0));
+            ui.painter().text(icon_rect.center(), egui::Align2::CENTER_CENTER, icon, FontId::new(24.0, egui::FontFamily::Name("Segoe Symbols".into())), BSKY_BLUE);
+
+            ui.with_layout(Layout::top_down(egui::Align::Min), |ui| {
+                ui.style_mut().spacing.item_spacing = vec2(4.0, 8.0);
+                ui.with_layout(Layout::left_to_right(egui::Align::Min), |avatars| {
+                    for author in group.authors.iter().take(8) {
+                        if profile_picture_viewer(avatars, &author.avatar, [30.0, 30.0], image).clicked() {
+                            new_view.set(FrontendMainView::Profile(FrontendProfileView::new(author.did.clone())));
+                        }
+                    }
+                });
+
+                let first = &group.authors[0];
+                let first_name = first.display_name.clone().filter(|dn| !dn.is_empty()).unwrap_or(first.handle.clone());
+                let line = match group.authors.len() {
+                    1 => format!("{} {}", first_name, text),
+                    2 => format!("{} and 1 other {}", first_name, text),
+                    n => format!("{} and {} others {}", first_name, n - 1, text),
+                };
+                ui.label(egui::RichText::new(line).font(FontId::proportional(14.0)));
+
+                if let Some(post) = &group.subject {
+                    let post = post.lock().unwrap();
+                    if !post.record.text.is_empty() {
+                        let text = ui.add(egui::Label::new(egui::RichText::new(&post.record.text).weak()).sense(egui::Sense::click()));
+                        if text.on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
+                            new_view.set(FrontendMainView::Thread(FrontendThreadView::new(post.uri.clone())));
+                        }
+                    }
+                }
+            });
+        });
+
+        if !group.is_read {
+            ui.painter().vline(res.response.rect.left() - 10.0, res.response.rect.y_range(), Stroke::new(4.0, BSKY_BLUE));
+        }
+        ui.painter().hline(res.response.rect.x_range(), res.response.rect.bottom() + 10.0, Stroke::new(1.0, Color32::from_gray(64)));
+    }
+
+    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
+        puffin::profile_function!();
+
+        ScrollArea::vertical().hscroll(false).max_width(ui.cursor().width()).id_salt("NotificationsScrollview").show(ui, |ui| {
+            let notifications = self.notifications.lock().unwrap();
+            for group in notifications.groups.iter() {
+                Self::render_group(ui, group, you, modal, backend, image, flyout, new_view);
+            }
+
+            // first page is in, everything on it has been seen now
+            if !self.seen_sent && !notifications.groups.is_empty() {
+                backend.backend_commander.send(FrontToBackMsg::UpdateSeenNotificationsRequest).unwrap();
+                self.seen_sent = true;
+            }
+
+            drop(notifications);
+            ui.with_layout(Layout::top_down(egui::Align::Center), |spinner| {
+                let spinner_rect = spinner.add_sized(vec2(40.0, 40.0), SegoeBootSpinner::new().size(40.0).color(BSKY_BLUE)).rect;
+                if spinner.is_rect_visible(spinner_rect) {
+                    // taking the cursor means we won't send duplicates while the backend works on it
+                    let cursor = self.notifications.lock().unwrap().cursor.take();
+                    if let Some(cursor) = cursor {
+                        backend.backend_commander.send(FrontToBackMsg::GetNotificationsRequest { cursor, notifications: self.notifications.clone() }).unwrap();
+                    }
+                }
+            });
+        });
+
+        ViewStackReturnInfo { title: Some("Notifications".into()), render_back_button: true, handle_back_logic: true, force_back: false }
+    }
+}
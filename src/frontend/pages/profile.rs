@@ -3,20 +3,22 @@ use std::sync::{Arc, Mutex};
 use egui::{pos2, vec2, Align, Align2, Color32, FontId, Id, Layout, Rect, Rounding, ScrollArea, Ui, UiBuilder};
 use puffin::profile_scope;
 
+use chrono::Utc;
+
 use crate::{
+    backend::record::BlueskyApiRecord,
     bridge::{Bridge, FrontToBackMsg},
-    defs::bsky::{actor::defs::ProfileViewDetailed, feed::defs::FeedCursorPair},
+    defs::bsky::{actor::defs::{ProfileAssociatedChatAllowIncoming, ProfileViewDetailed}, feed::defs::FeedCursorPair, graph::{Block, Follow}},
     frontend::{main::{ClientFrontendFlyout, ClientFrontendModal}, viewers::feed_post::feed_post_viewer},
     image::{ImageCache, LoadableImage},
     widgets::spinner::SegoeBootSpinner,
     BSKY_BLUE,
 };
-use crate::frontend::CursorListPair;
 use crate::frontend::pages::profile_list::FrontendProfileListVariant;
-use super::{profile_list, FrontendMainView, MainViewProposition, ViewStackReturnInfo};
+use super::{chat::FrontendChatView, profile_list, FrontendMainView, MainViewProposition, ViewStackReturnInfo};
 #[derive(Debug)]
 pub struct FrontendProfileView {
-    pub profile_data: Option<ProfileViewDetailed>,
+    pub profile_data: Option<Arc<Mutex<ProfileViewDetailed>>>,
     pub id_cmp: String,
     pub loading: bool,
     pub posts: Option<Arc<Mutex<FeedCursorPair>>>,
@@ -33,7 +35,7 @@ impl FrontendProfileView {
     pub fn new(did: String) -> Self {
         Self { profile_data: None, id_cmp: did.clone(), loading: false, posts: None, ctx: None, id: Id::new(format!("{}_profile_scrollview", did)) }
     }
-    pub fn render(&mut self, ui: &mut Ui, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
+    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
         puffin::profile_function!();
         ui.style_mut().spacing.scroll.floating = false;
         ui.style_mut().spacing.scroll.bar_width = 18.0;
@@ -41,7 +43,9 @@ impl FrontendProfileView {
         if self.ctx.is_none() {
             self.ctx = Some(ui.ctx().clone());
         }
-        if let Some(profile) = &self.profile_data {
+        if let Some(profile_mod) = &self.profile_data {
+            let profile = profile_mod.lock().unwrap();
+            let is_you = you.as_ref().is_some_and(|you| you.did == profile.did);
             let right_pad = ui.ctx().screen_rect().width() - ui.cursor().right();
             let title_pos = pos2(ui.cursor().left(), ui.cursor().top() - 40.0);
             let funny_rect = ui.cursor().with_max_x(ui.ctx().screen_rect().right()).with_min_x(ui.ctx().screen_rect().left()).with_max_y(ui.ctx().screen_rect().bottom());
@@ -67,12 +71,17 @@ impl FrontendProfileView {
                         let (_, rect1) = ui.allocate_space(vec2(height * 1.5, panel_height));
 
                         if let Some(pfp) = &profile.banner {
-                            match image.get_image(pfp) {
-                                LoadableImage::Unloaded | LoadableImage::Loading => {
+                            let loaded = match image.get_image(pfp) {
+                                LoadableImage::Unloaded | LoadableImage::Loading => None,
+                                LoadableImage::Loaded(texture_id, size) => Some((texture_id, size)),
+                                LoadableImage::Animated(anim) => Some((anim.frame(ui, rect0), anim.size())),
+                            };
+                            match loaded {
+                                None => {
                                     ui.painter().rect_filled(rect0, Rounding::ZERO, Color32::GRAY);
                                     SegoeBootSpinner::new().size(200.0).color(Color32::from_black_alpha(64)).paint_at(ui, rect0);
                                 }
-                                LoadableImage::Loaded(texture_id, size) => {
+                                Some((texture_id, size)) => {
                                     let ratio_src = size.x / size.y;
                                     let ratio_dst = rect0.width() / rect0.height();
                                     if ratio_dst < 1.0 {
@@ -120,6 +129,9 @@ impl FrontendProfileView {
                                 LoadableImage::Loaded(texture_id, _) => {
                                     ui.painter().image(texture_id, pfp_rect, Rect { min: pos2(0.0, 0.0), max: pos2(1.0, 1.0) }, Color32::WHITE);
                                 }
+                                LoadableImage::Animated(anim) => {
+                                    ui.painter().image(anim.frame(ui, pfp_rect), pfp_rect, Rect { min: pos2(0.0, 0.0), max: pos2(1.0, 1.0) }, Color32::WHITE);
+                                }
                             }
                         } else {
                             ui.painter().rect_filled(pfp_rect, Rounding::ZERO, BSKY_BLUE);
@@ -149,25 +161,26 @@ impl FrontendProfileView {
                             }
                         }
 
-                        //ui.disable();
                         if let Some(follows_count) = &profile.follows_count {
-                            ui.add_enabled_ui(false, |ui| {
-                                let button = ui.allocate_response(vec2(height * 0.5, panel_height), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
-                                ui.painter().rect_filled(button.rect, Rounding::ZERO, ui.style().visuals.extreme_bg_color);
+                            let button = ui.allocate_response(vec2(height * 0.5, panel_height), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
+                            ui.painter().rect_filled(button.rect, Rounding::ZERO, ui.style().visuals.extreme_bg_color);
 
-                                let text = if let Some(viewer) = &profile.viewer {
-                                    if viewer.followed_by.is_some() {
-                                        "Following (inc. You!)"
-                                    } else {
-                                        "Following"
-                                    }
+                            let text = if let Some(viewer) = &profile.viewer {
+                                if viewer.followed_by.is_some() {
+                                    "Following (inc. You!)"
                                 } else {
                                     "Following"
-                                };
+                                }
+                            } else {
+                                "Following"
+                            };
 
-                                ui.painter().text(button.rect.center() - vec2(0.0, big_text_size / 4.0), Align2::CENTER_BOTTOM, follows_count, FontId::proportional(big_text_size), ui.style().visuals.text_color());
-                                ui.painter().text(button.rect.center() + vec2(0.0, big_text_size * 1.2), Align2::CENTER_TOP, text, FontId::proportional(small_text_size), ui.style().visuals.text_color());
-                            });
+                            ui.painter().text(button.rect.center() - vec2(0.0, big_text_size / 4.0), Align2::CENTER_BOTTOM, follows_count, FontId::proportional(big_text_size), ui.style().visuals.text_color());
+                            ui.painter().text(button.rect.center() + vec2(0.0, big_text_size * 1.2), Align2::CENTER_TOP, text, FontId::proportional(small_text_size), ui.style().visuals.text_color());
+
+                            if button.clicked() {
+                                new_view.set(FrontendMainView::ProfileList(FrontendProfileListVariant::Following(profile_list::ListData::new(profile.did.clone()))));
+                            }
                         }
 
                         if let Some(followers_count) = &profile.followers_count {
@@ -188,12 +201,7 @@ impl FrontendProfileView {
                             ui.painter().text(button.rect.center() + vec2(0.0, big_text_size * 1.2), Align2::CENTER_TOP, text, FontId::proportional(small_text_size), ui.style().visuals.text_color());
 
                             if button.clicked() {
-                                new_view.set(FrontendMainView::ProfileList(FrontendProfileListVariant::Followers(
-                                    profile_list::ListData {
-                                        did: profile.did.clone(),
-                                        profiles: Arc::new(Mutex::new(CursorListPair { cursor: Some(String::new()), items: Vec::new() })),
-                                    }
-                                )))
+                                new_view.set(FrontendMainView::ProfileList(FrontendProfileListVariant::Followers(profile_list::ListData::new(profile.did.clone()))));
                             }
                         }
                     });
@@ -211,8 +219,32 @@ impl FrontendProfileView {
                             #[cfg(target_os = "windows")]
                             let _ = std::process::Command::new("cmd.exe").arg("/C").arg("start").arg(url).spawn();
                         }
-                        ui.disable();
-                        if let Some(viewer) = &profile.viewer {
+
+                        {
+                            profile_scope!("Message Button");
+                            // no chat declaration means the default, which is only people they follow
+                            let allow_incoming = profile.associated.as_ref().and_then(|associated| associated.chat.as_ref()).map(|chat| chat.allow_incoming.clone()).unwrap_or(ProfileAssociatedChatAllowIncoming::Following);
+                            let followed_by = profile.viewer.as_ref().is_some_and(|viewer| viewer.followed_by.is_some());
+                            let (allowed, reason) = match allow_incoming {
+                                ProfileAssociatedChatAllowIncoming::All => (true, ""),
+                                ProfileAssociatedChatAllowIncoming::Following => (followed_by, "Only accepts messages from people they follow"),
+                                ProfileAssociatedChatAllowIncoming::None => (false, "Doesn't accept messages"),
+                            };
+
+                            ui.add_enabled_ui(allowed, |ui| {
+                                let button = ui.allocate_response(vec2(height * 0.5, panel_height), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand).on_disabled_hover_text(reason);
+                                ui.painter().rect_filled(button.rect, Rounding::ZERO, ui.style().visuals.extreme_bg_color);
+
+                                ui.painter().text(button.rect.center() - vec2(0.0, big_text_size / 4.0), Align2::CENTER_BOTTOM, "\u{E15F}", FontId::new(big_text_size, egui::FontFamily::Name("Segoe Symbols".into())), ui.style().visuals.text_color());
+                                ui.painter().text(button.rect.center() + vec2(0.0, big_text_size * 1.2), Align2::CENTER_TOP, "Message", FontId::proportional(small_text_size), ui.style().visuals.text_color());
+                                if button.clicked() {
+                                    new_view.set(FrontendMainView::Chat(FrontendChatView::for_member(profile.did.clone())));
+                                }
+                            });
+                        }
+                        if let Some(viewer) = &profile.viewer
+                            && !is_you
+                        {
                             profile_scope!("Follow Button");
                             let button = ui.allocate_response(vec2(height * 0.5, panel_height), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
                             ui.painter().rect_filled(button.rect, Rounding::ZERO, ui.style().visuals.extreme_bg_color);
@@ -229,8 +261,60 @@ impl FrontendProfileView {
 
                             ui.painter().text(button.rect.center() - vec2(0.0, big_text_size / 4.0), Align2::CENTER_BOTTOM, icon, FontId::new(big_text_size, egui::FontFamily::Name("Segoe Symbols".into())), ui.style().visuals.text_color());
                             ui.painter().text(button.rect.center() + vec2(0.0, big_text_size * 1.2), Align2::CENTER_TOP, text, FontId::proportional(small_text_size), ui.style().visuals.text_color());
+                            if button.clicked() {
+                                if let Some(following) = &viewer.following {
+                                    backend.backend_commander.send(FrontToBackMsg::DeleteRecordUnderProfileRequest {
+                                        rkey: following.split("/").last().unwrap().to_owned(),
+                                        nsid: "app.bsky.graph.follow".to_owned(),
+                                        profile_mod: profile_mod.clone(),
+                                    }).unwrap();
+                                } else {
+                                    backend.backend_commander.send(FrontToBackMsg::CreateRecordUnderProfileRequest(
+                                        BlueskyApiRecord::Follow(Follow { subject: profile.did.clone(), created_at: Utc::now() }),
+                                        profile_mod.clone(),
+                                    )).unwrap();
+                                }
+                            }
                         }
                     });
+                    if let Some(viewer) = &profile.viewer
+                        && !is_you
+                    {
+                        ui.with_layout(Layout::top_down(egui::Align::Min), |ui| {
+                            profile_scope!("Block Button");
+                            let button = ui.allocate_response(vec2(height * 0.5, panel_height), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
+                            ui.painter().rect_filled(button.rect, Rounding::ZERO, ui.style().visuals.extreme_bg_color);
+
+                            let text = if viewer.blocking.is_some() { "Unblock" } else { "Block" };
+                            ui.painter().text(button.rect.center() - vec2(0.0, big_text_size / 4.0), Align2::CENTER_BOTTOM, "\u{E1E0}", FontId::new(big_text_size, egui::FontFamily::Name("Segoe Symbols".into())), ui.style().visuals.text_color());
+                            ui.painter().text(button.rect.center() + vec2(0.0, big_text_size * 1.2), Align2::CENTER_TOP, text, FontId::proportional(small_text_size), ui.style().visuals.text_color());
+                            if button.clicked() {
+                                if let Some(blocking) = &viewer.blocking {
+                                    backend.backend_commander.send(FrontToBackMsg::DeleteRecordUnderProfileRequest {
+                                        rkey: blocking.split("/").last().unwrap().to_owned(),
+                                        nsid: "app.bsky.graph.block".to_owned(),
+                                        profile_mod: profile_mod.clone(),
+                                    }).unwrap();
+                                } else {
+                                    backend.backend_commander.send(FrontToBackMsg::CreateRecordUnderProfileRequest(
+                                        BlueskyApiRecord::Block(Block { subject: profile.did.clone(), created_at: Utc::now() }),
+                                        profile_mod.clone(),
+                                    )).unwrap();
+                                }
+                            }
+
+                            profile_scope!("Mute Button");
+                            let muted = viewer.muted.unwrap_or(false);
+                            let button = ui.allocate_response(vec2(height * 0.5, panel_height), egui::Sense::click()).on_hover_cursor(egui::CursorIcon::PointingHand);
+                            ui.painter().rect_filled(button.rect, Rounding::ZERO, ui.style().visuals.extreme_bg_color);
+
+                            ui.painter().text(button.rect.center() - vec2(0.0, big_text_size / 4.0), Align2::CENTER_BOTTOM, "\u{E198}", FontId::new(big_text_size, egui::FontFamily::Name("Segoe Symbols".into())), ui.style().visuals.text_color());
+                            ui.painter().text(button.rect.center() + vec2(0.0, big_text_size * 1.2), Align2::CENTER_TOP, if muted { "Unmute" } else { "Mute" }, FontId::proportional(small_text_size), ui.style().visuals.text_color());
+                            if button.clicked() {
+                                backend.backend_commander.send(FrontToBackMsg::MuteActorRequest { did: profile.did.clone(), mute: !muted, profile_mod: Some(profile_mod.clone()) }).unwrap();
+                            }
+                        });
+                    }
                     ui.allocate_space(vec2(120.0, funny_rect.height()));
 
                     let posts_res = ui.with_layout(Layout::top_down(egui::Align::Min), |ui| {
@@ -243,7 +327,7 @@ impl FrontendProfileView {
                             if let Some(posts) = &self.posts {
                                 let posts = posts.lock().unwrap();
                                 for post in posts.feed.iter() {
-                                    feed_post_viewer(ui, post, modal, &backend, image, flyout, new_view);
+                                    feed_post_viewer(ui, post, you, modal, &backend, image, flyout, new_view);
                                 }
                             }
 
@@ -1,20 +1,25 @@
 use std::sync::{Arc, Mutex};
-use eframe::emath::vec2;
-use egui::{Layout, ScrollArea, Ui};
+use egui::{ScrollArea, Ui};
 
 use crate::{defs::bsky::actor::defs::ProfileView, bridge::Bridge, image::ImageCache, BSKY_BLUE};
 use crate::bridge::FrontToBackMsg;
-use crate::defs::bsky::feed::defs::FeedCursorPair;
 use crate::frontend::CursorListPair;
 use crate::frontend::viewers::profile::profile_viewer;
 use crate::widgets::spinner::SegoeBootSpinner;
 use super::{MainViewProposition, ViewStackReturnInfo};
 
 pub struct ListData {
-	pub did: String,
+	/// DID of the profile for follow lists, AT-URI of the post for likes and reposts
+	pub subject: String,
 	pub profiles: Arc<Mutex<CursorListPair<ProfileView>>>,
 }
 
+impl ListData {
+	pub fn new(subject: String) -> Self {
+		Self { subject, profiles: Arc::new(Mutex::new(CursorListPair { cursor: Some(String::new()), items: Vec::new() })) }
+	}
+}
+
 pub enum FrontendProfileListVariant {
 	Followers(ListData),
 	Following(ListData),
@@ -24,31 +29,34 @@ pub enum FrontendProfileListVariant {
 
 impl FrontendProfileListVariant {
 	pub fn render(&mut self, ui: &mut Ui, backend: &Bridge, image: &ImageCache, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
-		let (title, data, variant) = match self {
-			FrontendProfileListVariant::Followers(data) =>  ("Followers", data, 0),
-			FrontendProfileListVariant::Following(data) =>  ("Following", data, 1),
-			FrontendProfileListVariant::LikedBy(data) =>    ("Likes",     data, 2),
-			FrontendProfileListVariant::RepostedBy(data) => ("Reposts",   data, 3),
+		let (title, data) = match &*self {
+			FrontendProfileListVariant::Followers(data) =>  ("Followers", data),
+			FrontendProfileListVariant::Following(data) =>  ("Following", data),
+			FrontendProfileListVariant::LikedBy(data) =>    ("Likes",     data),
+			FrontendProfileListVariant::RepostedBy(data) => ("Reposts",   data),
 		};
 
-		ScrollArea::vertical().hscroll(false).max_width(ui.cursor().width()).id_salt(format!("{}_ProfileList", &data.did)).show(ui, |ui| {
+		ScrollArea::vertical().hscroll(false).max_width(ui.cursor().width()).id_salt(format!("{}_{}_ProfileList", &data.subject, title)).show(ui, |ui| {
 			let profiles = data.profiles.lock().unwrap();
 			for profile in profiles.items.iter() {
 				profile_viewer(ui, profile, image, new_view);
 			}
+			drop(profiles);
 
 			let loader_response = ui.add(SegoeBootSpinner::new().size(50.0).color(BSKY_BLUE));
 
-			if ui.is_rect_visible(loader_response.rect) && profiles.cursor.is_some() {
-				drop(profiles);
-				match variant {
-					0 => {
-						backend.backend_commander.send(FrontToBackMsg::GetFollowersRequest {
-							did: data.did.clone(),
-							profiles: data.profiles.clone(),
-						}).unwrap();
-					}
-					_ => {}
+			if ui.is_rect_visible(loader_response.rect) {
+				let cursor = data.profiles.lock().unwrap().cursor.take();
+				if let Some(cursor) = cursor {
+					let profiles = data.profiles.clone();
+					let subject = data.subject.clone();
+					let msg = match &*self {
+						FrontendProfileListVariant::Followers(_) =>  FrontToBackMsg::GetFollowersRequest { did: subject, cursor, profiles },
+						FrontendProfileListVariant::Following(_) =>  FrontToBackMsg::GetFollowsRequest { did: subject, cursor, profiles },
+						FrontendProfileListVariant::LikedBy(_) =>    FrontToBackMsg::GetLikesRequest { uri: subject, cursor, profiles },
+						FrontendProfileListVariant::RepostedBy(_) => FrontToBackMsg::GetRepostedByRequest { uri: subject, cursor, profiles },
+					};
+					backend.backend_commander.send(msg).unwrap();
 				}
 			}
 		});
@@ -60,4 +68,4 @@ impl FrontendProfileListVariant {
 			force_back: false
 		}
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,43 @@
+use std::sync::{Arc, Mutex};
+
+use egui::Ui;
+
+use crate::{
+    bridge::{Bridge, FrontToBackMsg},
+    defs::bsky::{actor::defs::ProfileViewDetailed, feed::defs::FeedCursorPair},
+    frontend::main::{ClientFrontendFlyout, ClientFrontendModal},
+    image::ImageCache,
+};
+
+use super::{timeline::feed_scroller, MainViewProposition, ViewStackReturnInfo};
+
+/// Posts quoting a given post, opened from the quote count on a thread's main post.
+pub struct FrontendQuotesView {
+    /// AT-URI of the quoted post
+    pub uri: String,
+    pub posts: Arc<Mutex<FeedCursorPair>>,
+    post_highlight: (usize, f32, bool),
+}
+
+impl FrontendQuotesView {
+    pub fn new(uri: String) -> Self {
+        Self { uri, posts: Arc::new(Mutex::new(FeedCursorPair { cursor: Some(String::new()), feed: Vec::new() })), post_highlight: (0, 999.999, false) }
+    }
+
+    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
+        puffin::profile_function!();
+
+        let posts = self.posts.lock().unwrap();
+        let load = feed_scroller(ui, format!("{}_QuotesScrollview", self.uri), &posts.feed, &mut self.post_highlight, you, modal, backend, image, flyout, new_view);
+        drop(posts);
+
+        if load {
+            let cursor = self.posts.lock().unwrap().cursor.take();
+            if let Some(cursor) = cursor {
+                backend.backend_commander.send(FrontToBackMsg::GetQuotesRequest { uri: self.uri.clone(), cursor, posts: self.posts.clone() }).unwrap();
+            }
+        }
+
+        ViewStackReturnInfo { title: Some("Quotes".into()), render_back_button: true, handle_back_logic: true, force_back: false }
+    }
+}
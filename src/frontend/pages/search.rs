@@ -0,0 +1,169 @@
+use std::sync::{Arc, Mutex};
+
+use egui::{vec2, FontId, Layout, RichText, ScrollArea, Ui};
+
+use crate::{
+    backend::search::{SearchPostsParams, SearchSort},
+    bridge::{Bridge, FrontToBackMsg},
+    defs::bsky::{actor::defs::{ProfileView, ProfileViewDetailed}, feed::defs::FeedCursorPair},
+    frontend::{
+        circle_button,
+        main::{ClientFrontendFlyout, ClientFrontendModal},
+        viewers::{feed_post::feed_post_viewer, profile::profile_viewer},
+        CursorListPair,
+    },
+    image::ImageCache,
+    widgets::spinner::SegoeBootSpinner,
+    BSKY_BLUE,
+};
+
+use super::{MainViewProposition, ViewStackReturnInfo};
+
+#[derive(PartialEq)]
+pub enum SearchTab {
+    Posts,
+    People,
+}
+
+pub struct FrontendSearchView {
+    /// What's in the search box, and the filters under it
+    pub draft: SearchPostsParams,
+    tags_draft: String,
+    pub tab: SearchTab,
+    /// What the results below were searched for, None until the first search
+    pub params: Option<SearchPostsParams>,
+    pub posts: Arc<Mutex<FeedCursorPair>>,
+    pub(crate) profiles: Arc<Mutex<CursorListPair<ProfileView>>>,
+}
+
+impl FrontendSearchView {
+    pub fn new() -> Self {
+        Self {
+            draft: SearchPostsParams::new(String::new()),
+            tags_draft: String::new(),
+            tab: SearchTab::Posts,
+            params: None,
+            posts: Arc::new(Mutex::new(FeedCursorPair { cursor: None, feed: Vec::new() })),
+            profiles: Arc::new(Mutex::new(CursorListPair { cursor: None, items: Vec::new() })),
+        }
+    }
+
+    /// Swaps out the result lists, so anything still coming in for the last search lands somewhere harmless
+    fn search(&mut self) {
+        self.draft.tag = self.tags_draft.split([' ', ',']).filter(|tag| !tag.is_empty()).map(|tag| tag.to_owned()).collect();
+        self.params = Some(self.draft.clone());
+        self.posts = Arc::new(Mutex::new(FeedCursorPair { cursor: Some(String::new()), feed: Vec::new() }));
+        self.profiles = Arc::new(Mutex::new(CursorListPair { cursor: Some(String::new()), items: Vec::new() }));
+    }
+
+    pub(super) fn tab_header(ui: &mut Ui, text: &str, active: bool) -> bool {
+        let color = if active { ui.visuals().text_color() } else { ui.visuals().weak_text_color() };
+        ui.add(egui::Label::new(RichText::new(text).font(FontId::new(30.0, egui::FontFamily::Name("Segoe Light".into()))).color(color)).sense(egui::Sense::click()).selectable(false)).on_hover_cursor(egui::CursorIcon::PointingHand).clicked()
+    }
+
+    fn filter_field(ui: &mut Ui, label: &str, hint: &str, text: &mut String) {
+        ui.label(label);
+        ui.add(egui::TextEdit::singleline(text).hint_text(hint).desired_width(200.0));
+        ui.end_row();
+    }
+
+    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
+        puffin::profile_function!();
+
+        let mut search = false;
+        ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+            let input = ui.add(egui::TextEdit::singleline(&mut self.draft.q).hint_text("Search Bluesky").font(FontId::proportional(20.0)).desired_width(400.0));
+            if input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                search = true;
+            }
+            if circle_button(ui, "\u{E11A}", 15.0, 15.0).on_hover_text("Search").clicked() {
+                search = true;
+            }
+        });
+
+        egui::CollapsingHeader::new("Filters").id_salt("SearchFilters").show(ui, |ui| {
+            egui::Grid::new("SearchFiltersGrid").num_columns(2).spacing(vec2(10.0, 4.0)).show(ui, |ui| {
+                Self::filter_field(ui, "From", "handle", &mut self.draft.author);
+                Self::filter_field(ui, "Mentioning", "handle", &mut self.draft.mentions);
+                Self::filter_field(ui, "Language", "en", &mut self.draft.lang);
+                Self::filter_field(ui, "Since", "YYYY-MM-DD", &mut self.draft.since);
+                Self::filter_field(ui, "Until", "YYYY-MM-DD", &mut self.draft.until);
+                Self::filter_field(ui, "Tags", "#art #photography", &mut self.tags_draft);
+            });
+        });
+
+        if search && !self.draft.q.trim().is_empty() {
+            self.search();
+        }
+
+        ui.with_layout(Layout::left_to_right(egui::Align::Max), |ui| {
+            ui.style_mut().spacing.item_spacing.x = 20.0;
+            if Self::tab_header(ui, "Posts", self.tab == SearchTab::Posts) {
+                self.tab = SearchTab::Posts;
+            }
+            if Self::tab_header(ui, "People", self.tab == SearchTab::People) {
+                self.tab = SearchTab::People;
+            }
+
+            if self.tab == SearchTab::Posts {
+                let old = self.draft.sort;
+                ui.with_layout(Layout::right_to_left(egui::Align::Max), |ui| {
+                    ui.selectable_value(&mut self.draft.sort, SearchSort::Latest, "Latest");
+                    ui.selectable_value(&mut self.draft.sort, SearchSort::Top, "Top");
+                });
+                // re-sorting is a new search, but only if there's already one to re-sort
+                if old != self.draft.sort && self.params.is_some() {
+                    self.search();
+                }
+            }
+        });
+        ui.add_space(8.0);
+
+        let Some(params) = &self.params else {
+            return ViewStackReturnInfo { title: Some("Search".into()), render_back_button: true, handle_back_logic: true, force_back: false };
+        };
+
+        match self.tab {
+            SearchTab::Posts => {
+                ScrollArea::vertical().hscroll(false).max_width(ui.cursor().width()).id_salt("SearchPostsScrollview").show(ui, |ui| {
+                    let posts = self.posts.lock().unwrap();
+                    for post in posts.feed.iter() {
+                        feed_post_viewer(ui, post, you, modal, backend, image, flyout, new_view);
+                    }
+                    drop(posts);
+
+                    ui.with_layout(Layout::top_down(egui::Align::Center), |spinner| {
+                        let spinner_rect = spinner.add_sized(vec2(40.0, 40.0), SegoeBootSpinner::new().size(40.0).color(BSKY_BLUE)).rect;
+                        if spinner.is_rect_visible(spinner_rect) {
+                            let cursor = self.posts.lock().unwrap().cursor.take();
+                            if let Some(cursor) = cursor {
+                                backend.backend_commander.send(FrontToBackMsg::SearchPostsRequest { params: params.clone(), cursor, posts: self.posts.clone() }).unwrap();
+                            }
+                        }
+                    });
+                });
+            }
+            SearchTab::People => {
+                ScrollArea::vertical().hscroll(false).max_width(ui.cursor().width()).id_salt("SearchPeopleScrollview").show(ui, |ui| {
+                    let profiles = self.profiles.lock().unwrap();
+                    for profile in profiles.items.iter() {
+                        profile_viewer(ui, profile, image, new_view);
+                    }
+                    drop(profiles);
+
+                    ui.with_layout(Layout::top_down(egui::Align::Center), |spinner| {
+                        let spinner_rect = spinner.add_sized(vec2(40.0, 40.0), SegoeBootSpinner::new().size(40.0).color(BSKY_BLUE)).rect;
+                        if spinner.is_rect_visible(spinner_rect) {
+                            let cursor = self.profiles.lock().unwrap().cursor.take();
+                            if let Some(cursor) = cursor {
+                                backend.backend_commander.send(FrontToBackMsg::SearchActorsRequest { query: params.q.clone(), cursor, profiles: self.profiles.clone() }).unwrap();
+                            }
+                        }
+                    });
+                });
+            }
+        }
+
+        ViewStackReturnInfo { title: Some("Search".into()), render_back_button: true, handle_back_logic: true, force_back: false }
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::{Arc, Mutex};
+
+use egui::{Layout, Ui};
+
+use crate::{
+    backend::search::{SearchPostsParams, SearchSort},
+    bridge::{Bridge, FrontToBackMsg},
+    defs::bsky::{actor::defs::ProfileViewDetailed, feed::defs::FeedCursorPair},
+    frontend::main::{ClientFrontendFlyout, ClientFrontendModal},
+    image::ImageCache,
+};
+
+use super::{search::FrontendSearchView, timeline::feed_scroller, MainViewProposition, ViewStackReturnInfo};
+
+/// Posts with a given hashtag, opened from tag facets and post tags.
+pub struct FrontendTagView {
+    /// Without the #
+    pub tag: String,
+    pub sort: SearchSort,
+    pub posts: Arc<Mutex<FeedCursorPair>>,
+    post_highlight: (usize, f32, bool),
+}
+
+impl FrontendTagView {
+    pub fn new(tag: String) -> Self {
+        Self { tag: tag.trim_start_matches(['#', '＃']).to_owned(), sort: SearchSort::Top, posts: Arc::new(Mutex::new(FeedCursorPair { cursor: Some(String::new()), feed: Vec::new() })), post_highlight: (0, 999.999, false) }
+    }
+
+    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
+        puffin::profile_function!();
+
+        let old = self.sort;
+        ui.with_layout(Layout::left_to_right(egui::Align::Max), |ui| {
+            ui.style_mut().spacing.item_spacing.x = 20.0;
+            if FrontendSearchView::tab_header(ui, "Top", self.sort == SearchSort::Top) {
+                self.sort = SearchSort::Top;
+            }
+            if FrontendSearchView::tab_header(ui, "Latest", self.sort == SearchSort::Latest) {
+                self.sort = SearchSort::Latest;
+            }
+        });
+        if old != self.sort {
+            // new arc so a page still coming in for the old order can't get mixed in
+            self.posts = Arc::new(Mutex::new(FeedCursorPair { cursor: Some(String::new()), feed: Vec::new() }));
+            self.post_highlight = (0, 999.999, false);
+        }
+        ui.add_space(8.0);
+
+        let posts = self.posts.lock().unwrap();
+        let load = feed_scroller(ui, format!("{}_{}_TagScrollview", self.tag, self.sort.as_str()), &posts.feed, &mut self.post_highlight, you, modal, backend, image, flyout, new_view);
+        drop(posts);
+
+        if load {
+            let cursor = self.posts.lock().unwrap().cursor.take();
+            if let Some(cursor) = cursor {
+                let mut params = SearchPostsParams::new(format!("#{}", self.tag));
+                params.sort = self.sort;
+                params.tag = vec![self.tag.clone()];
+                backend.backend_commander.send(FrontToBackMsg::SearchPostsRequest { params, cursor, posts: self.posts.clone() }).unwrap();
+            }
+        }
+
+        ViewStackReturnInfo { title: Some(format!("#{}", self.tag)), render_back_button: true, handle_back_logic: true, force_back: false }
+    }
+}
@@ -2,7 +2,7 @@ use egui::{pos2, vec2, Response, Rounding, ScrollArea, Stroke, Ui};
 
 use super::{MainViewProposition, ViewStackReturnInfo};
 use crate::bridge::FrontToBackMsg;
-use crate::defs::bsky::feed::defs::{BlockedPost, ThreadPostVariant};
+use crate::defs::bsky::{actor::defs::ProfileViewDetailed, feed::defs::{BlockedPost, ThreadPostVariant}};
 use crate::frontend::main::{ClientFrontendFlyout, ClientFrontendModal};
 use crate::BSKY_BLUE;
 use crate::frontend::viewers;
@@ -34,17 +34,17 @@ impl FrontendThreadView {
         res
     }
 
-    fn render_reply(ui: &mut Ui, reply: &ThreadPostVariant, depth: u32, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> Response {
+    fn render_reply(ui: &mut Ui, reply: &ThreadPostVariant, depth: u32, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> Response {
         match reply {
             ThreadPostVariant::NotFound(_) => { Self::render_not_found(ui) },
             ThreadPostVariant::Blocked(info) => { Self::render_blocked(ui, info, new_view) },
             ThreadPostVariant::ThreadView(post) => {
-                let rtn = viewers::post::post_viewer(ui, post.post.clone(), false, modal, backend, image, flyout, new_view);
+                let rtn = viewers::post::post_viewer(ui, post.post.clone(), false, you, modal, backend, image, flyout, new_view);
                 if depth <= 0 { return rtn; }
                 if let Some(replies) = &post.replies {
                     if let Some(first) = replies.first() {
                         ui.painter().line_segment([pos2(rtn.rect.left() + 30.0, rtn.rect.top() + 70.0), pos2(rtn.rect.left() + 30.0, rtn.rect.bottom() + (ui.style().spacing.item_spacing.y) + 10.0)], ui.style().visuals.widgets.inactive.fg_stroke);
-                        Self::render_reply(ui, first, depth - 1, modal, backend, image, flyout, new_view);
+                        Self::render_reply(ui, first, depth - 1, you, modal, backend, image, flyout, new_view);
                     }
                 }
                 rtn
@@ -52,7 +52,7 @@ impl FrontendThreadView {
         }
     }
 
-    fn render_recursive(ui: &mut Ui, thread: &ThreadPostVariant, first: bool, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> Response {
+    fn render_recursive(ui: &mut Ui, thread: &ThreadPostVariant, first: bool, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> Response {
         match thread {
             ThreadPostVariant::Blocked(info) => {
                 Self::render_blocked(ui, info, new_view)
@@ -62,10 +62,10 @@ impl FrontendThreadView {
             },
             ThreadPostVariant::ThreadView(thread) => {
                 if let Some(parent) = &thread.parent {
-                    let res = Self::render_recursive(ui, &parent.lock().unwrap(), false, modal, backend, image, flyout, new_view);
+                    let res = Self::render_recursive(ui, &parent.lock().unwrap(), false, you, modal, backend, image, flyout, new_view);
                     ui.painter().line_segment([pos2(res.rect.left() + 30.0, res.rect.top() + 70.0), pos2(res.rect.left() + 30.0, res.rect.bottom() + (ui.style().spacing.item_spacing.y) + 10.0)], ui.style().visuals.widgets.inactive.fg_stroke);
                 }
-                let rtn = viewers::post::post_viewer(ui, thread.post.clone(), first, modal, backend, image, flyout, new_view);
+                let rtn = viewers::post::post_viewer(ui, thread.post.clone(), first, you, modal, backend, image, flyout, new_view);
 
                 if first {
                     if let Some(replies) = &thread.replies {
@@ -79,7 +79,7 @@ impl FrontendThreadView {
                         let (_, line_rect) = ui.allocate_space(vec2(rtn.rect.width(), ui.style().visuals.widgets.inactive.fg_stroke.width * 2.0));
                         ui.painter().rect_filled(line_rect.with_max_x(ui.cursor().right()), Rounding::ZERO, ui.style().visuals.widgets.inactive.fg_stroke.color);
                         for reply in replies {
-                            Self::render_reply(ui, reply, reply_depth, modal, backend, image, flyout, new_view);
+                            Self::render_reply(ui, reply, reply_depth, you, modal, backend, image, flyout, new_view);
                         }
                     }
                 }
@@ -89,12 +89,12 @@ impl FrontendThreadView {
         }
     }
 
-    pub fn render(&mut self, ui: &mut Ui, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
+    pub fn render(&mut self, ui: &mut Ui, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
         puffin::profile_function!();
 
         if let Some(thread) = &self.data {
             ScrollArea::vertical().hscroll(false).show(ui, |scroll| {
-                Self::render_recursive(scroll, &thread, true, modal, backend, image, flyout, new_view);
+                Self::render_recursive(scroll, &thread, true, you, modal, backend, image, flyout, new_view);
                 scroll.allocate_space(vec2(scroll.cursor().width(), 0.0));
             });
         } else {
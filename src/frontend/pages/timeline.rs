@@ -5,12 +5,12 @@ use crate::{
     bridge::Bridge,
     defs::bsky::{
         actor::defs::ProfileViewDetailed,
-        feed::defs::{FeedCursorPair, GeneratorView},
+        feed::defs::{FeedCursorPair, FeedViewPost, GeneratorView},
     },
     frontend::{
-        flyouts::composer::ComposerFlyout,
+        flyouts::{accounts::AccountsFlyout, composer::ComposerFlyout},
         main::{ClientFrontendFlyout, ClientFrontendModal},
-        pages::{profile::FrontendProfileView, FrontendMainView},
+        pages::{convos::FrontendConvosView, notifications::FrontendNotificationsView, search::FrontendSearchView, FrontendMainView},
         viewers,
     },
     image::ImageCache,
@@ -26,24 +26,95 @@ pub struct FrontendTimelineView {
     pub feeds: Vec<(crate::defs::bsky::feed::defs::GeneratorView, FeedCursorPair)>,
     control_strip_deployed: bool,
     pub post_highlight: (usize, f32, bool),
+    /// ui time of the last unread notification count poll
+    last_unread_poll: Option<f64>,
 }
 
+/// How often the unread notification count gets refreshed, in seconds
+const UNREAD_POLL_INTERVAL: f64 = 60.0;
+
 fn ease_out_cubic(x: f32) -> f32 {
     return 1.0 - f32::powf(1.0 - x, 3.0);
 }
 
+/// Infinite-scrolling list of feed posts with J/K keyboard navigation, like the timeline.
+/// Returns whether the loading spinner at the bottom is on screen, at which point the caller should fetch more.
+pub fn feed_scroller(ui: &mut egui::Ui, id_salt: impl std::hash::Hash, feed: &[FeedViewPost], post_highlight: &mut (usize, f32, bool), you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> bool {
+    let top = ui.cursor().top(); // the top of the scroll rect, used to compare post positions for keyboard nav
+    ScrollArea::vertical().hscroll(false).max_width(ui.cursor().width()).id_salt(id_salt).max_height(ui.cursor().height()).show(ui, |tl| {
+        // keyboard nav polling
+        let (scrolling, scroll_to) = {
+            puffin::profile_scope!("Keyboard nav part A");
+
+            let scroll_to: Option<usize> = if !tl.is_enabled() {
+                None
+            } else {
+                tl.input(|r| {
+                    puffin::profile_scope!("Key polling");
+                    if r.key_pressed(egui::Key::K) {
+                        post_highlight.0 += 1;
+                        post_highlight.2 = true;
+                        Some(post_highlight.0)
+                    } else if r.key_pressed(egui::Key::J) && post_highlight.0 > 0 {
+                        post_highlight.0 -= 1;
+                        post_highlight.2 = true;
+                        Some(post_highlight.0)
+                    } else {
+                        None
+                    }
+                })
+            };
+            let scrolling = tl.input(|r| r.smooth_scroll_delta != Vec2::new(0.0, 0.0));
+            if scrolling {
+                post_highlight.2 = false;
+            }
+            post_highlight.1 = 9999.9999;
+            (scrolling, scroll_to)
+        };
+        for (i, post) in feed.iter().enumerate() {
+            puffin::profile_scope!("Post");
+
+            let res = viewers::feed_post::feed_post_viewer(tl, post, you, modal, backend, image, flyout, new_view);
+            // keyboard nav comparison, checks if we're scrolling (no need to update if not), and if we are, sets the closest post to the top as the active one
+            {
+                puffin::profile_scope!("Keyboard nav part B");
+                if scrolling
+                /* do some max height check here*/
+                {
+                    let comp = f32::abs(top - res.rect.top());
+                    if comp < post_highlight.1 {
+                        post_highlight.1 = comp;
+                        post_highlight.0 = i;
+                    }
+                }
+                if post_highlight.2 && i == post_highlight.0 {
+                    tl.painter().rect(res.rect, Rounding::ZERO, Color32::TRANSPARENT, Stroke::new(4.0, BSKY_BLUE));
+                }
+                if let Some(to) = scroll_to {
+                    if i == to {
+                        res.scroll_to_me(Some(egui::Align::Min));
+                    }
+                }
+            }
+        }
+        tl.with_layout(Layout::top_down(egui::Align::Center), |spinner| {
+            let spinner_rect = spinner.add_sized(vec2(40.0, 40.0), SegoeBootSpinner::new().size(40.0).color(BSKY_BLUE)).rect;
+            spinner.is_rect_visible(spinner_rect)
+        }).inner
+    }).inner
+}
+
 impl FrontendTimelineView {
     pub fn new(feeds: Vec<GeneratorView>) -> Self {
         let mut feeds_dest: Vec<(crate::defs::bsky::feed::defs::GeneratorView, FeedCursorPair)> = Vec::new();
         for feed in feeds {
             feeds_dest.push((feed, FeedCursorPair { cursor: Some(String::new()), feed: Vec::new() }));
         }
-        Self { timeline: FeedCursorPair { cursor: Some(String::new()), feed: Vec::new() }, feed: 0, feeds: feeds_dest, control_strip_deployed: false, post_highlight: (0, 999.999, false) }
+        Self { timeline: FeedCursorPair { cursor: Some(String::new()), feed: Vec::new() }, feed: 0, feeds: feeds_dest, control_strip_deployed: false, post_highlight: (0, 999.999, false), last_unread_poll: None }
     }
 
-    pub fn render(&mut self, ui: &mut egui::Ui, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
+    pub fn render(&mut self, ui: &mut egui::Ui, you: &Option<ProfileViewDetailed>, unread: usize, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> ViewStackReturnInfo {
         puffin::profile_function!();
-        let top = ui.cursor().top(); // the top of the scroll rect, used to compare post positions for keyboard nav
         let offset = ui.ctx().animate_bool_with_time_and_easing("FrontendMainViewStackTitleSlide".into(), true, 0.5, ease_out_cubic);
         let pos = pos2(120.0 + (100.0 - (offset * 100.0)), ui.cursor().top() - 40.0);
 
@@ -92,97 +163,44 @@ impl FrontendTimelineView {
                         crate::image::LoadableImage::Loaded(texture_id, _) => {
                             ui.painter().image(texture_id, image_rect, Rect::from_two_pos(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
                         }
+                        crate::image::LoadableImage::Animated(anim) => {
+                            ui.painter().image(anim.frame(ui, image_rect), image_rect, Rect::from_two_pos(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
+                        }
                     }
                 }
             }
         });
 
-        ScrollArea::vertical().hscroll(false).max_width(ui.cursor().width()).id_salt(self.feed).max_height(ui.cursor().height()).show(ui, |tl| {
-            // keyboard nav polling
-            let (scrolling, scroll_to) = {
-                puffin::profile_scope!("Keyboard nav part A");
-
-                let scroll_to: Option<usize> = if !tl.is_enabled() {
-                    None
-                } else {
-                    tl.input(|r| {
-                        puffin::profile_scope!("Key polling");
-                        if r.key_pressed(egui::Key::K) {
-                            self.post_highlight.0 += 1;
-                            self.post_highlight.2 = true;
-                            Some(self.post_highlight.0)
-                        } else if r.key_pressed(egui::Key::J) && self.post_highlight.0 > 0 {
-                            self.post_highlight.0 -= 1;
-                            self.post_highlight.2 = true;
-                            Some(self.post_highlight.0)
-                        } else {
-                            None
-                        }
-                    })
-                };
-                let scrolling = tl.input(|r| r.smooth_scroll_delta != Vec2::new(0.0, 0.0));
-                if scrolling {
-                    self.post_highlight.2 = false;
+        let feed_uri = if self.feed == 0 { None } else { Some(self.feeds.get(self.feed - 1).unwrap().0.uri.clone()) };
+        let tl: &mut FeedCursorPair = if self.feed == 0 { &mut self.timeline } else { &mut self.feeds.get_mut(self.feed - 1).unwrap().1 };
+        if feed_scroller(ui, self.feed, &tl.feed, &mut self.post_highlight, you, modal, backend, image, flyout, new_view) && tl.cursor.is_some() {
+            match feed_uri {
+                None => {
+                    backend.backend_commander.send(crate::bridge::FrontToBackMsg::GetTimelineRequest{
+                        cursor: tl.cursor.clone(),
+                        limit: None,
+                    }).unwrap();
                 }
-                self.post_highlight.1 = 9999.9999;
-                (scrolling, scroll_to)
-            };
-            let iter = if self.feed == 0 { self.timeline.feed.iter().enumerate() } else { self.feeds.get(self.feed - 1).unwrap().1.feed.iter().enumerate() };
-            for (i, post) in iter {
-                puffin::profile_scope!("Post");
-
-                let res = viewers::feed_post::feed_post_viewer(tl, &post, modal, backend, image, flyout, new_view);
-                // keyboard nav comparison, checks if we're scrolling (no need to update if not), and if we are, sets the closest post to the top as the active one
-                {
-                    puffin::profile_scope!("Keyboard nav part B");
-                    if scrolling
-                    /* do some max height check here*/
-                    {
-                        let comp = f32::abs(top - res.rect.top());
-                        if comp < self.post_highlight.1 {
-                            self.post_highlight.1 = comp;
-                            self.post_highlight.0 = i;
-                        }
-                    }
-                    if self.post_highlight.2 && i == self.post_highlight.0 {
-                        tl.painter().rect(res.rect, Rounding::ZERO, Color32::TRANSPARENT, Stroke::new(4.0, BSKY_BLUE));
-                    }
-                    if let Some(to) = scroll_to {
-                        if i == to {
-                            res.scroll_to_me(Some(egui::Align::Min));
-                        }
-                    }
+                Some(feed) => {
+                    backend.backend_commander.send(crate::bridge::FrontToBackMsg::GetFeedRequest{
+                        feed,
+                        cursor: tl.cursor.clone(),
+                        limit: None,
+                    }).unwrap();
                 }
             }
-            tl.with_layout(Layout::top_down(egui::Align::Center), |spinner| {
-                let spinner_rect = spinner.add_sized(vec2(40.0, 40.0), SegoeBootSpinner::new().size(40.0).color(BSKY_BLUE)).rect;
-                let mut jank = String::new();
-                let tl: &mut FeedCursorPair = if self.feed == 0 {
-                    &mut self.timeline
-                } else {
-                    jank = self.feeds.get(self.feed - 1).unwrap().0.uri.clone();
-                    &mut self.feeds.get_mut(self.feed - 1).unwrap().1
-                };
-                if spinner.is_rect_visible(spinner_rect) && tl.cursor.is_some() {
-                    if self.feed == 0 {
-                        backend.backend_commander.send(crate::bridge::FrontToBackMsg::GetTimelineRequest{
-                            cursor: tl.cursor.clone(),
-                            limit: None,
-                        }).unwrap();
-                    } else {
-                        backend.backend_commander.send(crate::bridge::FrontToBackMsg::GetFeedRequest{
-                            feed: jank,
-                            cursor: tl.cursor.clone(),
-                            limit: None,
-                        }).unwrap();
-                    }
 
-                    tl.cursor = None;
-                }
-            })
-        });
+            tl.cursor = None;
+        }
 
-        self.render_options_strip(ui, you, modal, backend, image, flyout, new_view);
+        let now = ui.input(|i| i.time);
+        if self.last_unread_poll.is_none_or(|last| now - last > UNREAD_POLL_INTERVAL) {
+            backend.backend_commander.send(crate::bridge::FrontToBackMsg::GetUnreadNotificationsRequest).unwrap();
+            self.last_unread_poll = Some(now);
+        }
+        ui.ctx().request_repaint_after_secs(UNREAD_POLL_INTERVAL as f32);
+
+        self.render_options_strip(ui, you, unread, modal, backend, image, flyout, new_view);
 
         ViewStackReturnInfo { title: None, render_back_button: true, handle_back_logic: true, force_back: false }
     }
@@ -213,7 +231,16 @@ impl FrontendTimelineView {
         button.on_hover_cursor(egui::CursorIcon::PointingHand)
     }
 
-    fn render_options_strip(&mut self, ui: &mut egui::Ui, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) {
+    /// Little count bubble, same idea as the live tile badges
+    fn unread_badge(ui: &mut egui::Ui, center: Pos2, count: usize) {
+        let text = if count > 99 { "99+".to_owned() } else { count.to_string() };
+        let galley = ui.painter().layout_no_wrap(text, FontId::new(12.0, egui::FontFamily::Name("Segoe Light".into())), Color32::WHITE);
+        let radius = (galley.rect.width() / 2.0 + 4.0).max(9.0);
+        ui.painter().circle_filled(center, radius, BSKY_BLUE);
+        ui.painter().galley(center - (galley.rect.size() / 2.0), galley, Color32::WHITE);
+    }
+
+    fn render_options_strip(&mut self, ui: &mut egui::Ui, you: &Option<ProfileViewDetailed>, unread: usize, modal: &mut ClientFrontendModal, backend: &Bridge, image: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) {
         // TODO(headassbtw): spin up a windows 8 VM and check accuracy
         let strip_deployed =  ui.ctx().animate_bool_with_time_and_easing(Id::from("TimelineControlStripDeployed"), self.control_strip_deployed, 0.6, emath::easing::cubic_out);
         let strip_rect = Rect {
@@ -230,6 +257,9 @@ impl FrontendTimelineView {
                 let offset_x = strip_rect.max.x - 15.0 - (15.0 * i as f32);
                 ui.painter().circle_filled(pos2(offset_x, (strip_rect.min.y + 10.0) - (20.0 * strip_deployed)), 3.0, Color32::from_white_alpha(((1.0 - strip_deployed) * 255.0) as u8));
             }
+            if unread > 0 && strip_deployed == 0.0 {
+                Self::unread_badge(ui, pos2(strip_rect.max.x - 70.0, strip_rect.min.y + 10.0), unread);
+            }
         }
 
         if strip_deployed != 0.0 {
@@ -252,7 +282,7 @@ impl FrontendTimelineView {
             }
             Separator::default().grow(-15.0).ui(ui);
             if Self::strip_button(ui, "\u{E2AF}", "You").clicked() {
-                if let Some(you) = &you {
+                if you.is_some() {
                     /* if let Some(pfp) = &you.avatar {
                         match image.get_image(pfp) {
                             crate::image::LoadableImage::Loaded(texture_id, _) => {
@@ -264,11 +294,24 @@ impl FrontendTimelineView {
                             }
                         }
                     }*/
-                    new_view.set(FrontendMainView::Profile(FrontendProfileView::new(you.did.clone())));
+                    flyout.set(crate::frontend::main::ClientFrontendFlyoutVariant::AccountsFlyout(AccountsFlyout::new()));
                 }
             }
             if Self::strip_button(ui, "\u{E11A}", "Search").clicked() {
-
+                new_view.set(FrontendMainView::Search(FrontendSearchView::new()));
+            }
+            let notifications_button = Self::strip_button(ui, "\u{E128}", "Notifications");
+            if unread > 0 {
+                Self::unread_badge(ui, notifications_button.rect.center_top() + vec2(18.0, 18.0), unread);
+            }
+            if notifications_button.clicked() {
+                new_view.set(FrontendMainView::Notifications(FrontendNotificationsView::new()));
+            }
+            if Self::strip_button(ui, "\u{E15F}", "Messages").clicked() {
+                new_view.set(FrontendMainView::Convos(FrontendConvosView::new()));
+            }
+            if Self::strip_button(ui, "\u{E115}", "Settings").clicked() {
+                flyout.set(crate::frontend::main::ClientFrontendFlyoutVariant::SettingsFlyout);
             }
 
             ui.with_layout(Layout::left_to_right(Align::Min), |ui| {
@@ -21,8 +21,13 @@ pub fn view_external(ui: &mut egui::Ui, external: &external::View, _media_size:
 	if !ui.is_rect_visible(resp.response.rect) { return resp.response; }
 
 	if let Some(thumb) = &external.thumb {
-		match img_cache.get_image(thumb) {
-		    crate::image::LoadableImage::Loaded(texture_id, vec2) => {
+		let loaded = match img_cache.get_image(thumb) {
+		    crate::image::LoadableImage::Loaded(texture_id, vec2) => Some((texture_id, vec2)),
+		    crate::image::LoadableImage::Animated(anim) => Some((anim.frame(ui, resp.response.rect), anim.size())),
+		    _ => None,
+		};
+		match loaded {
+		    Some((texture_id, vec2)) => {
 		    	let ratio = (resp.response.rect.height() / resp.response.rect.width()) / (vec2.y / vec2.x);
 		    	let rect = Rect::from_min_max(pos2(0.0, 0.5 - 0.5 * ratio), pos2(1.0, 0.5 + 0.5 * ratio));
 		    	ui.painter().image(texture_id, resp.response.rect.expand(4.0), rect, if ui.style().visuals.dark_mode { Color32::from_white_alpha(32) } else { Color32::from_white_alpha(128) });
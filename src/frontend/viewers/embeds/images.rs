@@ -30,6 +30,12 @@ pub fn view_images(ui: &mut egui::Ui, id_salt: egui::Id, images: &Vec<ViewImage>
                             container.painter().image(id, rtn.rect, Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
                             rtn
                         }
+                        LoadableImage::Animated(anim) => {
+                            let x_multiplier = if let Some(ratio) = &img.aspect_ratio { ratio.width as f32 / ratio.height as f32 } else { anim.size().x / anim.size().y };
+                            let rtn = container.allocate_rect(container.cursor().with_max_x(container.cursor().left() + (media_size * x_multiplier)), egui::Sense::click());
+                            container.painter().image(anim.frame(container, rtn.rect), rtn.rect, Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
+                            rtn
+                        }
                     };
 
                     // probably redundant but i love culling things
@@ -35,6 +35,9 @@ pub fn view_record(ui: &mut egui::Ui, record: &crate::defs::bsky::embed::record:
                                     LoadableImage::Loaded(texture_id, _) => {
                                         name.painter().image(texture_id, pfp_rect, Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
                                     }
+                                    LoadableImage::Animated(anim) => {
+                                        name.painter().image(anim.frame(name, pfp_rect), pfp_rect, Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
+                                    }
                                 }
                             } else {
                                 name.painter().rect_filled(pfp_rect, Rounding::ZERO, BSKY_BLUE);
@@ -41,7 +41,7 @@ pub fn view_video(ui: &mut egui::Ui, video: &video::View, media_size: f32, img_c
     ui.painter().text(video_rect.center(), Align2::CENTER_CENTER, "\u{25B6}", FontId::new(20.0, egui::FontFamily::Name("Segoe Symbols".into())), BSKY_BLUE);
 
     if rtn.clicked() {
-        new_view.set(FrontendMainView::Media(FrontendMediaViewVariant::Video(FrontendMediaVideoView {})));
+        new_view.set(FrontendMainView::Media(FrontendMediaViewVariant::Video(FrontendMediaVideoView::new(ui.ctx().clone(), video.clone()))));
     }
 
     rtn
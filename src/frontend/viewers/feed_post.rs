@@ -1,5 +1,5 @@
 use crate::{
-    bridge::Bridge, defs::bsky::feed::defs::{FeedViewPost, Reason, RelatedPostVariant}, frontend::{
+    bridge::Bridge, defs::bsky::{actor::defs::ProfileViewDetailed, feed::defs::{FeedViewPost, Reason, RelatedPostVariant}}, frontend::{
         main::{ClientFrontendFlyout, ClientFrontendModal},
         pages::{profile::FrontendProfileView, FrontendMainView, MainViewProposition},
     }, image::ImageCache,
@@ -12,7 +12,10 @@ use egui::{
 
 use super::post::post_viewer;
 
-pub fn feed_post_viewer(ui: &mut Ui, post: &FeedViewPost, modal: &mut ClientFrontendModal, backend: &Bridge, img_cache: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> Response {
+pub fn feed_post_viewer(ui: &mut Ui, post: &FeedViewPost, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, img_cache: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> Response {
+    if post.post.lock().unwrap().deleted {
+        return ui.allocate_response(vec2(0.0, 0.0), egui::Sense::hover());
+    }
     if post.reason.is_some() || post.reply.is_some() {
         puffin::profile_scope!("Reason");
         ui.style_mut().spacing.item_spacing = vec2(10.0, 2.0);
@@ -50,5 +53,5 @@ pub fn feed_post_viewer(ui: &mut Ui, post: &FeedViewPost, modal: &mut ClientFron
         });
         ui.style_mut().spacing.item_spacing.y = 10.0;
     }
-    post_viewer(ui, post.post.clone(), false, modal, backend, img_cache, flyout, new_view)
+    post_viewer(ui, post.post.clone(), false, you, modal, backend, img_cache, flyout, new_view)
 }
\ No newline at end of file
@@ -6,7 +6,7 @@ pub mod profile;
 pub mod embeds;
 pub mod profile_picture;
 
-fn offset_time(time: DateTime<Utc>) -> String {
+pub(crate) fn offset_time(time: DateTime<Utc>) -> String {
     puffin::profile_function!();
     let offset = Utc::now() - time;
     if offset.num_days() >= 7 {
@@ -2,13 +2,16 @@ use std::{sync::{Arc, Mutex}};
 
 use crate::{
     bridge::Bridge,
-    defs::bsky::{embed, feed::{self, defs::{BlockedPost, PostView}, ReplyRef, StrongRef}},
+    defs::bsky::{actor::defs::ProfileViewDetailed, embed, feed::{self, defs::{BlockedPost, PostView}, ReplyRef, StrongRef}},
     frontend::{
         flyouts::composer::ComposerFlyout,
         main::{ClientFrontendFlyout, ClientFrontendModal},
-        modals::deceptive_link::DeceptiveLinkModal,
+        modals::{deceptive_link::DeceptiveLinkModal, delete_post::DeletePostModal, interaction_settings::InteractionSettingsModal},
         pages::{
             profile::FrontendProfileView,
+            profile_list::{FrontendProfileListVariant, ListData},
+            quotes::FrontendQuotesView,
+            tag::FrontendTagView,
             thread::FrontendThreadView,
             FrontendMainView, MainViewProposition,
         },
@@ -100,7 +103,22 @@ pub fn not_found_post(ui: &mut Ui) -> Response {
     res        
 }
 
-pub fn post_viewer(ui: &mut Ui, post: Arc<Mutex<PostView>>, main: bool, modal: &mut ClientFrontendModal, backend: &Bridge, img_cache: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> Response {
+/// The "12 Likes" style counts under a thread's main post, returns whether it was clicked
+fn interaction_count(ui: &mut Ui, count: u32, one: &str, many: &str, number_font: &FontId, text_font: &FontId, clickable: bool) -> bool {
+    let sense = if clickable { egui::Sense::click() } else { egui::Sense::hover() };
+    ui.spacing_mut().item_spacing.x = 0.0;
+    let number = ui.add(egui::Label::new(egui::RichText::new(count.to_string()).font(number_font.clone())).sense(sense).selectable(false));
+    ui.spacing_mut().item_spacing.x = 16.0;
+    let text = ui.add(egui::Label::new(egui::RichText::new(if count > 1 { many } else { one }).font(text_font.clone())).sense(sense).selectable(false));
+    if !clickable {
+        return false;
+    }
+    let number = number.on_hover_cursor(egui::CursorIcon::PointingHand);
+    let text = text.on_hover_cursor(egui::CursorIcon::PointingHand);
+    number.clicked() || text.clicked()
+}
+
+pub fn post_viewer(ui: &mut Ui, post: Arc<Mutex<PostView>>, main: bool, you: &Option<ProfileViewDetailed>, modal: &mut ClientFrontendModal, backend: &Bridge, img_cache: &ImageCache, flyout: &mut ClientFrontendFlyout, new_view: &mut MainViewProposition) -> Response {
     puffin::profile_function!();
     let post_og = post.clone();
     let mut like: Option<bool> = None;
@@ -111,6 +129,9 @@ pub fn post_viewer(ui: &mut Ui, post: Arc<Mutex<PostView>>, main: bool, modal: &
         profile_scope!("Mutex Lock");
         &post_og.lock().unwrap()
     };
+    if post.deleted {
+        return ui.allocate_response(vec2(0.0, 0.0), egui::Sense::hover());
+    }
     ui.style_mut().spacing.item_spacing.y = 40.0;
 
     let ffs = ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui| {
@@ -189,7 +210,9 @@ pub fn post_viewer(ui: &mut Ui, post: Arc<Mutex<PostView>>, main: bool, modal: &
                                                 open_in_browser(&link.uri);
                                             }
                                         },
-                                        crate::defs::bsky::richtext::Feature::Tag(_) => {},
+                                        crate::defs::bsky::richtext::Feature::Tag(tag) => {
+                                            new_view.set(FrontendMainView::Tag(FrontendTagView::new(tag.tag.clone())));
+                                        },
                                     }
                                 }
                             }
@@ -212,6 +235,19 @@ pub fn post_viewer(ui: &mut Ui, post: Arc<Mutex<PostView>>, main: bool, modal: &
                 }
             }
 
+            // tags that aren't in the text itself
+            if let Some(tags) = &post.record.tags && !tags.is_empty() {
+                profile_scope!("Tags");
+                post_contents.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 8.0;
+                    for tag in tags {
+                        if ui.link(egui::RichText::new(format!("#{}", tag)).color(BSKY_BLUE).font(FontId::proportional(if main { 16.0 } else { 12.0 }))).clicked() {
+                            new_view.set(FrontendMainView::Tag(FrontendTagView::new(tag.clone())));
+                        }
+                    }
+                });
+            }
+
             let media_size: f32 = if main { 240.0 } else { 180.0 };
 
             let embed_enabled = if let Some(opt) = &post.viewer {
@@ -272,31 +308,19 @@ pub fn post_viewer(ui: &mut Ui, post: Arc<Mutex<PostView>>, main: bool, modal: &
                     let seglt = FontId::new(12.0, egui::FontFamily::Name("Segoe Light".into()));
 
                     if reply_count > 0 {
-                        ui.spacing_mut().item_spacing.x = 0.0;
-                        ui.label(egui::RichText::new(reply_count.to_string()).font(segoe.clone()));
-                        ui.spacing_mut().item_spacing.x = 16.0;
-                        ui.label(egui::RichText::new(if reply_count > 1 { " Replies" } else { " Reply" }).font(seglt.clone()));
+                        interaction_count(ui, reply_count, " Reply", " Replies", &segoe, &seglt, false);
                     }
 
-                    if repost_count > 0 {
-                        ui.spacing_mut().item_spacing.x = 0.0;
-                        ui.label(egui::RichText::new(repost_count.to_string()).font(segoe.clone()));
-                        ui.spacing_mut().item_spacing.x = 16.0;
-                        ui.label(egui::RichText::new(if repost_count > 1 { " Reposts" } else { " Repost" }).font(seglt.clone()));
+                    if repost_count > 0 && interaction_count(ui, repost_count, " Repost", " Reposts", &segoe, &seglt, true) {
+                        new_view.set(FrontendMainView::ProfileList(FrontendProfileListVariant::RepostedBy(ListData::new(post.uri.clone()))));
                     }
-                    
-                    if quote_count > 0 {
-                        ui.spacing_mut().item_spacing.x = 0.0;
-                        ui.label(egui::RichText::new(quote_count.to_string()).font(segoe.clone()));
-                        ui.spacing_mut().item_spacing.x = 16.0;
-                        ui.label(egui::RichText::new(if quote_count > 1 { " Quotes" } else { " Quote" }).font(seglt.clone()));
+
+                    if quote_count > 0 && interaction_count(ui, quote_count, " Quote", " Quotes", &segoe, &seglt, true) {
+                        new_view.set(FrontendMainView::Quotes(FrontendQuotesView::new(post.uri.clone())));
                     }
 
-                    if like_count > 0 {
-                        ui.spacing_mut().item_spacing.x = 0.0;
-                        ui.label(egui::RichText::new(like_count.to_string()).font(segoe.clone()));
-                        ui.spacing_mut().item_spacing.x = 16.0;
-                        ui.label(egui::RichText::new(if like_count > 1 { " Likes" } else { " Like" }).font(seglt.clone()));
+                    if like_count > 0 && interaction_count(ui, like_count, " Like", " Likes", &segoe, &seglt, true) {
+                        new_view.set(FrontendMainView::ProfileList(FrontendProfileListVariant::LikedBy(ListData::new(post.uri.clone()))));
                     }
                 });
                 post_contents.allocate_space(vec2(0.0, 0.0));
@@ -359,7 +383,10 @@ pub fn post_viewer(ui: &mut Ui, post: Arc<Mutex<PostView>>, main: bool, modal: &
                         if guh.add(Button::new(if self_reposted { "Un-Repost" } else { "Repost" }).min_size(guh.spacing().interact_size)).clicked() {
                             repost = Some(!self_reposted);
                         }
-                        if guh.add_enabled(false, Button::new("Quote Repost").min_size(guh.spacing().interact_size)).clicked() { }
+                        let embedding_disabled = post.viewer.as_ref().unwrap().embedding_disabled.unwrap_or(false);
+                        if guh.add_enabled(!embedding_disabled, Button::new("Quote Repost").min_size(guh.spacing().interact_size)).on_disabled_hover_text("The author has disabled quoting").clicked() {
+                            flyout.set(crate::frontend::main::ClientFrontendFlyoutVariant::PostComposerFlyout(ComposerFlyout::with_quote(post_og.clone())));
+                        }
                     });
                 }
                 
@@ -387,6 +414,26 @@ pub fn post_viewer(ui: &mut Ui, post: Arc<Mutex<PostView>>, main: bool, modal: &
                                 p.copied_text = post.url();
                             })
                         }
+
+                        let thread_muted = post.viewer.as_ref().and_then(|viewer| viewer.thread_muted).unwrap_or(false);
+                        if guh.add(Button::new(if thread_muted { "Unmute thread" } else { "Mute thread" }).min_size(guh.spacing().interact_size)).clicked() {
+                            let root = post.record.reply.as_ref().map(|reply| reply.root.uri.clone()).unwrap_or(post.uri.clone());
+                            backend.backend_commander.send(crate::bridge::FrontToBackMsg::MuteThreadRequest { root, mute: !thread_muted }).unwrap();
+                        }
+
+                        // can't mute yourself
+                        let author_muted = post.author.viewer.as_ref().and_then(|viewer| viewer.muted).unwrap_or(false);
+                        if you.as_ref().is_none_or(|you| you.did != post.author.did) && guh.add(Button::new(if author_muted { format!("Unmute @{}", post.author.handle) } else { format!("Mute @{}", post.author.handle) }).min_size(guh.spacing().interact_size)).clicked() {
+                            backend.backend_commander.send(crate::bridge::FrontToBackMsg::MuteActorRequest { did: post.author.did.clone(), mute: !author_muted, profile_mod: None }).unwrap();
+                        }
+
+                        if you.as_ref().is_some_and(|you| you.did == post.author.did) && guh.add(Button::new("Interaction settings").min_size(guh.spacing().interact_size)).clicked() {
+                            modal.set(crate::frontend::main::ClientFrontendModalVariant::InteractionSettings(InteractionSettingsModal::new(post_og.clone(), post)));
+                        }
+
+                        if you.as_ref().is_some_and(|you| you.did == post.author.did) && guh.add(Button::new("Delete post").min_size(guh.spacing().interact_size)).clicked() {
+                            modal.set(crate::frontend::main::ClientFrontendModalVariant::DeletePost(DeletePostModal::new(post.uri.clone(), post.record.text.clone())));
+                        }
                     });
                 }
 
@@ -25,6 +25,9 @@ pub fn profile_picture_viewer(ui: &mut Ui, avatar: &Option<String>, size: [f32;
                 LoadableImage::Loaded(texture_id, _) => {
                     ui.painter().image(texture_id, rect, Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
                 }
+                LoadableImage::Animated(anim) => {
+                    ui.painter().image(anim.frame(ui, rect), rect, Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::WHITE);
+                }
             }
         }
         None => {
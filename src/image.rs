@@ -1,16 +1,20 @@
 use directories::ProjectDirs;
-use egui::{ColorImage, TextureHandle, TextureId, TextureOptions, Vec2};
-use image::{DynamicImage, ImageReader};
+use egui::{ColorImage, Rect, TextureHandle, TextureId, TextureOptions, Vec2};
+use image::{
+    codecs::{gif::GifDecoder, webp::WebPDecoder},
+    AnimationDecoder, DynamicImage, Frames, ImageFormat, ImageReader,
+};
 use std::{
     collections::HashMap,
     fs::{self, File},
     hash::{DefaultHasher, Hash, Hasher},
-    io::Write,
+    io::{BufReader, Write},
     path::PathBuf,
     sync::{
         mpsc::{Receiver, Sender},
         Arc, Mutex,
     },
+    time::Duration,
 };
 
 use crate::settings::Settings;
@@ -22,16 +26,73 @@ pub enum LoadableImage {
     Loading,
     /// Here ya go
     Loaded(TextureId, Vec2),
+    /// Here ya go, but it moves
+    Animated(AnimatedImage),
+}
+
+/// Browsers treat anything faster than this as broken and slow it down, so do we
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+/// Past either of these an animation just shows its first frame, every frame is its own texture
+const MAX_ANIMATION_FRAMES: usize = 300;
+const MAX_ANIMATION_PIXELS: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone)]
+pub struct AnimatedImage {
+    frames: Arc<Vec<(TextureHandle, Duration)>>,
+    total: Duration,
+}
+
+impl AnimatedImage {
+    pub fn size(&self) -> Vec2 {
+        self.frames[0].0.size_vec2()
+    }
+
+    pub fn first_frame(&self) -> TextureId {
+        self.frames[0].0.id()
+    }
+
+    /// Picks the frame that should be showing right now, and asks for a repaint when the next one is due if `rect` is on screen
+    pub fn frame(&self, ui: &egui::Ui, rect: Rect) -> TextureId {
+        let total = self.total.as_millis().max(1);
+        let mut time = (ui.input(|i| i.time) * 1000.0) as u128 % total;
+
+        for (texture, delay) in self.frames.iter() {
+            let delay = delay.as_millis();
+            if time < delay {
+                if ui.is_rect_visible(rect) {
+                    ui.ctx().request_repaint_after(Duration::from_millis((delay - time) as u64));
+                }
+                return texture.id();
+            }
+            time -= delay;
+        }
+
+        self.first_frame()
+    }
+}
+
+enum CachedImage {
+    Still(TextureHandle),
+    Animated(AnimatedImage),
 }
 
 enum LoaderRequest {
     Shutdown,
     GetImg(String),
+    /// Folder under the cache for the account that's active now
+    Namespace(String),
 }
 
+type ImageMap = HashMap<String, Option<CachedImage>>;
+
 pub struct ImageCache {
-    db: Arc<Mutex<HashMap<String, Option<TextureHandle>>>>,
+    db: Arc<Mutex<ImageMap>>,
     tx: Sender<LoaderRequest>,
+    settings: Arc<Mutex<Settings>>,
+    /// Whose images are in `db` right now, and everyone else's, kept for switching back
+    namespace: Mutex<String>,
+    namespaces: Mutex<HashMap<String, ImageMap>>,
 }
 
 impl ImageCache {
@@ -41,7 +102,7 @@ impl ImageCache {
         let proj_dirs = ProjectDirs::from("dev", "headassbtw", "com.headassbtw.metro.bluesky");
         if proj_dirs.is_none() {
             println!("Could not create image cache folder");
-            return Self { db: Arc::new(Mutex::new(HashMap::new())), tx: tx0 };
+            return Self { db: Arc::new(Mutex::new(HashMap::new())), tx: tx0, settings, namespace: Mutex::new(String::new()), namespaces: Mutex::new(HashMap::new()) };
         }
         let proj_dirs = proj_dirs.unwrap();
 
@@ -52,29 +113,40 @@ impl ImageCache {
 
         let cache = dir.clone();
 
-        let map: Arc<Mutex<HashMap<String, Option<TextureHandle>>>> = Arc::new(Mutex::new(HashMap::new()));
+        let map: Arc<Mutex<HashMap<String, Option<CachedImage>>>> = Arc::new(Mutex::new(HashMap::new()));
         let map0 = map.clone();
+        let settings0 = settings.clone();
 
         tokio::task::spawn(async move {
-            let _result = ImageCache::run(rx1, map0, cache, ctx, settings).await;
+            let _result = ImageCache::run(rx1, map0, cache, ctx, settings0).await;
         });
 
-        Self { db: map, tx: tx0 }
+        Self { db: map, tx: tx0, settings, namespace: Mutex::new(String::new()), namespaces: Mutex::new(HashMap::new()) }
     }
 
-    async fn run(rx1: Receiver<LoaderRequest>, map: Arc<Mutex<HashMap<String, Option<TextureHandle>>>>, cache: PathBuf, ctx: egui::Context, settings: Arc<Mutex<Settings>>) -> anyhow::Result<()> {
+    async fn run(rx1: Receiver<LoaderRequest>, map: Arc<Mutex<HashMap<String, Option<CachedImage>>>>, cache: PathBuf, ctx: egui::Context, settings: Arc<Mutex<Settings>>) -> anyhow::Result<()> {
         println!("image cache dir: {}", cache.to_str().unwrap());
         let client = reqwest::Client::builder().user_agent("some fuckass rust app that looks like windows 8").build();
         if let Err(err) = client {
             return Err(anyhow::Error::msg(format!("{:?}", err)));
         }
         let client = client.unwrap();
+        let root = cache.clone();
+        let mut cache = cache;
 
         'outer: loop {
             while let Ok(req) = rx1.try_recv() {
                 let req = match req {
                     LoaderRequest::Shutdown => break 'outer Ok(()),
                     LoaderRequest::GetImg(path) => path,
+                    LoaderRequest::Namespace(namespace) => {
+                        cache = root.join(namespace);
+                        if let Err(err) = fs::create_dir_all(&cache) {
+                            println!("Could not create image cache folder {:?}: {}", cache, err);
+                            cache = root.clone();
+                        }
+                        continue;
+                    }
                 };
                 let req_og = req.clone();
                 let req = match settings.lock().unwrap().preferred_image_format {
@@ -159,15 +231,65 @@ impl ImageCache {
         }
     }
 
-    pub fn load_from_fs(ctx: egui::Context, path: &PathBuf) -> anyhow::Result<TextureHandle> {
+    /// Uploads every frame of an animation, or `None` if it turns out to only have one, or too many to keep around
+    fn load_animation(ctx: &egui::Context, identifier: &str, frames: Frames) -> anyhow::Result<Option<AnimatedImage>> {
+        let mut decoded = Vec::new();
+        let mut pixels = 0;
+        for frame in frames {
+            let frame = frame?;
+            pixels += frame.buffer().width() as u64 * frame.buffer().height() as u64;
+            if decoded.len() >= MAX_ANIMATION_FRAMES || pixels > MAX_ANIMATION_PIXELS {
+                println!("{} is too big to animate, showing the first frame", identifier);
+                return Ok(None);
+            }
+            decoded.push(frame);
+        }
+        let frames = decoded;
+        if frames.len() < 2 {
+            return Ok(None);
+        }
+
+        let mut total = Duration::ZERO;
+        let frames: Vec<(TextureHandle, Duration)> = frames
+            .into_iter()
+            .enumerate()
+            .map(|(idx, frame)| {
+                let delay = Duration::from(frame.delay());
+                let delay = if delay < MIN_FRAME_DELAY { DEFAULT_FRAME_DELAY } else { delay };
+                total += delay;
+
+                let buffer = frame.into_buffer();
+                let ci = ColorImage::from_rgba_unmultiplied([buffer.width() as usize, buffer.height() as usize], buffer.as_raw());
+                (ctx.load_texture(format!("{}#{}", identifier, idx), ci, TextureOptions::LINEAR), delay)
+            })
+            .collect();
+
+        Ok(Some(AnimatedImage { frames: Arc::new(frames), total }))
+    }
+
+    fn load_from_fs(ctx: egui::Context, path: &PathBuf) -> anyhow::Result<CachedImage> {
         let identifier = path.file_name().unwrap().to_str().unwrap();
 
         let img = ImageReader::open(path);
         if img.is_err() {
             return Err(anyhow::Error::msg(format!("Failed to open \"{}\"!", path.to_string_lossy())));
         }
+        let img = img?.with_guessed_format()?;
+
+        // animations go through their own decoders, anything that turns out to be still falls through to the normal path
+        let animation = match img.format() {
+            Some(ImageFormat::Gif) => Self::load_animation(&ctx, identifier, GifDecoder::new(BufReader::new(File::open(path)?))?.into_frames())?,
+            Some(ImageFormat::WebP) => {
+                let decoder = WebPDecoder::new(BufReader::new(File::open(path)?))?;
+                if decoder.has_animation() { Self::load_animation(&ctx, identifier, decoder.into_frames())? } else { None }
+            }
+            _ => None,
+        };
+        if let Some(animation) = animation {
+            return Ok(CachedImage::Animated(animation));
+        }
 
-        let img_decoded = img?.decode();
+        let img_decoded = img.decode();
         if let Err(err) = img_decoded {
             return Err(anyhow::Error::msg(format!("Failed to decode image: {:?}", err)));
         }
@@ -179,22 +301,40 @@ impl ImageCache {
                 let img_a = DynamicImage::ImageRgba8(img_decoded.into_rgba8());
                 let ci = ColorImage::from_rgba_unmultiplied([img_a.width() as usize, img_a.height() as usize], img_a.as_bytes());
 
-                return Ok(ctx.load_texture(identifier, ci, TextureOptions::LINEAR));
+                return Ok(CachedImage::Still(ctx.load_texture(identifier, ci, TextureOptions::LINEAR)));
             }
             3 => {
                 let ci = ColorImage::from_rgb([img_decoded.width() as usize, img_decoded.height() as usize], img_decoded.as_bytes());
 
-                return Ok(ctx.load_texture(identifier, ci, TextureOptions::LINEAR));
+                return Ok(CachedImage::Still(ctx.load_texture(identifier, ci, TextureOptions::LINEAR)));
             }
             4 => {
                 let ci = ColorImage::from_rgba_unmultiplied([img_decoded.width() as usize, img_decoded.height() as usize], img_decoded.as_bytes());
 
-                return Ok(ctx.load_texture(identifier, ci, TextureOptions::LINEAR));
+                return Ok(CachedImage::Still(ctx.load_texture(identifier, ci, TextureOptions::LINEAR)));
             }
             _ => return Err(anyhow::Error::msg("unsupported amount of channels")),
         }
     }
 
+    /// Gives each account its own images, in memory and on disk. Whatever was still loading for the old one gets asked for again when it's back.
+    pub fn set_namespace(&self, did: &str) {
+        let mut namespace = self.namespace.lock().unwrap();
+        if *namespace == did {
+            return;
+        }
+        let mut db = self.db.lock().unwrap();
+        let mut namespaces = self.namespaces.lock().unwrap();
+        let mut previous = std::mem::replace(&mut *db, namespaces.remove(did).unwrap_or_default());
+        previous.retain(|_, img| img.is_some());
+        namespaces.insert(std::mem::replace(&mut *namespace, did.to_owned()), previous);
+
+        // colons don't go in windows paths
+        if let Err(err) = self.tx.send(LoaderRequest::Namespace(did.replace(':', "_"))) {
+            println!("Send error! {:?}", err);
+        }
+    }
+
     /// tells the thread to \*lightning\*
     pub fn shutdown(&mut self) {
         self.tx.send(LoaderRequest::Shutdown).unwrap();
@@ -204,10 +344,15 @@ impl ImageCache {
     pub fn get_image(&self, id: &String) -> LoadableImage {
         let mut db = self.db.lock().unwrap();
         if let Some(img) = db.get(id) {
-            if let Some(id) = img {
-                return LoadableImage::Loaded(id.id(), id.size_vec2());
-            } else {
-                return LoadableImage::Loading;
+            match img {
+                Some(CachedImage::Still(id)) => return LoadableImage::Loaded(id.id(), id.size_vec2()),
+                Some(CachedImage::Animated(anim)) => {
+                    if self.settings.lock().unwrap().autoplay_animations {
+                        return LoadableImage::Animated(anim.clone());
+                    }
+                    return LoadableImage::Loaded(anim.first_frame(), anim.size());
+                }
+                None => return LoadableImage::Loading,
             }
         } else {
             // i don't want another enum so i just use existing as unloaded, and the option as loading/loaded
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+/// What can be picked for posting and filtering, by BCP-47 code
+pub const LANGUAGES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("ca", "Catalan"),
+    ("cs", "Czech"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fa", "Persian"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("hu", "Hungarian"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("no", "Norwegian"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ro", "Romanian"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
+
+/// The lexicon's limit on `langs`
+pub const MAX_POST_LANGUAGES: usize = 3;
+
+/// Short words that show up in nearly every sentence, enough to tell Latin-script languages apart
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "is", "you", "that", "it", "of", "to", "this", "for", "with", "was", "are", "have", "not", "but", "what", "my", "just", "i'm"]),
+    ("es", &["el", "la", "que", "y", "los", "es", "por", "una", "las", "con", "para", "pero", "muy", "como", "del", "se", "lo", "mi", "esto", "qué"]),
+    ("fr", &["le", "les", "et", "est", "une", "je", "pas", "des", "pour", "avec", "sur", "dans", "ce", "qui", "mais", "c'est", "du", "au", "vous", "j'ai"]),
+    ("de", &["der", "die", "das", "und", "ist", "ich", "nicht", "ein", "eine", "zu", "mit", "auf", "den", "sie", "auch", "wie", "aber", "für", "mir", "es"]),
+    ("pt", &["o", "que", "e", "não", "do", "da", "em", "um", "uma", "para", "com", "os", "as", "é", "mais", "mas", "eu", "você", "isso", "muito"]),
+    ("it", &["il", "di", "che", "non", "è", "un", "per", "sono", "ma", "gli", "anche", "questo", "come", "della", "io", "ho", "cosa", "perché", "mi", "nel"]),
+    ("nl", &["de", "het", "een", "en", "van", "ik", "niet", "dat", "op", "te", "zijn", "met", "voor", "maar", "je", "ook", "wat", "er", "dit", "heb"]),
+];
+
+pub fn language_name(code: &str) -> &str {
+    LANGUAGES.iter().find(|(lang, _)| *lang == code).map(|(_, name)| *name).unwrap_or(code)
+}
+
+/// `en-US` and `en_GB` are both just `en` as far as picking goes
+fn primary_tag(code: &str) -> String {
+    code.split(['-', '_']).next().unwrap_or(code).to_lowercase()
+}
+
+/// Whether a post tagged with `langs` is in one of the `wanted` languages. Posts that don't say always are.
+pub fn matches_languages(langs: &Option<Vec<String>>, wanted: &[String]) -> bool {
+    match langs {
+        Some(langs) if !langs.is_empty() => langs.iter().any(|lang| wanted.iter().any(|wanted| primary_tag(lang) == primary_tag(wanted))),
+        _ => true,
+    }
+}
+
+/// Languages with a script mostly to themselves
+fn script_language(chr: char) -> Option<&'static str> {
+    match chr as u32 {
+        0x3040..=0x30FF => Some("ja"),
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Some("ko"),
+        0x4E00..=0x9FFF => Some("zh"),
+        0x0400..=0x04FF => Some("ru"),
+        0x0370..=0x03FF => Some("el"),
+        0x0590..=0x05FF => Some("he"),
+        0x0600..=0x06FF => Some("ar"),
+        0x0900..=0x097F => Some("hi"),
+        0x0E00..=0x0E7F => Some("th"),
+        _ => None,
+    }
+}
+
+/// Best guess at what a post is written in, going by its script and then by common words.
+/// `None` when there's not enough to go on, mentions, tags and links don't count.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .filter(|word| !word.starts_with(['@', '#']) && !word.contains("://"))
+        .map(|word| word.trim_matches(|chr: char| !chr.is_alphanumeric() && chr != '\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let mut scripts: HashMap<&'static str, usize> = HashMap::new();
+    let mut latin = 0;
+    for chr in words.iter().flat_map(|word| word.chars()) {
+        if let Some(lang) = script_language(chr) {
+            *scripts.entry(lang).or_default() += 1;
+        } else if chr.is_alphabetic() {
+            latin += 1;
+        }
+    }
+
+    if let Some((&script, &count)) = scripts.iter().max_by_key(|(_, count)| **count)
+        && count > latin
+    {
+        return Some(match script {
+            // kanji next to any kana is japanese
+            "zh" if scripts.contains_key("ja") => "ja",
+            "ru" if text.contains(['і', 'ї', 'є', 'ґ']) => "uk",
+            "ar" if text.contains(['پ', 'چ', 'ژ', 'گ']) => "fa",
+            script => script,
+        });
+    }
+
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS.iter().map(|(lang, stopwords)| (*lang, words.iter().filter(|word| stopwords.contains(&word.as_str())).count())).collect();
+    scores.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    match scores.as_slice() {
+        // a tie means it could be either
+        [(lang, best), (_, second), ..] if *best > 0 && best > second => Some(lang),
+        _ => None,
+    }
+}
@@ -1,10 +1,14 @@
+pub mod accounts;
 pub mod backend;
 pub mod bridge;
 pub mod frontend;
 pub mod image;
+pub mod lang;
 pub mod widgets;
 pub mod defs;
+pub mod drafts;
 pub mod settings;
+pub mod video;
 
 use crate::frontend::main::ClientFrontend;
 
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Serialize, Deserialize};
 
 #[derive(Serialize, Deserialize)]
@@ -15,9 +17,22 @@ pub enum Theme {
 }
 
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
 	pub preferred_image_format: PreferredImageFormat,
 	pub theme: Theme,
+	/// Don't let posts with undescribed images go out
+	pub require_alt_text: bool,
+	/// Play GIFs and animated WebPs, otherwise they sit on their first frame
+	pub autoplay_animations: bool,
+	/// Hashtags we've posted with, newest first, for completion in the composer
+	pub recent_tags: Vec<String>,
+	/// What we post in, by account DID
+	pub post_languages: HashMap<String, Vec<String>>,
+	/// Tag each post with whatever language its text looks like, falling back to the picked ones
+	pub detect_post_language: bool,
+	/// Only show timeline and feed posts in these languages, everything if it's empty
+	pub content_languages: Vec<String>,
 }
 
 impl Default for Settings {
@@ -25,6 +40,12 @@ impl Default for Settings {
         Settings {
         	preferred_image_format: PreferredImageFormat::Original,
         	theme: Theme::System,
+        	require_alt_text: false,
+        	autoplay_animations: true,
+        	recent_tags: Vec::new(),
+        	post_languages: HashMap::new(),
+        	detect_post_language: false,
+        	content_languages: Vec::new(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,161 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use cpal::{
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+    FromSample, SizedSample,
+};
+use symphonia::{
+    core::{
+        audio::{Channels, SampleBuffer},
+        codecs::{CodecParameters, Decoder, DecoderOptions, CODEC_TYPE_AAC},
+        formats::Packet,
+    },
+    default::codecs::AacDecoder,
+};
+
+use super::ts::AdtsFrame;
+
+/// Turns ADTS frames into interleaved stereo samples.
+pub struct AudioDecoder {
+    decoder: AacDecoder,
+    sample_rate: u32,
+    channels: u8,
+}
+
+impl AudioDecoder {
+    pub fn new(frame: &AdtsFrame) -> Option<Self> {
+        let channels = match frame.channels {
+            1 => Channels::FRONT_LEFT,
+            _ => Channels::FRONT_LEFT | Channels::FRONT_RIGHT,
+        };
+        let mut params = CodecParameters::new();
+        params.for_codec(CODEC_TYPE_AAC).with_sample_rate(frame.sample_rate).with_channels(channels);
+
+        match AacDecoder::try_new(&params, &DecoderOptions::default()) {
+            Ok(decoder) => Some(Self { decoder, sample_rate: frame.sample_rate, channels: frame.channels }),
+            Err(err) => {
+                println!("Unsupported audio (profile {}, {} channels): {:?}", frame.profile, frame.channels, err);
+                None
+            }
+        }
+    }
+
+    /// Whether this decoder can keep going with `frame`, or the stream changed under us
+    pub fn matches(&self, frame: &AdtsFrame) -> bool {
+        self.sample_rate == frame.sample_rate && self.channels == frame.channels
+    }
+
+    pub fn decode(&mut self, data: &[u8]) -> Option<Vec<f32>> {
+        let packet = Packet::new_from_slice(0, 0, 1024, data);
+        let decoded = self.decoder.decode(&packet).ok()?;
+        let spec = *decoded.spec();
+        let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buf.copy_interleaved_ref(decoded);
+
+        let channels = spec.channels.count();
+        Some(match channels {
+            1 => buf.samples().iter().flat_map(|sample| [*sample, *sample]).collect(),
+            _ => buf.samples().chunks_exact(channels).flat_map(|frame| [frame[0], frame[1]]).collect(),
+        })
+    }
+}
+
+struct OutputState {
+    samples: VecDeque<f32>,
+    muted: bool,
+    paused: bool,
+}
+
+/// The default output device, fed from the player's clock.
+pub struct AudioOutput {
+    _stream: cpal::Stream,
+    state: Arc<Mutex<OutputState>>,
+    sample_rate: u32,
+    channels: usize,
+}
+
+fn build_stream<T: SizedSample + FromSample<f32>>(device: &cpal::Device, config: &cpal::StreamConfig, state: Arc<Mutex<OutputState>>) -> Option<cpal::Stream> {
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                let mut state = state.lock().unwrap();
+                let volume = if state.muted { 0.0 } else { 1.0 };
+                for sample in data.iter_mut() {
+                    let value = if state.paused { 0.0 } else { state.samples.pop_front().unwrap_or(0.0) };
+                    *sample = T::from_sample(value * volume);
+                }
+            },
+            |err| println!("Audio output error: {:?}", err),
+            None,
+        )
+        .ok()
+}
+
+impl AudioOutput {
+    pub fn new() -> Option<Self> {
+        let device = cpal::default_host().default_output_device()?;
+        let config = device.default_output_config().ok()?;
+        let state = Arc::new(Mutex::new(OutputState { samples: VecDeque::new(), muted: false, paused: false }));
+
+        let stream_config: cpal::StreamConfig = config.clone().into();
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &stream_config, state.clone()),
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &stream_config, state.clone()),
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &stream_config, state.clone()),
+            format => {
+                println!("Unsupported audio output format {:?}", format);
+                None
+            }
+        }?;
+        stream.play().ok()?;
+
+        Some(Self { _stream: stream, state, sample_rate: stream_config.sample_rate.0, channels: stream_config.channels as usize })
+    }
+
+    /// Queues interleaved stereo samples, resampled and remixed for the device
+    pub fn push(&self, sample_rate: u32, samples: &[f32]) {
+        let frames = samples.len() / 2;
+        if frames == 0 {
+            return;
+        }
+        let ratio = sample_rate as f64 / self.sample_rate as f64;
+        let out_frames = (frames as f64 / ratio) as usize;
+
+        let mut state = self.state.lock().unwrap();
+        for idx in 0..out_frames {
+            let pos = idx as f64 * ratio;
+            let first = (pos as usize).min(frames - 1);
+            let second = (first + 1).min(frames - 1);
+            let fract = (pos - first as f64) as f32;
+            let left = samples[first * 2] * (1.0 - fract) + samples[second * 2] * fract;
+            let right = samples[first * 2 + 1] * (1.0 - fract) + samples[second * 2 + 1] * fract;
+
+            match self.channels {
+                1 => state.samples.push_back((left + right) * 0.5),
+                channels => {
+                    state.samples.push_back(left);
+                    state.samples.push_back(right);
+                    for _ in 2..channels {
+                        state.samples.push_back(0.0);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().samples.clear();
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.state.lock().unwrap().paused = paused;
+    }
+
+    pub fn set_muted(&self, muted: bool) {
+        self.state.lock().unwrap().muted = muted;
+    }
+}
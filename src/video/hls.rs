@@ -0,0 +1,148 @@
+use reqwest::Url;
+
+/// One `#EXT-X-STREAM-INF` entry from a master playlist
+#[derive(Debug, Clone)]
+pub struct Variant {
+    pub bandwidth: u64,
+    pub resolution: Option<(u32, u32)>,
+    pub uri: Url,
+}
+
+/// An `#EXT-X-MEDIA:TYPE=SUBTITLES` rendition
+#[derive(Debug, Clone)]
+pub struct SubtitleTrack {
+    pub name: String,
+    pub language: Option<String>,
+    pub default: bool,
+    pub uri: Url,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MasterPlaylist {
+    pub variants: Vec<Variant>,
+    pub subtitles: Vec<SubtitleTrack>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Segment {
+    /// Where this segment sits on the timeline, in seconds
+    pub start: f64,
+    pub duration: f64,
+    pub uri: Url,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MediaPlaylist {
+    pub segments: Vec<Segment>,
+}
+
+impl MediaPlaylist {
+    pub fn duration(&self) -> f64 {
+        self.segments.last().map(|seg| seg.start + seg.duration).unwrap_or(0.0)
+    }
+
+    /// Index of the segment that contains `time`, clamped to the playlist
+    pub fn segment_at(&self, time: f64) -> usize {
+        self.segments.iter().rposition(|seg| seg.start <= time).unwrap_or(0)
+    }
+}
+
+/// Splits an HLS attribute list (`KEY=VALUE,KEY="VALUE, WITH COMMAS"`) into pairs
+fn attributes(list: &str) -> Vec<(&str, &str)> {
+    let mut rtn = Vec::new();
+    let mut rest = list;
+
+    while !rest.is_empty() {
+        let Some(eq) = rest.find('=') else { break };
+        let key = rest[..eq].trim();
+        rest = &rest[eq + 1..];
+
+        let value = if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            rest = quoted.get(end + 1..).unwrap_or("");
+            &quoted[..end]
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            let value = &rest[..end];
+            rest = &rest[end..];
+            value
+        };
+        rtn.push((key, value));
+        rest = rest.strip_prefix(',').unwrap_or(rest);
+    }
+
+    rtn
+}
+
+impl MasterPlaylist {
+    pub fn parse(body: &str, base: &Url) -> Self {
+        let mut rtn = Self::default();
+        let mut pending: Option<(u64, Option<(u32, u32)>)> = None;
+
+        for line in body.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+                let mut bandwidth = 0;
+                let mut resolution = None;
+                for (key, value) in attributes(attrs) {
+                    match key {
+                        "BANDWIDTH" => bandwidth = value.parse().unwrap_or(0),
+                        "RESOLUTION" => {
+                            resolution = value.split_once('x').and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+                        }
+                        _ => {}
+                    }
+                }
+                pending = Some((bandwidth, resolution));
+            } else if let Some(attrs) = line.strip_prefix("#EXT-X-MEDIA:") {
+                let attrs = attributes(attrs);
+                let get = |name: &str| attrs.iter().find(|(key, _)| *key == name).map(|(_, value)| *value);
+
+                if get("TYPE") != Some("SUBTITLES") {
+                    continue;
+                }
+                let Some(uri) = get("URI").and_then(|uri| base.join(uri).ok()) else { continue };
+                rtn.subtitles.push(SubtitleTrack {
+                    name: get("NAME").unwrap_or("Captions").to_owned(),
+                    language: get("LANGUAGE").map(str::to_owned),
+                    default: get("DEFAULT") == Some("YES"),
+                    uri,
+                });
+            } else if !line.starts_with('#')
+                && let Some((bandwidth, resolution)) = pending.take()
+                && let Ok(uri) = base.join(line)
+            {
+                rtn.variants.push(Variant { bandwidth, resolution, uri });
+            }
+        }
+
+        rtn
+    }
+
+    /// The best looking variant that doesn't go over `max_height`, or the smallest one if they all do
+    pub fn pick_variant(&self, max_height: u32) -> Option<&Variant> {
+        let fits = |variant: &&Variant| variant.resolution.is_none_or(|(_, height)| height <= max_height);
+        self.variants.iter().filter(fits).max_by_key(|variant| variant.bandwidth).or_else(|| self.variants.iter().min_by_key(|variant| variant.bandwidth))
+    }
+}
+
+impl MediaPlaylist {
+    pub fn parse(body: &str, base: &Url) -> Self {
+        let mut rtn = Self::default();
+        let mut start = 0.0;
+        let mut duration: Option<f64> = None;
+
+        for line in body.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(info) = line.strip_prefix("#EXTINF:") {
+                let length = info.split(',').next().unwrap_or("");
+                duration = length.trim().parse().ok();
+            } else if !line.starts_with('#') {
+                let Some(length) = duration.take() else { continue };
+                let Ok(uri) = base.join(line) else { continue };
+                rtn.segments.push(Segment { start, duration: length, uri });
+                start += length;
+            }
+        }
+
+        rtn
+    }
+}
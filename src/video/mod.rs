@@ -0,0 +1,532 @@
+pub mod hls;
+pub mod ts;
+pub mod vtt;
+
+#[cfg(feature = "audio")]
+mod audio;
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    sync::{
+        mpsc::{Receiver, Sender, TryRecvError},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use egui::{ColorImage, TextureHandle, TextureId, TextureOptions, Vec2};
+use hls::{MasterPlaylist, MediaPlaylist};
+use openh264::{
+    decoder::{DecodedYUV, Decoder, DecoderConfig, Flush},
+    formats::YUVSource,
+    OpenH264API,
+};
+use reqwest::Url;
+use tokio::{runtime::Handle, task::JoinHandle};
+use ts::StreamKind;
+use vtt::Cue;
+
+/// How many decoded frames the worker keeps ready, a 720p frame is a little under 4MB
+const MAX_QUEUED_FRAMES: usize = 12;
+/// Segments to download ahead of the decoder
+const SEGMENT_PREFETCH: usize = 3;
+/// Anything bigger is wasted on a window that's mostly letterboxing anyway
+const MAX_VARIANT_HEIGHT: u32 = 720;
+#[cfg(feature = "audio")]
+/// How far ahead of the video clock audio gets handed to the output
+const AUDIO_LEAD: f64 = 0.1;
+
+#[derive(Clone)]
+pub enum PlayerStatus {
+    /// Fetching playlists
+    Loading,
+    Ready,
+    Failed(String),
+}
+
+struct Frame {
+    time: f64,
+    image: ColorImage,
+}
+
+#[cfg(feature = "audio")]
+struct AudioChunk {
+    time: f64,
+    sample_rate: u32,
+    /// Interleaved stereo
+    samples: Vec<f32>,
+}
+
+/// Everything the worker thread hands back to the player
+struct PlayerState {
+    status: PlayerStatus,
+    duration: f64,
+    captions: Vec<Cue>,
+    frames: VecDeque<Frame>,
+    #[cfg(feature = "audio")]
+    audio: VecDeque<AudioChunk>,
+    /// The worker has decoded everything up to the end of the video
+    finished: bool,
+    /// Bumped on every seek, so frames decoded for an old position get thrown out
+    generation: u64,
+}
+
+enum PlayerRequest {
+    Shutdown,
+    Seek(f64, u64),
+}
+
+/// A demuxed PES packet placed on the playlist's timeline
+struct Unit {
+    kind: StreamKind,
+    time: f64,
+    data: Vec<u8>,
+}
+
+type SegmentResult = (usize, Result<Vec<u8>, String>);
+
+/// Plays an HLS stream, decoding video in software on its own thread.
+pub struct VideoPlayer {
+    state: Arc<Mutex<PlayerState>>,
+    tx: Sender<PlayerRequest>,
+    texture: Option<TextureHandle>,
+    position: f64,
+    playing: bool,
+    buffering: bool,
+    muted: bool,
+    /// Show the next frame even if it isn't due yet, for seeking while paused
+    needs_frame: bool,
+    last_tick: Option<Instant>,
+    #[cfg(feature = "audio")]
+    audio: Option<audio::AudioOutput>,
+}
+
+impl VideoPlayer {
+    pub fn new(ctx: egui::Context, playlist: &str) -> Self {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let state = Arc::new(Mutex::new(PlayerState {
+            status: PlayerStatus::Loading,
+            duration: 0.0,
+            captions: Vec::new(),
+            frames: VecDeque::new(),
+            #[cfg(feature = "audio")]
+            audio: VecDeque::new(),
+            finished: false,
+            generation: 0,
+        }));
+
+        let state0 = state.clone();
+        let playlist = playlist.to_owned();
+        let handle = Handle::current();
+        std::thread::spawn(move || {
+            if let Err(err) = Self::run(rx, state0.clone(), ctx.clone(), handle, playlist) {
+                println!("Video playback failed: {:?}", err);
+                state0.lock().unwrap().status = PlayerStatus::Failed(err.to_string());
+                ctx.request_repaint();
+            }
+        });
+
+        Self {
+            state,
+            tx,
+            texture: None,
+            position: 0.0,
+            playing: true,
+            buffering: true,
+            muted: false,
+            needs_frame: true,
+            last_tick: None,
+            #[cfg(feature = "audio")]
+            audio: None,
+        }
+    }
+
+    async fn load(client: &reqwest::Client, playlist: &str) -> anyhow::Result<(MediaPlaylist, Vec<Cue>)> {
+        let master_url = Url::parse(playlist)?;
+        let body = client.get(master_url.clone()).send().await?.error_for_status()?.text().await?;
+
+        if !body.contains("#EXT-X-STREAM-INF") {
+            // already a media playlist, nothing to pick from
+            return Ok((MediaPlaylist::parse(&body, &master_url), Vec::new()));
+        }
+
+        let master = MasterPlaylist::parse(&body, &master_url);
+        let Some(variant) = master.pick_variant(MAX_VARIANT_HEIGHT) else {
+            return Err(anyhow::Error::msg("No playable variants in playlist"));
+        };
+        let body = client.get(variant.uri.clone()).send().await?.error_for_status()?.text().await?;
+        let media = MediaPlaylist::parse(&body, &variant.uri);
+
+        let track = master.subtitles.iter().find(|track| track.default).or(master.subtitles.first());
+        let captions = match track {
+            Some(track) => Self::load_captions(client, &track.uri).await.unwrap_or_else(|err| {
+                println!("Failed to load captions ({}): {:?}", track.name, err);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+
+        Ok((media, captions))
+    }
+
+    /// Subtitle renditions are usually a playlist of WebVTT segments, but sometimes just the file
+    async fn load_captions(client: &reqwest::Client, uri: &Url) -> anyhow::Result<Vec<Cue>> {
+        let body = client.get(uri.clone()).send().await?.error_for_status()?.text().await?;
+        if !body.starts_with("#EXTM3U") {
+            return Ok(vtt::parse(&body));
+        }
+
+        let mut rtn = Vec::new();
+        for segment in MediaPlaylist::parse(&body, uri).segments {
+            let body = client.get(segment.uri).send().await?.error_for_status()?.text().await?;
+            rtn.extend(vtt::parse(&body));
+        }
+        Ok(rtn)
+    }
+
+    /// Downloads segments in order starting at `from`, staying a few ahead of whoever's reading
+    fn fetch_from(handle: &Handle, client: &reqwest::Client, media: &MediaPlaylist, from: usize) -> (JoinHandle<()>, tokio::sync::mpsc::Receiver<SegmentResult>) {
+        let (tx, rx) = tokio::sync::mpsc::channel(SEGMENT_PREFETCH);
+        let client = client.clone();
+        let uris: Vec<(usize, Url)> = media.segments.iter().enumerate().skip(from).map(|(idx, seg)| (idx, seg.uri.clone())).collect();
+
+        let task = handle.spawn(async move {
+            for (idx, uri) in uris {
+                let res = match client.get(uri).send().await.and_then(|res| res.error_for_status()) {
+                    Ok(res) => res.bytes().await.map(|bytes| bytes.to_vec()).map_err(|err| err.to_string()),
+                    Err(err) => Err(err.to_string()),
+                };
+                let failed = res.is_err();
+                if tx.send((idx, res)).await.is_err() || failed {
+                    return;
+                }
+            }
+        });
+
+        (task, rx)
+    }
+
+    /// Demuxes a segment and lines its timestamps up with where the playlist says it starts
+    fn demux_segment(segment: &hls::Segment, data: &[u8]) -> Vec<Unit> {
+        let packets = ts::demux(data);
+        let base = packets.iter().filter(|pes| pes.kind == StreamKind::Video).filter_map(|pes| pes.pts).min().or_else(|| packets.iter().filter_map(|pes| pes.pts).min()).unwrap_or(0);
+
+        packets
+            .into_iter()
+            .map(|pes| Unit {
+                kind: pes.kind,
+                time: segment.start + pes.pts.map(|pts| (pts as f64 - base as f64) / 90000.0).unwrap_or(0.0),
+                data: pes.data,
+            })
+            .collect()
+    }
+
+    fn new_decoder() -> anyhow::Result<Decoder> {
+        // flushing after every packet breaks B-frame reordering
+        Ok(Decoder::with_api_config(OpenH264API::from_source(), DecoderConfig::new().flush_after_decode(Flush::NoFlush))?)
+    }
+
+    fn to_image(yuv: &DecodedYUV) -> ColorImage {
+        let (width, height) = yuv.dimensions();
+        let mut rgba = vec![0; width * height * 4];
+        yuv.write_rgba8(&mut rgba);
+        ColorImage::from_rgba_unmultiplied([width, height], &rgba)
+    }
+
+    fn run(rx: Receiver<PlayerRequest>, state: Arc<Mutex<PlayerState>>, ctx: egui::Context, handle: Handle, playlist: String) -> anyhow::Result<()> {
+        let client = reqwest::Client::builder().user_agent("some fuckass rust app that looks like windows 8").build()?;
+        let (media, captions) = handle.block_on(Self::load(&client, &playlist))?;
+        if media.segments.is_empty() {
+            return Err(anyhow::Error::msg("Playlist has no segments"));
+        }
+
+        {
+            let mut state = state.lock().unwrap();
+            state.duration = media.duration();
+            state.captions = captions;
+            state.status = PlayerStatus::Ready;
+        }
+        ctx.request_repaint();
+
+        let mut generation = 0;
+        let mut decoder = Self::new_decoder()?;
+        let (mut fetch, mut segments) = Self::fetch_from(&handle, &client, &media, 0);
+        let mut pending: VecDeque<Unit> = VecDeque::new();
+        // decoded frames come out in display order, so they get the earliest timestamp we've fed in
+        let mut timestamps: BinaryHeap<Reverse<u64>> = BinaryHeap::new();
+        let mut skip_until = 0.0;
+        let mut done = false;
+        #[cfg(feature = "audio")]
+        let mut audio_decoder: Option<audio::AudioDecoder> = None;
+
+        let push_frame = |time: f64, image: ColorImage, generation: u64| {
+            let mut state = state.lock().unwrap();
+            if state.generation == generation {
+                state.frames.push_back(Frame { time, image });
+            }
+        };
+
+        loop {
+            // once everything's decoded there's nothing to do until someone seeks
+            let req = if done { rx.recv().map_err(|_| TryRecvError::Disconnected) } else { rx.try_recv() };
+            match req {
+                Ok(PlayerRequest::Shutdown) | Err(TryRecvError::Disconnected) => {
+                    fetch.abort();
+                    return Ok(());
+                }
+                Ok(PlayerRequest::Seek(time, new_generation)) => {
+                    fetch.abort();
+                    generation = new_generation;
+                    (fetch, segments) = Self::fetch_from(&handle, &client, &media, media.segment_at(time));
+                    decoder = Self::new_decoder()?;
+                    pending.clear();
+                    timestamps.clear();
+                    skip_until = time;
+                    done = false;
+                    #[cfg(feature = "audio")]
+                    {
+                        audio_decoder = None;
+                    }
+                    continue;
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+
+            if pending.is_empty() {
+                match segments.try_recv() {
+                    Ok((idx, Ok(data))) => {
+                        for unit in Self::demux_segment(&media.segments[idx], &data) {
+                            if unit.kind == StreamKind::Video {
+                                timestamps.push(Reverse((unit.time * 1_000_000.0) as u64));
+                            }
+                            pending.push_back(unit);
+                        }
+                    }
+                    Ok((idx, Err(err))) => {
+                        println!("Failed to download video segment {}: {}", idx, err);
+                        state.lock().unwrap().status = PlayerStatus::Failed(err);
+                        ctx.request_repaint();
+                        done = true;
+                    }
+                    Err(tokio::sync::mpsc::error::TryRecvError::Empty) => std::thread::sleep(Duration::from_millis(5)),
+                    Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => {
+                        let remaining: Vec<ColorImage> = decoder.flush_remaining().map(|frames| frames.iter().map(Self::to_image).collect()).unwrap_or_default();
+                        for image in remaining {
+                            let Some(Reverse(time)) = timestamps.pop() else { break };
+                            let time = time as f64 / 1_000_000.0;
+                            if time >= skip_until {
+                                push_frame(time, image, generation);
+                            }
+                        }
+                        let mut state = state.lock().unwrap();
+                        if state.generation == generation {
+                            state.finished = true;
+                        }
+                        ctx.request_repaint();
+                        done = true;
+                    }
+                }
+                continue;
+            }
+
+            if state.lock().unwrap().frames.len() >= MAX_QUEUED_FRAMES {
+                std::thread::sleep(Duration::from_millis(5));
+                continue;
+            }
+
+            let Some(unit) = pending.pop_front() else { continue };
+            match unit.kind {
+                StreamKind::Video => {
+                    let image = match decoder.decode(&unit.data) {
+                        Ok(Some(yuv)) => Self::to_image(&yuv),
+                        Ok(None) => continue,
+                        Err(err) => {
+                            // that packet's not coming out the other end, keep the timestamps lined up
+                            println!("Failed to decode video frame: {:?}", err);
+                            timestamps.pop();
+                            continue;
+                        }
+                    };
+                    let Some(Reverse(time)) = timestamps.pop() else { continue };
+                    let time = time as f64 / 1_000_000.0;
+                    if time >= skip_until {
+                        push_frame(time, image, generation);
+                    }
+                }
+                #[cfg(feature = "audio")]
+                StreamKind::Audio => {
+                    for (idx, frame) in ts::adts_frames(&unit.data).iter().enumerate() {
+                        if audio_decoder.as_ref().is_none_or(|decoder| !decoder.matches(frame)) {
+                            audio_decoder = audio::AudioDecoder::new(frame);
+                        }
+                        let Some(samples) = audio_decoder.as_mut().and_then(|decoder| decoder.decode(frame.data)) else { continue };
+                        let time = unit.time + (idx * 1024) as f64 / frame.sample_rate as f64;
+                        if time < skip_until {
+                            continue;
+                        }
+                        let mut state = state.lock().unwrap();
+                        if state.generation == generation {
+                            state.audio.push_back(AudioChunk { time, sample_rate: frame.sample_rate, samples });
+                        }
+                    }
+                }
+                #[cfg(not(feature = "audio"))]
+                StreamKind::Audio => {}
+            }
+        }
+    }
+
+    /// Advances the clock and swaps in whichever frame is due. Call once per frame before drawing.
+    pub fn tick(&mut self, ctx: &egui::Context) {
+        let now = Instant::now();
+        let delta = self.last_tick.map(|last| (now - last).as_secs_f64()).unwrap_or(0.0);
+        self.last_tick = Some(now);
+
+        let mut state = self.state.lock().unwrap();
+        self.buffering = state.frames.is_empty() && !state.finished;
+        if self.playing && !self.buffering {
+            self.position = (self.position + delta).min(state.duration);
+        }
+
+        let mut latest = None;
+        if self.needs_frame && let Some(frame) = state.frames.pop_front() {
+            self.needs_frame = false;
+            latest = Some(frame);
+        }
+        while let Some(frame) = state.frames.front()
+            && frame.time <= self.position
+        {
+            latest = state.frames.pop_front();
+        }
+
+        if self.playing && state.finished && state.frames.is_empty() {
+            // reached the end
+            self.playing = false;
+            self.position = state.duration;
+        }
+
+        #[cfg(feature = "audio")]
+        {
+            while let Some(chunk) = state.audio.front()
+                && chunk.time <= self.position + AUDIO_LEAD
+            {
+                let chunk = state.audio.pop_front().unwrap();
+                if !self.playing || chunk.time + AUDIO_LEAD < self.position {
+                    continue;
+                }
+                if self.audio.is_none() {
+                    self.audio = audio::AudioOutput::new();
+                    if let Some(output) = &self.audio {
+                        output.set_muted(self.muted);
+                    }
+                }
+                if let Some(output) = &self.audio {
+                    output.push(chunk.sample_rate, &chunk.samples);
+                }
+            }
+            if let Some(output) = &self.audio {
+                output.set_paused(!self.playing || self.buffering);
+            }
+        }
+
+        let loading = matches!(state.status, PlayerStatus::Loading);
+        drop(state);
+
+        if let Some(frame) = latest {
+            match &mut self.texture {
+                Some(texture) => texture.set(frame.image, TextureOptions::LINEAR),
+                None => self.texture = Some(ctx.load_texture("video_player", frame.image, TextureOptions::LINEAR)),
+            }
+        }
+
+        if self.playing || loading || self.needs_frame {
+            ctx.request_repaint();
+        }
+    }
+
+    pub fn status(&self) -> PlayerStatus {
+        self.state.lock().unwrap().status.clone()
+    }
+
+    pub fn frame(&self) -> Option<(TextureId, Vec2)> {
+        self.texture.as_ref().map(|texture| (texture.id(), texture.size_vec2()))
+    }
+
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.state.lock().unwrap().duration
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn is_buffering(&self) -> bool {
+        self.playing && self.buffering
+    }
+
+    pub fn toggle_playback(&mut self) {
+        if !self.playing && self.position >= self.duration() {
+            self.seek(0.0);
+        }
+        self.playing = !self.playing;
+    }
+
+    pub fn seek(&mut self, time: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        state.frames.clear();
+        state.finished = false;
+        #[cfg(feature = "audio")]
+        state.audio.clear();
+
+        self.position = time.clamp(0.0, state.duration);
+        self.needs_frame = true;
+        let _ = self.tx.send(PlayerRequest::Seek(self.position, state.generation));
+
+        #[cfg(feature = "audio")]
+        if let Some(output) = &self.audio {
+            output.clear();
+        }
+    }
+
+    /// Whether there's anywhere for sound to go, builds without the `audio` feature are always silent
+    pub fn has_audio(&self) -> bool {
+        #[cfg(feature = "audio")]
+        return self.audio.is_some();
+        #[cfg(not(feature = "audio"))]
+        return false;
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        #[cfg(feature = "audio")]
+        if let Some(output) = &self.audio {
+            output.set_muted(muted);
+        }
+    }
+
+    /// The caption that should be on screen right now, if there is one
+    pub fn caption(&self) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state.captions.iter().find(|cue| cue.start <= self.position && self.position < cue.end).map(|cue| cue.text.clone())
+    }
+
+    pub fn has_captions(&self) -> bool {
+        !self.state.lock().unwrap().captions.is_empty()
+    }
+}
+
+impl Drop for VideoPlayer {
+    fn drop(&mut self) {
+        let _ = self.tx.send(PlayerRequest::Shutdown);
+    }
+}
@@ -0,0 +1,154 @@
+//! Just enough MPEG-TS to get H.264 and AAC out of an HLS segment.
+
+use std::collections::HashMap;
+
+const PACKET_SIZE: usize = 188;
+const STREAM_TYPE_AAC: u8 = 0x0F;
+const STREAM_TYPE_H264: u8 = 0x1B;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    /// Annex B H.264, one access unit per PES packet
+    Video,
+    /// ADTS framed AAC, possibly several frames per PES packet
+    Audio,
+}
+
+#[derive(Debug)]
+pub struct Pes {
+    pub kind: StreamKind,
+    /// 90kHz presentation timestamp
+    pub pts: Option<u64>,
+    pub data: Vec<u8>,
+}
+
+fn read_pts(b: &[u8]) -> u64 {
+    ((b[0] as u64 >> 1) & 0x07) << 30 | (b[1] as u64) << 22 | (b[2] as u64 >> 1) << 15 | (b[3] as u64) << 7 | b[4] as u64 >> 1
+}
+
+/// Strips the PES header off of a reassembled packet
+fn finish_pes(kind: StreamKind, buf: Vec<u8>) -> Option<Pes> {
+    if buf.len() < 9 || buf[0..3] != [0, 0, 1] {
+        return None;
+    }
+    let header_len = buf[8] as usize;
+    let payload_start = 9 + header_len;
+    if buf.len() < payload_start {
+        return None;
+    }
+    let pts = if buf[7] & 0x80 != 0 && header_len >= 5 { Some(read_pts(&buf[9..14])) } else { None };
+
+    Some(Pes { kind, pts, data: buf[payload_start..].to_vec() })
+}
+
+/// Skips the pointer field and returns the section after it
+fn section(payload: &[u8]) -> Option<&[u8]> {
+    let pointer = *payload.first()? as usize;
+    let section = payload.get(1 + pointer..)?;
+    let length = ((section.get(1)? & 0x0F) as usize) << 8 | *section.get(2)? as usize;
+    // minus the CRC
+    section.get(..(3 + length).checked_sub(4)?)
+}
+
+/// Pulls every video and audio PES packet out of a transport stream, in stream order.
+pub fn demux(data: &[u8]) -> Vec<Pes> {
+    let mut rtn = Vec::new();
+    let mut pmt_pid: Option<u16> = None;
+    let mut streams: HashMap<u16, StreamKind> = HashMap::new();
+    let mut buffers: HashMap<u16, Vec<u8>> = HashMap::new();
+
+    for packet in data.chunks_exact(PACKET_SIZE) {
+        if packet[0] != 0x47 {
+            continue;
+        }
+        let unit_start = packet[1] & 0x40 != 0;
+        let pid = ((packet[1] & 0x1F) as u16) << 8 | packet[2] as u16;
+        let adaptation = (packet[3] >> 4) & 0x03;
+
+        let payload_start = match adaptation {
+            0b01 => 4,
+            0b11 => 5 + packet[4] as usize,
+            _ => continue,
+        };
+        let Some(payload) = packet.get(payload_start..) else { continue };
+
+        if pid == 0 {
+            // PAT, we only care about the first program
+            if let Some(pat) = section(payload)
+                && let Some(entry) = pat.get(8..12)
+            {
+                pmt_pid = Some(((entry[2] & 0x1F) as u16) << 8 | entry[3] as u16);
+            }
+        } else if Some(pid) == pmt_pid {
+            let Some(pmt) = section(payload) else { continue };
+            let Some(info_len) = pmt.get(10..12).map(|b| ((b[0] & 0x0F) as usize) << 8 | b[1] as usize) else { continue };
+            let mut offset = 12 + info_len;
+            while let Some(entry) = pmt.get(offset..offset + 5) {
+                let es_pid = ((entry[1] & 0x1F) as u16) << 8 | entry[2] as u16;
+                match entry[0] {
+                    STREAM_TYPE_H264 => _ = streams.insert(es_pid, StreamKind::Video),
+                    STREAM_TYPE_AAC => _ = streams.insert(es_pid, StreamKind::Audio),
+                    _ => {}
+                }
+                offset += 5 + (((entry[3] & 0x0F) as usize) << 8 | entry[4] as usize);
+            }
+        } else if let Some(kind) = streams.get(&pid) {
+            if unit_start
+                && let Some(buf) = buffers.remove(&pid)
+                && let Some(pes) = finish_pes(*kind, buf)
+            {
+                rtn.push(pes);
+            }
+            if unit_start || buffers.contains_key(&pid) {
+                buffers.entry(pid).or_default().extend_from_slice(payload);
+            }
+        }
+    }
+
+    // whatever's left at the end of the segment is complete too
+    let mut leftovers: Vec<(u16, Vec<u8>)> = buffers.into_iter().collect();
+    leftovers.sort_by_key(|(pid, _)| *pid);
+    for (pid, buf) in leftovers {
+        if let Some(pes) = finish_pes(streams[&pid], buf) {
+            rtn.push(pes);
+        }
+    }
+
+    rtn
+}
+
+/// One AAC frame with the ADTS header taken off
+pub struct AdtsFrame<'a> {
+    pub sample_rate: u32,
+    pub channels: u8,
+    /// 1 = Main, 2 = LC, etc.
+    pub profile: u8,
+    pub data: &'a [u8],
+}
+
+const ADTS_SAMPLE_RATES: [u32; 13] = [96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350];
+
+/// Splits an audio PES payload into its ADTS frames
+pub fn adts_frames(mut data: &[u8]) -> Vec<AdtsFrame<'_>> {
+    let mut rtn = Vec::new();
+
+    while data.len() >= 7 && data[0] == 0xFF && data[1] & 0xF0 == 0xF0 {
+        let protection_absent = data[1] & 0x01 != 0;
+        let header_len = if protection_absent { 7 } else { 9 };
+        let frame_len = ((data[3] & 0x03) as usize) << 11 | (data[4] as usize) << 3 | (data[5] as usize) >> 5;
+        if frame_len < header_len || frame_len > data.len() {
+            break;
+        }
+        let Some(sample_rate) = ADTS_SAMPLE_RATES.get(((data[2] >> 2) & 0x0F) as usize) else { break };
+
+        rtn.push(AdtsFrame {
+            sample_rate: *sample_rate,
+            channels: (data[2] & 0x01) << 2 | data[3] >> 6,
+            profile: (data[2] >> 6) + 1,
+            data: &data[header_len..frame_len],
+        });
+        data = &data[frame_len..];
+    }
+
+    rtn
+}
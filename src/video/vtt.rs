@@ -0,0 +1,55 @@
+#[derive(Debug, Clone)]
+pub struct Cue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// `hh:mm:ss.ttt` or `mm:ss.ttt`, in seconds
+fn parse_timestamp(stamp: &str) -> Option<f64> {
+    let stamp = stamp.trim();
+    let (rest, millis) = stamp.split_once('.')?;
+    let mut seconds = 0.0;
+    for part in rest.split(':') {
+        seconds = seconds * 60.0 + part.parse::<u32>().ok()? as f64;
+    }
+    Some(seconds + millis.parse::<u32>().ok()? as f64 / 1000.0)
+}
+
+/// Drops `<v Someone>`, `<i>`, `<00:00:01.000>` and the like, we only draw plain text
+fn strip_tags(line: &str) -> String {
+    let mut rtn = String::with_capacity(line.len());
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => rtn.push(c),
+            _ => {}
+        }
+    }
+    rtn.replace("&amp;", "&").replace("&lt;", "<").replace("&gt;", ">").replace("&nbsp;", " ")
+}
+
+/// Parses the cues out of a WebVTT file, ignoring cue settings and styling.
+pub fn parse(body: &str) -> Vec<Cue> {
+    let mut rtn = Vec::new();
+    let body = body.replace("\r\n", "\n");
+
+    for block in body.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let Some(timing) = lines.next() else { continue };
+        let Some((start, end)) = timing.split_once("-->") else { continue };
+        // cue settings come after the end time
+        let end = end.split_whitespace().next().unwrap_or("");
+        let (Some(start), Some(end)) = (parse_timestamp(start), parse_timestamp(end)) else { continue };
+
+        let text = lines.map(strip_tags).collect::<Vec<String>>().join("\n");
+        if !text.trim().is_empty() {
+            rtn.push(Cue { start, end, text });
+        }
+    }
+
+    rtn.sort_by(|a, b| a.start.total_cmp(&b.start));
+    rtn
+}